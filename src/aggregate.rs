@@ -0,0 +1,501 @@
+//! Funciones de agregación (`COUNT`, `SUM`, `AVG`, `MIN` y `MAX`).
+//!
+//! Cada agregación se calcula con un `Accumulator` que recibe las filas de a una, a medida que se leen
+//! de la tabla, por lo que no hace falta guardar todas las filas en memoria. Para `GROUP BY`, un
+//! `GroupTable` guarda un juego de acumuladores por cada grupo, buscándolo por su clave en un `HashMap`.
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    error::{ErrorType, SqlError},
+    extras::{find_column, get_float_value, integer_value, Value},
+    operations::compare_values,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+
+/// Representa una función de agregación.
+///
+/// # Variantes
+/// * `Count` - Cantidad de filas (`COUNT(*)`) o de valores no nulos (`COUNT(columna)`).
+/// * `Sum` - Suma de los valores numéricos.
+/// * `Avg` - Promedio de los valores numéricos.
+/// * `Min` - El menor de los valores.
+/// * `Max` - El mayor de los valores.
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    /// Obtiene la función a partir de su nombre, sin distinguir mayúsculas de minúsculas.
+    ///
+    /// # Argumentos
+    /// * `name` - El nombre de la función, por ejemplo `COUNT` o `sum`.
+    ///
+    /// # Retorno
+    /// Devuelve la `AggregateFunction` correspondiente, o `None` si no es una función de agregación.
+    ///
+    pub fn from_name(name: &str) -> Option<AggregateFunction> {
+        match name.to_lowercase().as_str() {
+            "count" => Some(AggregateFunction::Count),
+            "sum" => Some(AggregateFunction::Sum),
+            "avg" => Some(AggregateFunction::Avg),
+            "min" => Some(AggregateFunction::Min),
+            "max" => Some(AggregateFunction::Max),
+            _ => None,
+        }
+    }
+}
+
+/// Muestra el nombre de la función en mayúsculas.
+impl fmt::Display for AggregateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AggregateFunction::Count => "COUNT",
+            AggregateFunction::Sum => "SUM",
+            AggregateFunction::Avg => "AVG",
+            AggregateFunction::Min => "MIN",
+            AggregateFunction::Max => "MAX",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa una agregación de la lista de columnas de un `SELECT`, con los parámetros:
+///
+/// * `function` - La función de agregación.
+/// * `column` - La columna sobre la que se calcula, o `None` para `COUNT(*)`.
+/// * `distinct` - Si solo se tienen en cuenta los valores distintos (`COUNT(DISTINCT columna)`).
+///
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    pub column: Option<String>,
+    pub distinct: bool,
+}
+
+impl Aggregate {
+    /// Crea el acumulador que calcula la agregación sobre las filas de una tabla.
+    ///
+    /// # Argumentos
+    /// * `headers` - Los nombres de las columnas de la tabla.
+    ///
+    /// # Retorno
+    /// Devuelve un `Accumulator` vacío, o un error de tipo `ErrorType::InvalidColumn` si la columna no existe.
+    ///
+    pub fn accumulator(&self, headers: &[&str]) -> Result<Accumulator, SqlError> {
+        let column = match &self.column {
+            Some(column) => Some(find_column(headers, column)?),
+            None => None,
+        };
+        Ok(Accumulator {
+            aggregate: self.clone(),
+            column,
+            count: 0,
+            integer_sum: 0,
+            float_sum: None,
+            extreme: Value::Null,
+            seen: HashSet::new(),
+        })
+    }
+}
+
+/// Muestra la agregación como aparece en el encabezado del resultado, por ejemplo `COUNT(*)`, `SUM(cantidad)`
+/// o `COUNT(DISTINCT producto)`.
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let distinct = if self.distinct { "DISTINCT " } else { "" };
+        match &self.column {
+            Some(column) => write!(f, "{}({}{})", self.function, distinct, column),
+            None => write!(f, "{}(*)", self.function),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+
+/// Estado parcial de una agregación, que se actualiza fila por fila. Tiene los parámetros:
+///
+/// * `aggregate` - La agregación que se está calculando.
+/// * `column` - El índice de la columna en la fila, o `None` para `COUNT(*)`.
+/// * `count` - La cantidad de valores (o de filas, para `COUNT(*)`) acumulados.
+/// * `integer_sum` - La suma de los valores, mientras todos sean enteros.
+/// * `float_sum` - La suma de los valores, desde que aparece el primer `Float`.
+/// * `extreme` - El menor (o mayor) valor visto hasta ahora, para `MIN` y `MAX`.
+/// * `seen` - Los valores ya acumulados, para descartar los repetidos si la agregación es `DISTINCT`.
+///
+pub struct Accumulator {
+    aggregate: Aggregate,
+    column: Option<usize>,
+    count: i64,
+    integer_sum: i64,
+    float_sum: Option<f64>,
+    extreme: Value,
+    seen: HashSet<String>,
+}
+
+impl Accumulator {
+    /// Agrega una fila a la agregación. Los valores `NULL` se ignoran, salvo en `COUNT(*)`, y con `DISTINCT`
+    /// también los valores que ya se acumularon.
+    ///
+    /// # Argumentos
+    /// * `row` - Los valores tipados de la fila.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(())`, o un error de tipo `ErrorType::Error` si `SUM` o `AVG` reciben un valor que no es
+    /// numérico o si la suma de enteros desborda.
+    ///
+    pub fn update(&mut self, row: &[Value]) -> Result<(), SqlError> {
+        let value = match self.column {
+            Some(index) => &row[index],
+            None => {
+                self.count += 1;
+                return Ok(());
+            }
+        };
+        if value.is_null() || (self.aggregate.distinct && !self.seen.insert(value.to_string())) {
+            return Ok(());
+        }
+        match self.aggregate.function {
+            AggregateFunction::Count => {}
+            AggregateFunction::Sum | AggregateFunction::Avg => self.add(value)?,
+            AggregateFunction::Min => self.keep_extreme(value, Ordering::Less),
+            AggregateFunction::Max => self.keep_extreme(value, Ordering::Greater),
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Devuelve el resultado de la agregación. `COUNT` devuelve un entero y `AVG` un `Float`; `SUM`
+    /// devuelve un entero si todos los valores lo son. Sin valores, todas salvo `COUNT` devuelven `NULL`.
+    pub fn finish(self) -> Value {
+        let sum = || match self.float_sum {
+            Some(sum) => Value::Float(sum),
+            None => integer_value(self.integer_sum),
+        };
+        match self.aggregate.function {
+            AggregateFunction::Count => integer_value(self.count),
+            _ if self.count == 0 => Value::Null,
+            AggregateFunction::Sum => sum(),
+            AggregateFunction::Avg => {
+                let total = self.float_sum.unwrap_or(self.integer_sum as f64);
+                Value::Float(total / self.count as f64)
+            }
+            AggregateFunction::Min | AggregateFunction::Max => self.extreme,
+        }
+    }
+
+    /// Suma un valor numérico. Los enteros se suman sin perder precisión hasta que aparece un `Float`.
+    fn add(&mut self, value: &Value) -> Result<(), SqlError> {
+        let error = |message: &str| {
+            SqlError::new(ErrorType::Error, message).with_context(&self.aggregate.to_string())
+        };
+        let integer = match value {
+            Value::Int(value) => Some(i64::from(*value)),
+            Value::BigInt(value) => Some(*value),
+            _ => None,
+        };
+        match (integer, self.float_sum) {
+            (Some(integer), None) => {
+                self.integer_sum = self
+                    .integer_sum
+                    .checked_add(integer)
+                    .ok_or_else(|| error("La suma desborda el rango de los enteros"))?;
+            }
+            (_, sum) => {
+                let value = get_float_value(value)
+                    .ok_or_else(|| error("La función de agregación requiere valores numéricos"))?;
+                self.float_sum = Some(sum.unwrap_or(self.integer_sum as f64) + value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reemplaza el extremo guardado si `value` lo supera en la dirección indicada. Los valores que no
+    /// pueden compararse con el extremo se ignoran, igual que en `ORDER BY`.
+    fn keep_extreme(&mut self, value: &Value, direction: Ordering) {
+        if self.extreme.is_null() || compare_values(value, &self.extreme) == Some(direction) {
+            self.extreme = value.clone();
+        }
+    }
+}
+
+#[derive(Debug)]
+
+/// Agrupa las filas por los valores de sus columnas de agrupamiento y calcula las agregaciones de cada grupo.
+/// Tiene los parámetros:
+///
+/// * `key_columns` - Los índices de las columnas de `GROUP BY` en la fila.
+/// * `template` - Los acumuladores vacíos, que se copian al crear cada grupo.
+/// * `index` - Relaciona la clave de cada grupo con su posición en `groups`.
+/// * `groups` - Los valores de la clave y los acumuladores de cada grupo, en el orden en que aparecieron.
+///
+pub struct GroupTable {
+    key_columns: Vec<usize>,
+    template: Vec<Accumulator>,
+    index: HashMap<Vec<Option<String>>, usize>,
+    groups: Vec<(Vec<Value>, Vec<Accumulator>)>,
+}
+
+impl GroupTable {
+    /// Crea una tabla de grupos vacía.
+    ///
+    /// # Argumentos
+    /// * `group_by` - Las columnas de `GROUP BY` (vacío si la consulta solo tiene agregaciones).
+    /// * `aggregates` - Las agregaciones a calcular en cada grupo.
+    /// * `headers` - Los nombres de las columnas de la tabla.
+    ///
+    /// # Retorno
+    /// Devuelve el `GroupTable`, o un error de tipo `ErrorType::InvalidColumn` si alguna columna no existe.
+    ///
+    pub fn new(
+        group_by: &[String],
+        aggregates: &[&Aggregate],
+        headers: &[&str],
+    ) -> Result<GroupTable, SqlError> {
+        let key_columns = group_by
+            .iter()
+            .map(|column| find_column(headers, column))
+            .collect::<Result<Vec<usize>, SqlError>>()?;
+        let template = aggregates
+            .iter()
+            .map(|aggregate| aggregate.accumulator(headers))
+            .collect::<Result<Vec<Accumulator>, SqlError>>()?;
+        Ok(GroupTable {
+            key_columns,
+            template,
+            index: HashMap::new(),
+            groups: Vec::new(),
+        })
+    }
+
+    /// Agrega una fila al grupo que le corresponde, creándolo si es la primera fila con esa clave.
+    ///
+    /// # Argumentos
+    /// * `row` - Los valores tipados de la fila.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(())`, o el error de alguno de los acumuladores (ver `Accumulator::update`).
+    ///
+    /// # Notas
+    /// La clave se arma con el texto de cada valor (ver `Value::to_field`), por lo que todos los `NULL`
+    /// de una columna quedan en el mismo grupo, como en SQL.
+    ///
+    pub fn add(&mut self, row: &[Value]) -> Result<(), SqlError> {
+        let key: Vec<Option<String>> = self
+            .key_columns
+            .iter()
+            .map(|&index| row[index].to_field())
+            .collect();
+        let position = match self.index.get(&key) {
+            Some(&position) => position,
+            None => {
+                let values = self.key_columns.iter().map(|&i| row[i].clone()).collect();
+                self.groups.push((values, self.template.clone()));
+                self.index.insert(key, self.groups.len() - 1);
+                self.groups.len() - 1
+            }
+        };
+        for accumulator in &mut self.groups[position].1 {
+            accumulator.update(row)?;
+        }
+        Ok(())
+    }
+
+    /// Devuelve una fila por grupo, con los valores de las columnas de `GROUP BY` seguidos por el resultado
+    /// de cada agregación. Sin columnas de agrupamiento, siempre hay un único grupo, aunque no haya filas.
+    pub fn finish(mut self) -> Vec<Vec<Value>> {
+        if self.groups.is_empty() && self.key_columns.is_empty() {
+            self.groups.push((Vec::new(), self.template));
+        }
+        self.groups
+            .into_iter()
+            .map(|(mut values, accumulators)| {
+                values.extend(accumulators.into_iter().map(Accumulator::finish));
+                values
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregate(function: AggregateFunction, column: Option<&str>, rows: &[Value]) -> Value {
+        let aggregate = Aggregate {
+            function,
+            column: column.map(|column| column.to_string()),
+            distinct: false,
+        };
+        let mut accumulator = aggregate.accumulator(&["valor"]).unwrap();
+        for value in rows {
+            accumulator.update(std::slice::from_ref(value)).unwrap();
+        }
+        accumulator.finish()
+    }
+
+    #[test]
+    fn test_count_rows_and_values() {
+        let rows = [Value::Int(1), Value::Null, Value::Int(3)];
+        assert_eq!(
+            aggregate(AggregateFunction::Count, None, &rows),
+            Value::Int(3)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Count, Some("valor"), &rows),
+            Value::Int(2)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Count, Some("valor"), &[]),
+            Value::Int(0)
+        );
+    }
+
+    #[test]
+    fn test_sum_and_avg() {
+        let integers = [Value::Int(2_000_000_000), Value::Int(2_000_000_000)];
+        assert_eq!(
+            aggregate(AggregateFunction::Sum, Some("valor"), &integers),
+            Value::BigInt(4_000_000_000)
+        );
+        let mixed = [Value::Int(1), Value::Null, Value::Float(2.5)];
+        assert_eq!(
+            aggregate(AggregateFunction::Sum, Some("valor"), &mixed),
+            Value::Float(3.5)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Avg, Some("valor"), &mixed),
+            Value::Float(1.75)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Avg, Some("valor"), &[Value::Null]),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_min_and_max() {
+        let rows = [
+            Value::Int(10),
+            Value::Null,
+            Value::Float(9.5),
+            Value::BigInt(3_000_000_000),
+        ];
+        assert_eq!(
+            aggregate(AggregateFunction::Min, Some("valor"), &rows),
+            Value::Float(9.5)
+        );
+        assert_eq!(
+            aggregate(AggregateFunction::Max, Some("valor"), &rows),
+            Value::BigInt(3_000_000_000)
+        );
+        let words = [
+            Value::Str("pera".to_string()),
+            Value::Str("kiwi".to_string()),
+        ];
+        assert_eq!(
+            aggregate(AggregateFunction::Min, Some("valor"), &words),
+            Value::Str("kiwi".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sum_errors() {
+        let aggregate = Aggregate {
+            function: AggregateFunction::Sum,
+            column: Some("nombre".to_string()),
+            distinct: false,
+        };
+        let error = aggregate.accumulator(&["valor"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidColumn);
+
+        let mut accumulator = aggregate.accumulator(&["nombre"]).unwrap();
+        let error = accumulator
+            .update(&[Value::Str("Ana".to_string())])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        assert_eq!(error.context(), Some("SUM(nombre)"));
+
+        let mut accumulator = aggregate.accumulator(&["nombre"]).unwrap();
+        accumulator.update(&[Value::BigInt(i64::MAX)]).unwrap();
+        assert!(accumulator.update(&[Value::Int(1)]).is_err());
+    }
+
+    #[test]
+    fn test_group_table() {
+        let count = Aggregate {
+            function: AggregateFunction::Count,
+            column: None,
+            distinct: false,
+        };
+        let sum = Aggregate {
+            function: AggregateFunction::Sum,
+            column: Some("cantidad".to_string()),
+            distinct: false,
+        };
+        let headers = ["cliente", "cantidad"];
+        let mut groups =
+            GroupTable::new(&["cliente".to_string()], &[&count, &sum], &headers).unwrap();
+        let rows = [
+            [Value::Int(2), Value::Int(3)],
+            [Value::Null, Value::Int(1)],
+            [Value::Int(2), Value::Int(4)],
+            [Value::Null, Value::Null],
+        ];
+        for row in &rows {
+            groups.add(row).unwrap();
+        }
+        assert_eq!(
+            groups.finish(),
+            vec![
+                vec![Value::Int(2), Value::Int(2), Value::Int(7)],
+                vec![Value::Null, Value::Int(2), Value::Int(1)],
+            ]
+        );
+
+        let groups = GroupTable::new(&[], &[&count], &headers).unwrap();
+        assert_eq!(groups.finish(), vec![vec![Value::Int(0)]]);
+        let groups = GroupTable::new(&["cliente".to_string()], &[&count], &headers).unwrap();
+        assert!(groups.finish().is_empty());
+    }
+
+    #[test]
+    fn test_distinct_aggregates() {
+        let rows = [
+            Value::Int(2),
+            Value::Int(3),
+            Value::Null,
+            Value::Int(2),
+            Value::Int(3),
+        ];
+        let aggregate = |function| Aggregate {
+            function,
+            column: Some("valor".to_string()),
+            distinct: true,
+        };
+        for (function, expected) in [
+            (AggregateFunction::Count, Value::Int(2)),
+            (AggregateFunction::Sum, Value::Int(5)),
+            (AggregateFunction::Avg, Value::Float(2.5)),
+        ] {
+            let mut accumulator = aggregate(function).accumulator(&["valor"]).unwrap();
+            for value in &rows {
+                accumulator.update(std::slice::from_ref(value)).unwrap();
+            }
+            assert_eq!(accumulator.finish(), expected);
+        }
+        assert_eq!(
+            aggregate(AggregateFunction::Count).to_string(),
+            "COUNT(DISTINCT valor)"
+        );
+    }
+}
@@ -0,0 +1,430 @@
+use std::io::{self, BufRead, Write};
+
+/// Los campos de un registro del CSV. Cada campo es `None` si representa un valor `NULL`.
+pub type Record = Vec<Option<String>>;
+
+/// Lee registros de un archivo CSV siguiendo el formato RFC 4180.
+///
+/// Un campo puede estar entre comillas dobles; en ese caso puede contener comas, saltos de línea
+/// y comillas dobles escritas dos veces (`""`). Los registros pueden terminar en `\n` o en `\r\n`.
+///
+/// Un campo vacío sin comillas representa un valor `NULL` y se lee como `None`; una cadena vacía
+/// se escribe entre comillas (`""`) y se lee como `Some("")`.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::csv::CsvReader;
+///
+/// let data = "id,producto\n1,\"Mouse, inalámbrico\"\n2,\"Monitor \"\"4K\"\"\"\n3,\n";
+/// let mut reader = CsvReader::new(data.as_bytes());
+/// let field = |s: &str| Some(s.to_string());
+///
+/// assert_eq!(reader.read_record().unwrap(), Some(vec![field("id"), field("producto")]));
+/// assert_eq!(reader.read_record().unwrap(), Some(vec![field("1"), field("Mouse, inalámbrico")]));
+/// assert_eq!(reader.read_record().unwrap(), Some(vec![field("2"), field("Monitor \"4K\"")]));
+/// assert_eq!(reader.read_record().unwrap(), Some(vec![field("3"), None]));
+/// assert_eq!(reader.read_record().unwrap(), None);
+/// ```
+pub struct CsvReader<R> {
+    reader: R,
+    records: usize,
+    field_count: Option<usize>,
+    pending_line: Option<String>,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    /// Crea un lector de registros a partir de cualquier fuente con buffer.
+    pub fn new(reader: R) -> Self {
+        CsvReader {
+            reader,
+            records: 0,
+            field_count: None,
+            pending_line: None,
+        }
+    }
+
+    /// Indica la cantidad de campos que deben tener los registros siguientes (la del encabezado de la tabla).
+    /// Desde entonces, `read_record` devuelve un error si un registro tiene más o menos campos.
+    ///
+    /// # Ejemplo
+    /// ```rust
+    /// use sql::csv::CsvReader;
+    ///
+    /// let mut reader = CsvReader::new("a,b,c\n1,2,3\n1,2\n".as_bytes());
+    /// reader.read_record().unwrap();
+    /// reader.expect_fields(3);
+    /// assert!(reader.read_record().is_ok());
+    /// let error = reader.read_record().unwrap_err();
+    /// assert_eq!(error.to_string(), "el registro 3 tiene 2 campos, pero el encabezado tiene 3");
+    /// ```
+    pub fn expect_fields(&mut self, count: usize) {
+        self.field_count = Some(count);
+    }
+
+    /// Lee el siguiente registro del archivo.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(Some(campos))` con los campos del registro (`None` para los campos `NULL`), `Ok(None)` si no quedan registros,
+    /// o un `io::Error` si la lectura falla, el archivo termina dentro de un campo entre comillas o el registro no tiene
+    /// la cantidad de campos indicada con `expect_fields`.
+    ///
+    /// # Notas
+    /// Si un campo entre comillas contiene saltos de línea, se siguen leyendo líneas hasta cerrar las comillas.
+    /// Una línea vacía es un registro con un único campo `NULL`, por lo que en una tabla de varias columnas
+    /// es un error, salvo que solo queden líneas vacías hasta el final del archivo: en ese caso se ignoran.
+    ///
+    pub fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let mut fields: Record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut line = String::new();
+        let mut started = false;
+
+        loop {
+            line.clear();
+            if self.read_line(&mut line)? == 0 {
+                if !started {
+                    return Ok(None);
+                }
+                if in_quotes {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "campo entre comillas sin cerrar",
+                    ));
+                }
+                break;
+            }
+            started = true;
+
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c == '"' {
+                        if chars.peek() == Some(&'"') {
+                            chars.next();
+                            field.push('"');
+                        } else {
+                            in_quotes = false;
+                        }
+                    } else {
+                        field.push(c);
+                    }
+                    continue;
+                }
+                match c {
+                    ',' => fields.push(finish_field(&mut field, &mut quoted)),
+                    '"' if field.is_empty() => {
+                        in_quotes = true;
+                        quoted = true;
+                    }
+                    '\r' if chars.peek() == Some(&'\n') => {}
+                    '\n' => {}
+                    _ => field.push(c),
+                }
+            }
+            if !in_quotes {
+                break;
+            }
+        }
+        fields.push(finish_field(&mut field, &mut quoted));
+        self.records += 1;
+        match self.field_count {
+            Some(count) if count != fields.len() => {
+                if fields == [None] && self.only_blank_lines_left()? {
+                    return Ok(None);
+                }
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "el registro {} tiene {} campos, pero el encabezado tiene {}",
+                        self.records,
+                        fields.len(),
+                        count
+                    ),
+                ))
+            }
+            _ => Ok(Some(fields)),
+        }
+    }
+
+    /// Lee la siguiente línea, empezando por la que haya quedado pendiente de `only_blank_lines_left`.
+    fn read_line(&mut self, line: &mut String) -> io::Result<usize> {
+        match self.pending_line.take() {
+            Some(pending) => {
+                line.push_str(&pending);
+                Ok(pending.len())
+            }
+            None => self.reader.read_line(line),
+        }
+    }
+
+    /// Avanza sobre las líneas vacías siguientes y devuelve `true` si el archivo termina luego de ellas.
+    /// Si aparece una línea con contenido, queda pendiente para el próximo registro.
+    fn only_blank_lines_left(&mut self) -> io::Result<bool> {
+        loop {
+            let mut line = String::new();
+            if self.read_line(&mut line)? == 0 {
+                return Ok(true);
+            }
+            if !line.trim_end_matches(['\r', '\n']).is_empty() {
+                self.pending_line = Some(line);
+                return Ok(false);
+            }
+            self.records += 1;
+        }
+    }
+}
+
+/// Termina el campo que se estaba leyendo y lo deja listo para el siguiente.
+/// Un campo vacío que no estaba entre comillas es `NULL`.
+fn finish_field(field: &mut String, quoted: &mut bool) -> Option<String> {
+    let value = if field.is_empty() && !*quoted {
+        None
+    } else {
+        Some(std::mem::take(field))
+    };
+    *quoted = false;
+    value
+}
+
+/// Permite recorrer los registros con un `for`, del mismo modo que `BufRead::lines`.
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Convierte una línea en sus campos, interpretando las comillas como en un archivo CSV.
+///
+/// # Argumentos
+/// * `line` - La línea a separar.
+///
+/// # Retorno
+/// Devuelve los campos de la línea. Si la línea tiene comillas sin cerrar, devuelve la línea
+/// completa como único campo.
+///
+pub fn parse_record(line: &str) -> Record {
+    let mut reader = CsvReader::new(line.as_bytes());
+    match reader.read_record() {
+        Ok(Some(fields)) => fields,
+        _ => vec![Some(line.to_string())],
+    }
+}
+
+/// Un valor que puede escribirse como campo de un registro CSV: un texto, o `None` para un valor `NULL`.
+pub trait CsvField {
+    /// Devuelve el texto del campo, o `None` si el campo es `NULL`.
+    fn as_field(&self) -> Option<&str>;
+}
+
+impl CsvField for String {
+    fn as_field(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl CsvField for &str {
+    fn as_field(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T: CsvField> CsvField for Option<T> {
+    fn as_field(&self) -> Option<&str> {
+        self.as_ref().and_then(CsvField::as_field)
+    }
+}
+
+/// Escribe un campo, agregando comillas solo si está vacío o contiene comas, comillas o saltos de línea.
+/// Las cadenas vacías van entre comillas para no confundirse con un valor `NULL`.
+///
+/// # Argumentos
+/// * `value` - El valor del campo.
+///
+/// # Retorno
+/// Devuelve el campo listo para escribirse en el archivo CSV.
+///
+pub fn format_field(value: &str) -> String {
+    if value.is_empty() || value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Une los campos de un registro en una línea CSV, sin el salto de línea final.
+///
+/// # Argumentos
+/// * `values` - Los campos del registro.
+///
+/// # Retorno
+/// Devuelve la línea con los campos separados por comas y escapados con `format_field`.
+/// Los campos `NULL` se escriben vacíos y sin comillas.
+///
+pub fn format_record<S: CsvField>(values: &[S]) -> String {
+    values
+        .iter()
+        .map(|value| value.as_field().map(format_field).unwrap_or_default())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Escribe un registro completo, terminado en salto de línea.
+///
+/// # Argumentos
+/// * `writer` - El destino de la escritura.
+/// * `values` - Los campos del registro.
+///
+/// # Retorno
+/// Devuelve un `io::Result<()>` que indica el éxito o el fallo de la escritura.
+///
+pub fn write_record<W: Write, S: CsvField>(writer: &mut W, values: &[S]) -> io::Result<()> {
+    writeln!(writer, "{}", format_record(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(data: &str) -> Vec<Vec<String>> {
+        CsvReader::new(data.as_bytes())
+            .map(|record| {
+                record
+                    .unwrap()
+                    .into_iter()
+                    .map(Option::unwrap_or_default)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_plain_records() {
+        let records = read_all("id,nombre\n1,Ana\r\n2,Luis");
+        assert_eq!(
+            records,
+            vec![
+                vec!["id".to_string(), "nombre".to_string()],
+                vec!["1".to_string(), "Ana".to_string()],
+                vec!["2".to_string(), "Luis".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_quoted_fields() {
+        let records = read_all("1,\"Pérez, Juan\",\"dijo \"\"hola\"\"\",\"\"\n");
+        assert_eq!(
+            records,
+            vec![vec![
+                "1".to_string(),
+                "Pérez, Juan".to_string(),
+                "dijo \"hola\"".to_string(),
+                String::new(),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_read_embedded_newline() {
+        let records = read_all("1,\"línea uno\nlínea dos\",x\n2,y,z\n");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0][1], "línea uno\nlínea dos");
+        assert_eq!(records[0][2], "x");
+        assert_eq!(records[1], vec!["2", "y", "z"]);
+    }
+
+    #[test]
+    fn test_read_unterminated_quote() {
+        let mut reader = CsvReader::new("1,\"sin cerrar\n".as_bytes());
+        let error = reader.read_record().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_format_record_round_trip() {
+        let values = vec![
+            "1".to_string(),
+            "Mouse, inalámbrico".to_string(),
+            "Monitor \"4K\"".to_string(),
+            "dos\nlíneas".to_string(),
+        ];
+        let line = format_record(&values);
+        assert_eq!(
+            line,
+            "1,\"Mouse, inalámbrico\",\"Monitor \"\"4K\"\"\",\"dos\nlíneas\""
+        );
+        assert_eq!(read_all(&line), vec![values]);
+    }
+
+    #[test]
+    fn test_read_null_and_empty_fields() {
+        let mut reader = CsvReader::new("1,,\"\",x\n".as_bytes());
+        assert_eq!(
+            reader.read_record().unwrap(),
+            Some(vec![
+                Some("1".to_string()),
+                None,
+                Some(String::new()),
+                Some("x".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_format_record_null_and_empty() {
+        let values = vec![Some("1".to_string()), None, Some(String::new())];
+        let line = format_record(&values);
+        assert_eq!(line, "1,,\"\"");
+        assert_eq!(parse_record(&line), values);
+    }
+
+    #[test]
+    fn test_parse_record() {
+        assert_eq!(
+            parse_record("a,\"b,c\",d"),
+            vec![
+                Some("a".to_string()),
+                Some("b,c".to_string()),
+                Some("d".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_wrong_field_count() {
+        let mut reader = CsvReader::new("a,b,c\n1,2\n\n3,4,\"5\n6\"\n7,8,9\n".as_bytes());
+        reader.read_record().unwrap();
+        reader.expect_fields(3);
+
+        let error = reader.read_record().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error
+            .to_string()
+            .starts_with("el registro 2 tiene 2 campos"));
+        let error = reader.read_record().unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("el registro 3 tiene 1 campos"));
+        assert_eq!(reader.read_record().unwrap().map(|r| r.len()), Some(3));
+        assert_eq!(reader.read_record().unwrap().map(|r| r.len()), Some(3));
+        assert!(reader.read_record().unwrap().is_none());
+
+        let mut trailing_blank_lines = CsvReader::new("id,name\n1,a\n\n\r\n".as_bytes());
+        trailing_blank_lines.read_record().unwrap();
+        trailing_blank_lines.expect_fields(2);
+        assert_eq!(
+            trailing_blank_lines.read_record().unwrap().map(|r| r.len()),
+            Some(2)
+        );
+        assert!(trailing_blank_lines.read_record().unwrap().is_none());
+
+        let mut single_column = CsvReader::new("a\n\n1\n".as_bytes());
+        single_column.read_record().unwrap();
+        single_column.expect_fields(1);
+        assert_eq!(single_column.read_record().unwrap(), Some(vec![None]));
+    }
+}
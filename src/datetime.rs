@@ -0,0 +1,1026 @@
+//! Tipos de fecha y hora (`DATE`, `TIME`, `TIMESTAMP` e `INTERVAL`) y las funciones que operan sobre ellos.
+//!
+//! Las fechas usan el calendario gregoriano proléptico y se convierten a una cantidad de días desde
+//! el 1970-01-01 para compararlas y operar con ellas. Los timestamps no guardan zona horaria: si el texto
+//! trae un desplazamiento (`Z`, `+03:00`, ...), se convierte a UTC al leerlo.
+use std::{
+    cmp::Ordering,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    error::{ErrorType, SqlError},
+    extras::{cast_to_value, integer_value, Value},
+};
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// Primer y último día, contados desde el 1970-01-01, que puede tener el resultado de una operación con fechas:
+/// el 0000-01-01 y el 9999-12-31, las fechas que se pueden escribir en una tabla y volver a leer.
+const MIN_DAYS: i64 = -719_528;
+const MAX_DAYS: i64 = 2_932_896;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+
+/// Representa una fecha del calendario, con el formato ISO-8601 `AAAA-MM-DD`.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::Date;
+///
+/// let date = Date::parse("2024-02-29").unwrap();
+/// assert_eq!(date.to_string(), "2024-02-29");
+/// assert_eq!(date.add_months(12).unwrap().to_string(), "2025-02-28");
+/// assert!(Date::parse("2023-02-29").is_none());
+/// ```
+pub struct Date {
+    year: i64,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Crea una fecha, verificando que el mes y el día sean válidos.
+    pub fn new(year: i64, month: u32, day: u32) -> Option<Date> {
+        if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// Parsea una fecha con el formato `AAAA-MM-DD`.
+    ///
+    /// # Retorno
+    /// Devuelve la `Date`, o `None` si el texto no tiene ese formato o la fecha no existe.
+    ///
+    pub fn parse(s: &str) -> Option<Date> {
+        let parts: Vec<&str> = s.split('-').collect();
+        match parts.as_slice() {
+            [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+                Date::new(digits(year)?, digits(month)? as u32, digits(day)? as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Crea la fecha que está `days` días después del 1970-01-01.
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Date { year, month, day }
+    }
+
+    /// Crea la fecha que está `days` días después del 1970-01-01, o `None` si queda fuera del rango
+    /// `0000-01-01` a `9999-12-31`.
+    fn checked_from_days(days: i64) -> Option<Date> {
+        (MIN_DAYS..=MAX_DAYS)
+            .contains(&days)
+            .then(|| Date::from_days(days))
+    }
+
+    /// Devuelve la cantidad de días desde el 1970-01-01 (negativa para fechas anteriores).
+    pub fn days(&self) -> i64 {
+        let year = if self.month <= 2 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + i64::from(self.day)
+            - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Suma una cantidad de meses (que puede ser negativa). Si el día no existe en el mes resultante,
+    /// se usa el último día de ese mes. Devuelve `None` si el resultado queda fuera del rango
+    /// `0000-01-01` a `9999-12-31`.
+    pub fn add_months(&self, months: i64) -> Option<Date> {
+        let total = self
+            .year
+            .checked_mul(12)?
+            .checked_add(i64::from(self.month) - 1)?
+            .checked_add(months)?;
+        let year = total.div_euclid(12);
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        let month = total.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Some(Date { year, month, day })
+    }
+
+    /// Devuelve el día de la semana, de 0 (lunes) a 6 (domingo).
+    fn weekday(&self) -> i64 {
+        (self.days() + 3).rem_euclid(7)
+    }
+}
+
+/// Muestra la fecha con el formato `AAAA-MM-DD`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+
+/// Representa una hora del día, con el formato ISO-8601 `HH:MM[:SS[.ffffff]]`.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::Time;
+///
+/// assert_eq!(Time::parse("08:30").unwrap().to_string(), "08:30:00");
+/// assert_eq!(Time::parse("23:59:59.5").unwrap().to_string(), "23:59:59.5");
+/// assert!(Time::parse("24:00").is_none());
+/// ```
+pub struct Time {
+    micros: i64,
+}
+
+impl Time {
+    /// Parsea una hora con el formato `HH:MM`, `HH:MM:SS` o `HH:MM:SS.ffffff`.
+    ///
+    /// # Retorno
+    /// Devuelve la `Time`, o `None` si el texto no tiene ese formato o la hora no existe.
+    ///
+    pub fn parse(s: &str) -> Option<Time> {
+        let (clock, fraction) = match s.split_once('.') {
+            Some((clock, fraction)) => (clock, Some(fraction)),
+            None => (s, None),
+        };
+        let parts: Vec<&str> = clock.split(':').collect();
+        if parts.iter().any(|part| part.len() != 2) {
+            return None;
+        }
+        let (hour, minute, second) = match parts.as_slice() {
+            [hour, minute] => (digits(hour)?, digits(minute)?, 0),
+            [hour, minute, second] => (digits(hour)?, digits(minute)?, digits(second)?),
+            _ => return None,
+        };
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        let micros = match fraction {
+            Some(fraction) if parts.len() == 3 && (1..=6).contains(&fraction.len()) => {
+                digits(fraction)? * 10_i64.pow(6 - fraction.len() as u32)
+            }
+            Some(_) => return None,
+            None => 0,
+        };
+        Some(Time {
+            micros: hour * MICROS_PER_HOUR
+                + minute * MICROS_PER_MINUTE
+                + second * MICROS_PER_SECOND
+                + micros,
+        })
+    }
+
+    /// Crea la hora que está `micros` microsegundos después de la medianoche, dando la vuelta al reloj si hace falta.
+    pub fn from_micros(micros: i64) -> Time {
+        Time {
+            micros: micros.rem_euclid(MICROS_PER_DAY),
+        }
+    }
+
+    /// Devuelve la cantidad de microsegundos desde la medianoche.
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+}
+
+/// Muestra la hora con el formato `HH:MM:SS`, agregando la fracción de segundo solo si la tiene.
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            self.micros / MICROS_PER_HOUR,
+            self.micros % MICROS_PER_HOUR / MICROS_PER_MINUTE,
+            self.micros % MICROS_PER_MINUTE / MICROS_PER_SECOND
+        )?;
+        let fraction = self.micros % MICROS_PER_SECOND;
+        if fraction != 0 {
+            let fraction = format!("{:06}", fraction);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+
+/// Representa un instante, con una fecha y una hora, en UTC.
+///
+/// Se lee con el formato ISO-8601 `AAAA-MM-DDTHH:MM[:SS[.ffffff]]`, donde la `T` también puede ser un espacio,
+/// seguido opcionalmente de `Z` o de un desplazamiento `+HH:MM` / `-HH:MM`. Se muestra como `AAAA-MM-DD HH:MM:SS`.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::Timestamp;
+///
+/// let timestamp = Timestamp::parse("2024-03-10T23:30:00-03:00").unwrap();
+/// assert_eq!(timestamp.to_string(), "2024-03-11 02:30:00");
+/// ```
+pub struct Timestamp {
+    date: Date,
+    time: Time,
+}
+
+impl Timestamp {
+    /// Crea un timestamp a partir de su fecha y su hora.
+    pub fn new(date: Date, time: Time) -> Timestamp {
+        Timestamp { date, time }
+    }
+
+    /// Parsea un timestamp ISO-8601, convirtiéndolo a UTC si tiene desplazamiento.
+    ///
+    /// # Retorno
+    /// Devuelve el `Timestamp`, o `None` si el texto no tiene ese formato.
+    ///
+    pub fn parse(s: &str) -> Option<Timestamp> {
+        if s.len() < 16 || !s.is_char_boundary(10) {
+            return None;
+        }
+        let (date, rest) = s.split_at(10);
+        let rest = rest.strip_prefix(['T', 't', ' '])?;
+        let (time, offset) = if let Some(time) = rest.strip_suffix(['Z', 'z']) {
+            (time, 0)
+        } else if let Some(index) = rest.rfind(['+', '-']) {
+            (&rest[..index], parse_offset(&rest[index..])?)
+        } else {
+            (rest, 0)
+        };
+        let timestamp = Timestamp::new(Date::parse(date)?, Time::parse(time)?);
+        Some(Timestamp::from_micros(timestamp.micros() - offset))
+    }
+
+    /// Crea el timestamp que está `micros` microsegundos después del 1970-01-01 00:00:00.
+    pub fn from_micros(micros: i64) -> Timestamp {
+        Timestamp {
+            date: Date::from_days(micros.div_euclid(MICROS_PER_DAY)),
+            time: Time::from_micros(micros.rem_euclid(MICROS_PER_DAY)),
+        }
+    }
+
+    /// Devuelve la cantidad de microsegundos desde el 1970-01-01 00:00:00.
+    pub fn micros(&self) -> i64 {
+        self.date.days() * MICROS_PER_DAY + self.time.micros()
+    }
+
+    /// Devuelve el instante actual, en UTC.
+    pub fn now() -> Timestamp {
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_micros() as i64)
+            .unwrap_or(0);
+        Timestamp::from_micros(elapsed)
+    }
+
+    /// Devuelve la fecha del timestamp.
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Devuelve la hora del timestamp.
+    pub fn time(&self) -> Time {
+        self.time
+    }
+}
+
+impl From<Date> for Timestamp {
+    /// Convierte una fecha en el timestamp de su medianoche.
+    fn from(date: Date) -> Timestamp {
+        Timestamp::new(date, Time::from_micros(0))
+    }
+}
+
+/// Muestra el timestamp con el formato `AAAA-MM-DD HH:MM:SS`.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+/// Representa un intervalo de tiempo, separado en meses, días y microsegundos porque ni los meses ni los días
+/// tienen una duración fija al sumarse a una fecha.
+///
+/// Se lee como una lista de pares `cantidad unidad`, por ejemplo `1 year 2 months`, `3 days` o `-1.5 hours`,
+/// con las unidades `year`, `month`, `week`, `day`, `hour`, `minute` y `second` (en singular o plural).
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::Interval;
+///
+/// let interval = Interval::parse("1 year 2 months 1 week 90 minutes").unwrap();
+/// assert_eq!(interval.to_string(), "1 year 2 months 7 days 1 hour 30 minutes");
+/// ```
+pub struct Interval {
+    months: i64,
+    days: i64,
+    micros: i64,
+}
+
+impl Interval {
+    /// Crea un intervalo a partir de sus meses, días y microsegundos.
+    pub fn new(months: i64, days: i64, micros: i64) -> Interval {
+        Interval {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    /// Parsea un intervalo escrito como pares `cantidad unidad`.
+    ///
+    /// # Retorno
+    /// Devuelve el `Interval`, o `None` si algún par no es válido o el total no entra en 64 bits. Solo las horas,
+    /// los minutos y los segundos admiten cantidades con decimales.
+    ///
+    pub fn parse(s: &str) -> Option<Interval> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        if words.is_empty() || !words.len().is_multiple_of(2) {
+            return None;
+        }
+        let mut interval = Interval::new(0, 0, 0);
+        for pair in words.chunks(2) {
+            let unit = pair[1].to_lowercase();
+            let unit = unit.strip_suffix('s').unwrap_or(&unit);
+            let whole = || pair[0].parse::<i64>().ok();
+            let fractional = |micros: i64| {
+                let amount = pair[0].parse::<f64>().ok()?;
+                let total = (amount * micros as f64).round();
+                (pair[0]
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+                    && total.abs() < i64::MAX as f64)
+                    .then_some(total as i64)
+            };
+            let (months, days, micros) = match unit {
+                "year" => (whole()?.checked_mul(12)?, 0, 0),
+                "month" | "mon" => (whole()?, 0, 0),
+                "week" => (0, whole()?.checked_mul(7)?, 0),
+                "day" => (0, whole()?, 0),
+                "hour" => (0, 0, fractional(MICROS_PER_HOUR)?),
+                "minute" | "min" => (0, 0, fractional(MICROS_PER_MINUTE)?),
+                "second" | "sec" => (0, 0, fractional(MICROS_PER_SECOND)?),
+                _ => return None,
+            };
+            interval = interval.add(&Interval::new(months, days, micros))?;
+        }
+        Some(interval)
+    }
+
+    /// Devuelve el intervalo con el signo opuesto, o `None` si algún componente no entra en 64 bits.
+    pub fn negate(&self) -> Option<Interval> {
+        Some(Interval::new(
+            self.months.checked_neg()?,
+            self.days.checked_neg()?,
+            self.micros.checked_neg()?,
+        ))
+    }
+
+    /// Suma dos intervalos componente a componente, o devuelve `None` si algún componente no entra en 64 bits.
+    pub fn add(&self, other: &Interval) -> Option<Interval> {
+        Some(Interval::new(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.micros.checked_add(other.micros)?,
+        ))
+    }
+
+    /// Devuelve la duración aproximada en microsegundos, contando los meses como 30 días, para comparar intervalos.
+    fn approximate_micros(&self) -> i128 {
+        (i128::from(self.months) * 30 + i128::from(self.days)) * i128::from(MICROS_PER_DAY)
+            + i128::from(self.micros)
+    }
+}
+
+/// Los intervalos se ordenan por su duración aproximada (ver `Interval::approximate_micros`).
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Interval) -> Option<Ordering> {
+        Some(self.approximate_micros().cmp(&other.approximate_micros()))
+    }
+}
+
+/// Muestra el intervalo como pares `cantidad unidad`, en el mismo formato que acepta `Interval::parse`.
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = self.micros % MICROS_PER_MINUTE;
+        let parts = [
+            (self.months / 12, "year".to_string()),
+            (self.months % 12, "month".to_string()),
+            (self.days, "day".to_string()),
+            (self.micros / MICROS_PER_HOUR, "hour".to_string()),
+            (
+                self.micros % MICROS_PER_HOUR / MICROS_PER_MINUTE,
+                "minute".to_string(),
+            ),
+        ];
+        let mut words: Vec<String> = parts
+            .iter()
+            .filter(|(amount, _)| *amount != 0)
+            .map(|(amount, unit)| plural(&amount.to_string(), unit, amount.unsigned_abs() == 1))
+            .collect();
+        if seconds != 0 {
+            let amount = if seconds % MICROS_PER_SECOND == 0 {
+                (seconds / MICROS_PER_SECOND).to_string()
+            } else {
+                (seconds as f64 / MICROS_PER_SECOND as f64).to_string()
+            };
+            words.push(plural(&amount, "second", seconds == MICROS_PER_SECOND));
+        }
+        if words.is_empty() {
+            words.push("0 days".to_string());
+        }
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// Devuelve la fecha, la hora o el timestamp que representa un valor. Las cadenas se interpretan con `cast_to_value`.
+fn temporal_value(value: &Value) -> Value {
+    match value {
+        Value::Str(s) => cast_to_value(s.trim()),
+        value => value.clone(),
+    }
+}
+
+/// Compara dos valores de fecha u hora. Una fecha se compara con un timestamp como si fuera su medianoche.
+///
+/// # Retorno
+/// Devuelve `Some(Ordering)` si ambos valores son fechas, horas, timestamps o intervalos comparables entre sí,
+/// o `None` en otro caso.
+///
+pub fn compare_temporal(value1: &Value, value2: &Value) -> Option<Ordering> {
+    match (value1, value2) {
+        (Value::Date(d1), Value::Date(d2)) => Some(d1.cmp(d2)),
+        (Value::Time(t1), Value::Time(t2)) => Some(t1.cmp(t2)),
+        (Value::Timestamp(t1), Value::Timestamp(t2)) => Some(t1.cmp(t2)),
+        (Value::Date(d1), Value::Timestamp(t2)) => Some(Timestamp::from(*d1).cmp(t2)),
+        (Value::Timestamp(t1), Value::Date(d2)) => Some(t1.cmp(&Timestamp::from(*d2))),
+        (Value::Interval(i1), Value::Interval(i2)) => i1.partial_cmp(i2),
+        _ => None,
+    }
+}
+
+/// Suma o resta dos valores cuando alguno es de fecha u hora.
+///
+/// # Argumentos
+/// * `value1` - El primer operando.
+/// * `value2` - El segundo operando.
+/// * `operator` - `+` o `-`.
+///
+/// # Retorno
+/// Devuelve `Some(Ok(valor))` con el resultado, o `None` si la operación no está definida para esos tipos:
+/// * fecha, hora o timestamp `±` intervalo (una fecha más un intervalo con horas da un timestamp),
+/// * fecha `±` entero (cantidad de días),
+/// * intervalo `±` intervalo,
+/// * fecha `-` fecha (cantidad de días), y timestamp `-` timestamp u hora `-` hora (un intervalo).
+///
+/// Devuelve `Some(Err(..))` con un error de tipo `ErrorType::Error` si la fecha resultante queda fuera del rango
+/// `0000-01-01` a `9999-12-31` o algún componente del intervalo no entra en 64 bits.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::{date_arithmetic, Date, Interval};
+/// use sql::extras::Value;
+///
+/// let date = Value::Date(Date::parse("2024-01-31").unwrap());
+/// let month = Value::Interval(Interval::parse("1 month").unwrap());
+/// let result = date_arithmetic(&date, &month, "+").unwrap().unwrap();
+/// assert_eq!(result.to_string(), "2024-02-29");
+///
+/// let years = Value::Interval(Interval::parse("9000 years").unwrap());
+/// assert!(date_arithmetic(&date, &years, "+").unwrap().is_err());
+/// ```
+pub fn date_arithmetic(
+    value1: &Value,
+    value2: &Value,
+    operator: &str,
+) -> Option<Result<Value, SqlError>> {
+    let sign = match operator {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let interval = |value: &Interval| {
+        if sign < 0 {
+            value.negate()
+        } else {
+            Some(*value)
+        }
+    };
+    let result = match (value1, value2) {
+        (Value::Timestamp(timestamp), Value::Interval(value)) => interval(value)
+            .and_then(|value| add_interval(*timestamp, &value))
+            .map(Value::Timestamp),
+        (Value::Date(date), Value::Interval(value)) => interval(value).and_then(|value| {
+            let result = add_interval(Timestamp::from(*date), &value)?;
+            if value.micros == 0 {
+                Some(Value::Date(result.date()))
+            } else {
+                Some(Value::Timestamp(result))
+            }
+        }),
+        (Value::Time(time), Value::Interval(value)) => interval(value)
+            .and_then(|value| time.micros().checked_add(value.micros))
+            .map(|micros| Value::Time(Time::from_micros(micros))),
+        (Value::Interval(value), Value::Timestamp(_) | Value::Date(_) | Value::Time(_))
+            if sign > 0 =>
+        {
+            return date_arithmetic(value2, &Value::Interval(*value), operator)
+        }
+        (Value::Interval(i1), Value::Interval(i2)) => {
+            interval(i2).and_then(|i2| i1.add(&i2)).map(Value::Interval)
+        }
+        (Value::Date(date), Value::Int(days)) => date
+            .days()
+            .checked_add(sign * i64::from(*days))
+            .and_then(Date::checked_from_days)
+            .map(Value::Date),
+        (Value::Date(d1), Value::Date(d2)) if sign < 0 => {
+            d1.days().checked_sub(d2.days()).map(integer_value)
+        }
+        (Value::Timestamp(_) | Value::Date(_), Value::Timestamp(_) | Value::Date(_))
+            if sign < 0 =>
+        {
+            let micros = to_timestamp(value1)?.micros() - to_timestamp(value2)?.micros();
+            Some(Value::Interval(Interval::new(
+                0,
+                micros / MICROS_PER_DAY,
+                micros % MICROS_PER_DAY,
+            )))
+        }
+        (Value::Time(t1), Value::Time(t2)) if sign < 0 => Some(Value::Interval(Interval::new(
+            0,
+            0,
+            t1.micros() - t2.micros(),
+        ))),
+        _ => return None,
+    };
+    Some(result.ok_or_else(|| {
+        SqlError::new(ErrorType::Error, "El resultado está fuera de rango")
+            .with_context(&format!("{} {} {}", value1, operator, value2))
+    }))
+}
+
+/// Implementa `NOW()`: devuelve el instante actual como un `Value::Timestamp`, en UTC.
+pub fn now() -> Value {
+    Value::Timestamp(Timestamp::now())
+}
+
+/// Implementa `DATE_TRUNC(unidad, valor)`: trunca una fecha o un timestamp al comienzo de la unidad indicada.
+///
+/// # Argumentos
+/// * `unit` - La unidad: `year`, `quarter`, `month`, `week` (las semanas empiezan el lunes), `day`, `hour`,
+///   `minute` o `second`, sin distinguir mayúsculas de minúsculas.
+/// * `value` - La fecha o el timestamp a truncar. Las cadenas con formato ISO-8601 también se aceptan.
+///
+/// # Retorno
+/// Devuelve un valor del mismo tipo que `value` (`NULL` si `value` es `NULL`), o un error de tipo
+/// `ErrorType::Error` si la unidad no existe o el valor no es una fecha.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::{date_trunc, Timestamp};
+/// use sql::extras::Value;
+///
+/// let value = Value::Timestamp(Timestamp::parse("2024-05-17 13:45:10").unwrap());
+/// assert_eq!(date_trunc("month", &value).unwrap().to_string(), "2024-05-01 00:00:00");
+/// assert_eq!(date_trunc("hour", &value).unwrap().to_string(), "2024-05-17 13:00:00");
+/// ```
+pub fn date_trunc(unit: &str, value: &Value) -> Result<Value, SqlError> {
+    let value = temporal_value(value);
+    let timestamp = match &value {
+        Value::Null => return Ok(Value::Null),
+        Value::Date(date) => Timestamp::from(*date),
+        Value::Timestamp(timestamp) => *timestamp,
+        _ => return Err(not_a_date("DATE_TRUNC", &value)),
+    };
+    let date = timestamp.date();
+    let micros = timestamp.time().micros();
+    let truncated = match unit.to_lowercase().as_str() {
+        "year" => Timestamp::from(start_of_year(date.year)),
+        "quarter" => Timestamp::from(Date {
+            month: (date.month - 1) / 3 * 3 + 1,
+            day: 1,
+            ..date
+        }),
+        "month" => Timestamp::from(Date { day: 1, ..date }),
+        "week" => Timestamp::from(Date::from_days(date.days() - date.weekday())),
+        "day" => Timestamp::from(date),
+        "hour" => truncate_time(date, micros, MICROS_PER_HOUR),
+        "minute" => truncate_time(date, micros, MICROS_PER_MINUTE),
+        "second" => truncate_time(date, micros, MICROS_PER_SECOND),
+        _ => return Err(unknown_unit(unit)),
+    };
+    match value {
+        Value::Date(_) => Ok(Value::Date(truncated.date())),
+        _ => Ok(Value::Timestamp(truncated)),
+    }
+}
+
+/// Implementa `EXTRACT(campo FROM valor)`: obtiene una parte de una fecha, una hora o un timestamp.
+///
+/// # Argumentos
+/// * `field` - El campo: `year`, `quarter`, `month`, `week` (semana ISO), `day`, `dow` (0 = domingo),
+///   `isodow` (1 = lunes), `doy`, `hour`, `minute`, `second` o `epoch`, sin distinguir mayúsculas de minúsculas.
+/// * `value` - La fecha, la hora o el timestamp. Las cadenas con formato ISO-8601 también se aceptan.
+///
+/// # Retorno
+/// Devuelve el campo como un entero (`second` y `epoch` son `Float` si tienen fracción de segundo, y `epoch`
+/// es `BigInt` si no entra en un `Int`), `NULL` si `value` es `NULL`, o un error de tipo `ErrorType::Error`
+/// si el campo no existe o no corresponde al tipo del valor.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::{extract, Date};
+/// use sql::extras::Value;
+///
+/// let value = Value::Date(Date::parse("2024-12-30").unwrap());
+/// assert_eq!(extract("year", &value).unwrap(), Value::Int(2024));
+/// assert_eq!(extract("week", &value).unwrap(), Value::Int(1));
+/// assert_eq!(extract("dow", &value).unwrap(), Value::Int(1));
+/// ```
+pub fn extract(field: &str, value: &Value) -> Result<Value, SqlError> {
+    let value = temporal_value(value);
+    let (date, micros) = match &value {
+        Value::Null => return Ok(Value::Null),
+        Value::Date(date) => (Some(*date), None),
+        Value::Time(time) => (None, Some(time.micros())),
+        Value::Timestamp(timestamp) => (Some(timestamp.date()), Some(timestamp.time().micros())),
+        _ => return Err(not_a_date("EXTRACT", &value)),
+    };
+    let field = field.to_lowercase();
+    let result = match (field.as_str(), date, micros) {
+        ("year", Some(date), _) => integer_value(date.year),
+        ("quarter", Some(date), _) => integer_value(i64::from((date.month - 1) / 3 + 1)),
+        ("month", Some(date), _) => integer_value(i64::from(date.month)),
+        ("week", Some(date), _) => integer_value(iso_week(date)),
+        ("day", Some(date), _) => integer_value(i64::from(date.day)),
+        ("dow", Some(date), _) => integer_value((date.weekday() + 1) % 7),
+        ("isodow", Some(date), _) => integer_value(date.weekday() + 1),
+        ("doy", Some(date), _) => integer_value(date.days() - start_of_year(date.year).days() + 1),
+        ("hour", _, Some(micros)) => integer_value(micros / MICROS_PER_HOUR),
+        ("minute", _, Some(micros)) => integer_value(micros % MICROS_PER_HOUR / MICROS_PER_MINUTE),
+        ("second", _, Some(micros)) => seconds(micros % MICROS_PER_MINUTE),
+        ("epoch", Some(date), micros) => date
+            .days()
+            .checked_mul(MICROS_PER_DAY)
+            .and_then(|days| days.checked_add(micros.unwrap_or(0)))
+            .map(seconds)
+            .ok_or_else(|| {
+                SqlError::new(ErrorType::Error, "El resultado está fuera de rango")
+                    .with_context(&value.to_string())
+            })?,
+        ("epoch", None, Some(micros)) => seconds(micros),
+        _ => return Err(unknown_unit(&field)),
+    };
+    Ok(result)
+}
+
+/// Suma un intervalo a un timestamp: primero los meses, luego los días y por último los microsegundos.
+/// Devuelve `None` si el resultado queda fuera del rango `0000-01-01` a `9999-12-31`.
+fn add_interval(timestamp: Timestamp, interval: &Interval) -> Option<Timestamp> {
+    let date = timestamp.date().add_months(interval.months)?;
+    let micros = timestamp.time().micros().checked_add(interval.micros)?;
+    let days = date
+        .days()
+        .checked_add(interval.days)?
+        .checked_add(micros.div_euclid(MICROS_PER_DAY))?;
+    Some(Timestamp::new(
+        Date::checked_from_days(days)?,
+        Time::from_micros(micros),
+    ))
+}
+
+/// Convierte una fecha o un timestamp en un timestamp.
+fn to_timestamp(value: &Value) -> Option<Timestamp> {
+    match value {
+        Value::Date(date) => Some(Timestamp::from(*date)),
+        Value::Timestamp(timestamp) => Some(*timestamp),
+        _ => None,
+    }
+}
+
+/// Trunca la hora de un timestamp a un múltiplo de `unit` microsegundos.
+fn truncate_time(date: Date, micros: i64, unit: i64) -> Timestamp {
+    Timestamp::new(date, Time::from_micros(micros - micros % unit))
+}
+
+/// Devuelve el 1 de enero de un año.
+fn start_of_year(year: i64) -> Date {
+    Date {
+        year,
+        month: 1,
+        day: 1,
+    }
+}
+
+/// Calcula el número de semana ISO-8601: la semana 1 es la que contiene el primer jueves del año.
+fn iso_week(date: Date) -> i64 {
+    let thursday = Date::from_days(date.days() - date.weekday() + 3);
+    (thursday.days() - start_of_year(thursday.year).days()) / 7 + 1
+}
+
+/// Convierte una cantidad de microsegundos en segundos: un entero si no tiene fracción, o un `Float`.
+fn seconds(micros: i64) -> Value {
+    if micros % MICROS_PER_SECOND == 0 {
+        integer_value(micros / MICROS_PER_SECOND)
+    } else {
+        Value::Float(micros as f64 / MICROS_PER_SECOND as f64)
+    }
+}
+
+/// Devuelve la cantidad de días de un mes.
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Convierte un texto formado solo por dígitos ASCII en un número.
+fn digits(s: &str) -> Option<i64> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parsea un desplazamiento horario `+HH`, `+HHMM` o `+HH:MM` (o con `-`) y lo devuelve en microsegundos.
+fn parse_offset(s: &str) -> Option<i64> {
+    let (sign, rest) = match s.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let rest = rest.replace(':', "");
+    let (hours, minutes) = match rest.len() {
+        2 => (digits(&rest)?, 0),
+        4 => (digits(&rest[..2])?, digits(&rest[2..])?),
+        _ => return None,
+    };
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * MICROS_PER_HOUR + minutes * MICROS_PER_MINUTE))
+}
+
+/// Agrega la `s` del plural a una unidad si la cantidad no es uno.
+fn plural(amount: &str, unit: &str, singular: bool) -> String {
+    if singular {
+        format!("{} {}", amount, unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
+
+/// Crea el error para una función de fechas que recibió un valor que no es una fecha.
+fn not_a_date(function: &str, value: &Value) -> SqlError {
+    SqlError::new(
+        ErrorType::Error,
+        &format!("{} espera una fecha, una hora o un timestamp", function),
+    )
+    .with_context(&value.to_string())
+}
+
+/// Crea el error para una unidad o un campo de fecha desconocido.
+fn unknown_unit(unit: &str) -> SqlError {
+    SqlError::new(ErrorType::Error, "Unidad de fecha no soportada").with_context(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(s: &str) -> Value {
+        Value::Timestamp(Timestamp::parse(s).unwrap())
+    }
+
+    fn interval(s: &str) -> Value {
+        Value::Interval(Interval::parse(s).unwrap())
+    }
+
+    fn arithmetic(value1: &Value, value2: &Value, operator: &str) -> Option<Value> {
+        date_arithmetic(value1, value2, operator).map(|result| result.unwrap())
+    }
+
+    #[test]
+    fn test_days_round_trip() {
+        for days in [-719_468, -1, 0, 1, 11_016, 19_782, 2_932_896] {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+        assert_eq!(Date::parse("1970-01-01").unwrap().days(), 0);
+        assert_eq!(Date::parse("2000-03-01").unwrap().days(), 11_017);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_dates_and_times() {
+        assert!(Date::parse("2024-13-01").is_none());
+        assert!(Date::parse("2024-1-01").is_none());
+        assert!(Date::parse("20240101").is_none());
+        assert!(Time::parse("12:60").is_none());
+        assert!(Time::parse("12:00:00.1234567").is_none());
+        assert!(Timestamp::parse("2024-01-01X10:00").is_none());
+        assert!(Timestamp::parse("2024-01-01 10:00+25:00").is_none());
+    }
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        let expected = "2024-01-15 10:30:00";
+        assert_eq!(timestamp("2024-01-15T10:30").to_string(), expected);
+        assert_eq!(timestamp("2024-01-15 10:30:00Z").to_string(), expected);
+        assert_eq!(timestamp("2024-01-15T12:30:00+0200").to_string(), expected);
+        assert_eq!(
+            timestamp("2024-01-15T10:30:00.250").to_string(),
+            "2024-01-15 10:30:00.25"
+        );
+    }
+
+    #[test]
+    fn test_interval_parse_and_display() {
+        assert_eq!(interval("2 weeks").to_string(), "14 days");
+        assert_eq!(
+            interval("-1 day 1.5 seconds").to_string(),
+            "-1 day 1.5 seconds"
+        );
+        assert_eq!(interval("0 hours").to_string(), "0 days");
+        assert!(Interval::parse("3").is_none());
+        assert!(Interval::parse("1.5 months").is_none());
+        assert!(Interval::parse("2 fortnights").is_none());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let date = Value::Date(Date::parse("2024-03-31").unwrap());
+        assert_eq!(
+            arithmetic(&date, &interval("1 month"), "-"),
+            Some(Value::Date(Date::parse("2024-02-29").unwrap()))
+        );
+        assert_eq!(
+            arithmetic(&date, &interval("12 hours"), "+"),
+            Some(timestamp("2024-03-31 12:00"))
+        );
+        assert_eq!(
+            arithmetic(&date, &Value::Int(1), "+"),
+            Some(Value::Date(Date::parse("2024-04-01").unwrap()))
+        );
+        assert_eq!(
+            arithmetic(&date, &Value::Date(Date::parse("2024-01-01").unwrap()), "-"),
+            Some(Value::Int(90))
+        );
+        assert_eq!(
+            arithmetic(
+                &timestamp("2024-01-02 06:00"),
+                &timestamp("2024-01-01 00:00"),
+                "-"
+            ),
+            Some(interval("1 day 6 hours"))
+        );
+        assert_eq!(
+            arithmetic(&interval("1 day"), &timestamp("2024-01-01 23:00"), "+"),
+            Some(timestamp("2024-01-02 23:00"))
+        );
+        let time = Value::Time(Time::parse("23:30").unwrap());
+        assert_eq!(
+            arithmetic(&time, &interval("45 minutes"), "+"),
+            Some(Value::Time(Time::parse("00:15").unwrap()))
+        );
+        assert_eq!(arithmetic(&interval("1 day"), &date, "-"), None);
+        assert_eq!(arithmetic(&date, &Value::Str("x".to_string()), "+"), None);
+    }
+
+    #[test]
+    fn test_interval_parse_overflow() {
+        assert!(Interval::parse("999999999999999999 years").is_none());
+        assert!(Interval::parse("9223372036854775807 days 1 day").is_none());
+        assert!(Interval::parse("9999999999999 hours").is_none());
+        assert!(Interval::parse("-9223372036854775808 days").is_some());
+    }
+
+    #[test]
+    fn test_date_arithmetic_out_of_range() {
+        let date = Value::Date(Date::parse("2024-01-01").unwrap());
+        let out_of_range = |value1: &Value, value2: &Value, operator: &str| {
+            date_arithmetic(value1, value2, operator)
+                .unwrap()
+                .map_err(|error| error.kind())
+        };
+        assert_eq!(
+            out_of_range(&date, &interval("999999999999 days"), "+"),
+            Err(ErrorType::Error)
+        );
+        assert_eq!(
+            out_of_range(
+                &timestamp("2024-01-01 00:00"),
+                &interval("2562047788 hours"),
+                "+"
+            ),
+            Err(ErrorType::Error)
+        );
+        assert_eq!(
+            out_of_range(&date, &interval("-9223372036854775808 days"), "-"),
+            Err(ErrorType::Error)
+        );
+        assert_eq!(
+            out_of_range(&date, &interval("2025 years"), "-"),
+            Err(ErrorType::Error)
+        );
+        let time = Value::Time(Time::parse("10:00").unwrap());
+        assert_eq!(
+            out_of_range(&time, &interval("9223372036854 seconds"), "+"),
+            Err(ErrorType::Error)
+        );
+        assert_eq!(
+            arithmetic(&date, &interval("7975 years 11 months 30 days"), "+"),
+            Some(Value::Date(Date::parse("9999-12-31").unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_compare_temporal() {
+        let date = Value::Date(Date::parse("2024-01-01").unwrap());
+        assert_eq!(
+            compare_temporal(&date, &timestamp("2024-01-01 00:00")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare_temporal(&date, &timestamp("2023-12-31 23:59")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_temporal(&interval("1 month"), &interval("29 days")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare_temporal(&date, &Value::Int(1)), None);
+    }
+
+    #[test]
+    fn test_date_trunc() {
+        let value = timestamp("2024-08-17 13:45:10.5");
+        let trunc = |unit: &str| date_trunc(unit, &value).unwrap().to_string();
+        assert_eq!(trunc("YEAR"), "2024-01-01 00:00:00");
+        assert_eq!(trunc("quarter"), "2024-07-01 00:00:00");
+        assert_eq!(trunc("week"), "2024-08-12 00:00:00");
+        assert_eq!(trunc("day"), "2024-08-17 00:00:00");
+        assert_eq!(trunc("minute"), "2024-08-17 13:45:00");
+        assert_eq!(trunc("second"), "2024-08-17 13:45:10");
+
+        let date = Value::Str("2024-08-17".to_string());
+        assert_eq!(
+            date_trunc("month", &date).unwrap(),
+            Value::Date(Date::parse("2024-08-01").unwrap())
+        );
+        assert_eq!(date_trunc("month", &Value::Null).unwrap(), Value::Null);
+        assert!(date_trunc("decade", &value).is_err());
+        assert!(date_trunc("day", &Value::Int(3)).is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let value = timestamp("2021-01-03 07:08:09.5");
+        let field = |name: &str| extract(name, &value).unwrap();
+        assert_eq!(field("year"), Value::Int(2021));
+        assert_eq!(field("quarter"), Value::Int(1));
+        assert_eq!(field("week"), Value::Int(53));
+        assert_eq!(field("dow"), Value::Int(0));
+        assert_eq!(field("isodow"), Value::Int(7));
+        assert_eq!(field("doy"), Value::Int(3));
+        assert_eq!(field("hour"), Value::Int(7));
+        assert_eq!(field("second"), Value::Float(9.5));
+        assert_eq!(
+            extract("epoch", &timestamp("2001-09-09 01:46:40")).unwrap(),
+            Value::Int(1_000_000_000)
+        );
+        let time = Value::Time(Time::parse("10:20").unwrap());
+        assert_eq!(extract("minute", &time).unwrap(), Value::Int(20));
+        assert!(extract("year", &time).is_err());
+    }
+
+    #[test]
+    fn test_now_is_after_2024() {
+        let now = Timestamp::now();
+        assert!(now > Timestamp::parse("2024-01-01 00:00").unwrap());
+    }
+}
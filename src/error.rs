@@ -1,4 +1,6 @@
-#[derive(Debug, PartialEq)]
+use std::{error, fmt, io};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 
 /// Representa los diferentes tipos de errores que pueden ocurrir.
 ///
@@ -14,37 +16,172 @@ pub enum ErrorType {
     Error,
 }
 
-/// Imprime un mensaje de error en la salida estándar de error (`stderr`) basado en el tipo de error recibido.
+/// Muestra la etiqueta con la que se imprime cada tipo de error.
+impl fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorType::InvalidTable => write!(f, "INVALID_TABLE"),
+            ErrorType::InvalidColumn => write!(f, "INVALID_COLUMN"),
+            ErrorType::InvalidSyntax => write!(f, "INVALID_SYNTAX"),
+            ErrorType::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+#[derive(Debug)]
+
+/// Representa un error ocurrido al parsear o ejecutar una consulta, con los parámetros:
+///
+/// * `kind` - El tipo de error (`ErrorType`).
+/// * `message` - La descripción del error.
+/// * `context` - El token, columna o tabla que provocó el error, si se conoce.
+/// * `position` - La posición (en bytes) dentro de la consulta donde se detectó el error, si se conoce.
+/// * `source` - El error de entrada/salida que lo originó, si lo hay.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::error::{ErrorType, SqlError};
+///
+/// let error = SqlError::new(ErrorType::InvalidColumn, "La columna no existe").with_context("precio");
+/// assert_eq!(error.kind(), ErrorType::InvalidColumn);
+/// assert_eq!(error.to_string(), "[INVALID_COLUMN]: [La columna no existe: 'precio']");
+/// ```
+pub struct SqlError {
+    kind: ErrorType,
+    message: String,
+    context: Option<String>,
+    position: Option<usize>,
+    source: Option<io::Error>,
+}
+
+impl SqlError {
+    /// Crea un error del tipo indicado con su descripción.
+    pub fn new(kind: ErrorType, message: &str) -> Self {
+        SqlError {
+            kind,
+            message: message.to_string(),
+            context: None,
+            position: None,
+            source: None,
+        }
+    }
+
+    /// Indica el token, columna o tabla que provocó el error.
+    pub fn with_context(mut self, context: &str) -> Self {
+        self.context = Some(context.to_string());
+        self
+    }
+
+    /// Indica la posición (en bytes) de la consulta donde se detectó el error.
+    pub fn with_position(mut self, position: usize) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Indica el error de entrada/salida que originó el error.
+    pub fn with_source(mut self, source: io::Error) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Devuelve el tipo de error.
+    pub fn kind(&self) -> ErrorType {
+        self.kind
+    }
+
+    /// Devuelve la descripción del error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Devuelve el token, columna o tabla que provocó el error, si se conoce.
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Devuelve la posición de la consulta donde se detectó el error, si se conoce.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+}
+
+/// Muestra el error con el formato `[TIPO]: [descripción: 'contexto' (posición N): causa]`,
+/// omitiendo las partes que no se conocen.
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}]: [{}", self.kind, self.message)?;
+        if let Some(context) = &self.context {
+            write!(f, ": '{}'", context)?;
+        }
+        if let Some(position) = self.position {
+            write!(f, " (posición {})", position)?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, ": {}", source)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl error::Error for SqlError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn error::Error + 'static))
+    }
+}
+
+/// Imprime un error en la salida estándar de error (`stderr`).
 ///
 /// # Argumentos
-/// * `error_type` - El tipo de error (`ErrorType`) que ocurrió.
-/// * `description` - Una descripción adicional sobre el error.
+/// * `error` - El error (`SqlError`) que ocurrió.
 ///
 /// # Ejemplo
 /// ```rust
-/// use sql::error::{print_error, ErrorType};
+/// use sql::error::{print_error, ErrorType, SqlError};
 /// // Error por tabla inválida
-/// print_error(ErrorType::InvalidTable, "La tabla 'usuarios' no existe");
-///
-/// // Error por columna inválida
-/// print_error(ErrorType::InvalidColumn, "La columna 'nombre' no existe en la tabla 'usuarios'");
+/// print_error(&SqlError::new(ErrorType::InvalidTable, "La tabla no existe").with_context("usuarios"));
 ///
 /// // Error de sintaxis
-/// print_error(ErrorType::InvalidSyntax, "Error de sintaxis en la cláusula WHERE");
-///
-/// // Error general
-/// print_error(ErrorType::Error, "Error desconocido durante la ejecución");
+/// print_error(&SqlError::new(ErrorType::InvalidSyntax, "Falta 'FROM'").with_position(14));
 /// ```
 ///
 /// # Notas
 /// Esta función usa `eprintln!` para imprimir los errores en `stderr`, lo cual es útil en
 /// aplicaciones de línea de comandos para diferenciar la salida normal de los mensajes de error.
 ///
-pub fn print_error(error_type: ErrorType, description: &str) {
-    match error_type {
-        ErrorType::InvalidTable => eprintln!("[INVALID_TABLE]: [{}]", description),
-        ErrorType::InvalidColumn => eprintln!("[INVALID_COLUMN]: [{}]", description),
-        ErrorType::InvalidSyntax => eprintln!("[INVALID_SYNTAX]: [{}]", description),
-        ErrorType::Error => eprintln!("[ERROR]: [{}]", description),
+pub fn print_error(error: &SqlError) {
+    eprintln!("{}", error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_display_with_position() {
+        let error = SqlError::new(ErrorType::InvalidSyntax, "Token inesperado")
+            .with_context(")")
+            .with_position(12);
+        assert_eq!(
+            error.to_string(),
+            "[INVALID_SYNTAX]: [Token inesperado: ')' (posición 12)]"
+        );
+        assert_eq!(error.position(), Some(12));
+        assert_eq!(error.context(), Some(")"));
+    }
+
+    #[test]
+    fn test_display_with_source() {
+        let source = io::Error::new(io::ErrorKind::NotFound, "no such file");
+        let error = SqlError::new(ErrorType::InvalidTable, "No se pudo abrir el archivo")
+            .with_context("ordenes.csv")
+            .with_source(source);
+        assert_eq!(
+            error.to_string(),
+            "[INVALID_TABLE]: [No se pudo abrir el archivo: 'ordenes.csv': no such file]"
+        );
+        assert!(error.source().is_some());
     }
 }
@@ -0,0 +1,1262 @@
+use std::fmt;
+
+use crate::{
+    aggregate::{Aggregate, AggregateFunction},
+    datetime::{Date, Interval, Time, Timestamp},
+    error::{ErrorType, SqlError},
+    extras::{cast_to_value, find_column, Value},
+    functions::{call_function, lookup},
+    lexer::{Token, TokenKind},
+    operations::{arithmetic, filter, like},
+};
+
+/// Operadores de comparación que pueden aparecer entre dos operandos.
+const COMPARISON_OPERATORS: [&str; 7] = ["=", "<", ">", "<=", ">=", "!=", "<>"];
+
+/// Predicados que pueden seguir a un operando, opcionalmente precedidos por `NOT`.
+const PREDICATES: [&str; 4] = ["like", "ilike", "in", "between"];
+
+/// Operadores aritméticos de suma y resta, que ligan más débil que los de multiplicación.
+const ADDITIVE_OPERATORS: [&str; 2] = ["+", "-"];
+
+/// Operadores aritméticos de multiplicación, división y resto.
+const MULTIPLICATIVE_OPERATORS: [&str; 3] = ["*", "/", "%"];
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa una expresión de la cláusula `WHERE` como un árbol.
+///
+/// # Variantes
+/// * `Column` - Referencia al valor de una columna de la fila, opcionalmente calificada con su tabla (`c.nombre`).
+/// * `Literal` - Un valor constante.
+/// * `Compare` - Comparación entre dos expresiones con un operador (`=`, `<`, `<>`, ...).
+/// * `And` - Conjunción de dos condiciones.
+/// * `Or` - Disyunción de dos condiciones.
+/// * `Not` - Negación de una condición.
+/// * `IsNull` - El predicado `IS NULL` (o `IS NOT NULL` si `negated` es `true`).
+/// * `Like` - El predicado `LIKE` (o `ILIKE` si `case_insensitive` es `true`), con un `ESCAPE` opcional.
+/// * `InList` - El predicado `IN (lista)` (o `NOT IN`).
+/// * `Between` - El predicado `BETWEEN low AND high` (o `NOT BETWEEN`), que incluye ambos extremos.
+/// * `Arithmetic` - Operación aritmética (`+`, `-`, `*`, `/`, `%`) o concatenación (`||`) entre dos expresiones
+///   (por ejemplo, `cantidad * 2` o una fecha más un intervalo).
+/// * `Function` - Llamada a una función escalar del registro de `functions`, como `UPPER(nombre)`, `NOW()`,
+///   `DATE_TRUNC('month', fecha)` o `EXTRACT(YEAR FROM fecha)`.
+/// * `Aggregate` - Una función de agregación, como `COUNT(*)`. Solo tiene valor en la cláusula `HAVING`,
+///   donde la fila agrupada tiene una columna por cada agregación.
+///
+/// # Notas
+/// El parseo respeta la precedencia habitual de SQL: `NOT` liga más fuerte que `AND`,
+/// y `AND` más fuerte que `OR`. Entre operandos, `*`, `/` y `%` ligan más fuerte que `+` y `-`, y estos más
+/// fuerte que `||`. Los paréntesis permiten alterar ese orden.
+/// Las condiciones se evalúan con la lógica de tres valores de SQL (ver `Expr::truth_value`).
+pub enum Expr {
+    Column(String),
+    Literal(Value),
+    Compare {
+        left: Box<Expr>,
+        operator: String,
+        right: Box<Expr>,
+    },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    Like {
+        expr: Box<Expr>,
+        pattern: Box<Expr>,
+        escape: Option<Box<Expr>>,
+        case_insensitive: bool,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool,
+    },
+    Arithmetic {
+        left: Box<Expr>,
+        operator: String,
+        right: Box<Expr>,
+    },
+    Function {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Aggregate(Aggregate),
+}
+
+impl Expr {
+    /// Calcula el valor de la expresión para una fila.
+    ///
+    /// # Argumentos
+    /// * `row` - Los valores tipados de la fila (ver `schema::parse_row`).
+    /// * `headers` - Los nombres de las columnas de la tabla.
+    ///
+    /// # Retorno
+    /// Devuelve el `Value` de la columna, del literal, de la operación o de la función, o un error de tipo
+    /// `ErrorType::InvalidColumn` si la columna no existe (o de tipo `ErrorType::Error` si una operación o una
+    /// función no admite sus valores). Las condiciones no tienen un valor propio, por lo que devuelven un error
+    /// de tipo `ErrorType::InvalidSyntax`.
+    ///
+    pub fn evaluate(&self, row: &[Value], headers: &[&str]) -> Result<Value, SqlError> {
+        match self {
+            Expr::Column(name) => Ok(row[find_column(headers, name)?].clone()),
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Arithmetic {
+                left,
+                operator,
+                right,
+            } => arithmetic(
+                left.evaluate(row, headers)?,
+                right.evaluate(row, headers)?,
+                operator,
+            ),
+            Expr::Function { name, args } => {
+                let values = args
+                    .iter()
+                    .map(|arg| arg.evaluate(row, headers))
+                    .collect::<Result<Vec<Value>, SqlError>>()?;
+                call_function(name, &values)
+            }
+            Expr::Aggregate(aggregate) => {
+                let label = aggregate.to_string();
+                match headers.iter().position(|header| *header == label) {
+                    Some(index) => Ok(row[index].clone()),
+                    None => Err(SqlError::new(
+                        ErrorType::InvalidSyntax,
+                        "Las funciones de agregación solo pueden usarse en SELECT o HAVING",
+                    )
+                    .with_context(&label)),
+                }
+            }
+            _ => Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "Se esperaba una columna o un valor, no una condición",
+            )),
+        }
+    }
+
+    /// Devuelve las columnas que aparecen en la expresión fuera de las funciones de agregación, en el orden
+    /// en que aparecen.
+    pub fn columns(&self) -> Vec<&str> {
+        match self {
+            Expr::Column(name) => vec![name.as_str()],
+            Expr::Literal(_) | Expr::Aggregate(_) => Vec::new(),
+            Expr::Compare { left, right, .. }
+            | Expr::Arithmetic { left, right, .. }
+            | Expr::And(left, right)
+            | Expr::Or(left, right) => {
+                let mut columns = left.columns();
+                columns.extend(right.columns());
+                columns
+            }
+            Expr::Not(expr) | Expr::IsNull { expr, .. } => expr.columns(),
+            Expr::Function { args, .. } => args.iter().flat_map(Expr::columns).collect(),
+            predicate => predicate
+                .operands()
+                .into_iter()
+                .flat_map(Expr::columns)
+                .collect(),
+        }
+    }
+
+    /// Devuelve las funciones de agregación que aparecen en la expresión, en el orden en que aparecen.
+    pub fn aggregates(&self) -> Vec<&Aggregate> {
+        match self {
+            Expr::Aggregate(aggregate) => vec![aggregate],
+            Expr::Column(_) | Expr::Literal(_) => Vec::new(),
+            Expr::Compare { left, right, .. }
+            | Expr::Arithmetic { left, right, .. }
+            | Expr::And(left, right)
+            | Expr::Or(left, right) => {
+                let mut aggregates = left.aggregates();
+                aggregates.extend(right.aggregates());
+                aggregates
+            }
+            Expr::Not(expr) | Expr::IsNull { expr, .. } => expr.aggregates(),
+            Expr::Function { args, .. } => args.iter().flat_map(Expr::aggregates).collect(),
+            predicate => predicate
+                .operands()
+                .into_iter()
+                .flat_map(Expr::aggregates)
+                .collect(),
+        }
+    }
+
+    /// Devuelve los operandos de los predicados `LIKE`, `IN` y `BETWEEN`, en el orden en que aparecen.
+    /// Para el resto de las expresiones devuelve un vector vacío.
+    fn operands(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Like {
+                expr,
+                pattern,
+                escape,
+                ..
+            } => [expr, pattern]
+                .into_iter()
+                .chain(escape)
+                .map(|operand| operand.as_ref())
+                .collect(),
+            Expr::InList { expr, list, .. } => std::iter::once(expr.as_ref()).chain(list).collect(),
+            Expr::Between {
+                expr, low, high, ..
+            } => vec![expr, low, high],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Evalúa la expresión como condición sobre una fila.
+    ///
+    /// # Argumentos
+    /// * `row` - Los valores tipados de la fila.
+    /// * `headers` - Los nombres de las columnas de la tabla.
+    ///
+    /// # Retorno
+    /// Devuelve `true` si la fila cumple la condición, `false` si no la cumple o si el resultado es desconocido,
+    /// o un `SqlError` si la condición referencia una columna inexistente o no es una condición.
+    ///
+    pub fn is_true(&self, row: &[Value], headers: &[&str]) -> Result<bool, SqlError> {
+        Ok(self.truth_value(row, headers)? == Some(true))
+    }
+
+    /// Evalúa la expresión como condición con la lógica de tres valores de SQL.
+    ///
+    /// # Argumentos
+    /// * `row` - Los valores tipados de la fila.
+    /// * `headers` - Los nombres de las columnas de la tabla.
+    ///
+    /// # Retorno
+    /// Devuelve `Some(true)` o `Some(false)` si el resultado se conoce, `None` si es desconocido (por ejemplo,
+    /// al comparar con `NULL`), o un `SqlError` si la condición referencia una columna inexistente o no es una condición.
+    ///
+    /// # Notas
+    /// `AND` es falso si algún lado es falso, y `OR` es verdadero si algún lado es verdadero, aunque el otro
+    /// sea desconocido; ambos se evalúan en cortocircuito. `NOT` de un valor desconocido sigue siendo desconocido.
+    /// `IS NULL` e `IS NOT NULL` siempre tienen un resultado conocido. `x IN (lista)` es verdadero si `x` es igual
+    /// a algún elemento, y desconocido si no lo es pero la lista tiene un `NULL`; `BETWEEN` equivale a
+    /// `x >= low AND x <= high`. Una columna, un literal o una función
+    /// pueden usarse como condición si su valor es booleano (o `NULL`, que es desconocido). Una columna con un
+    /// valor de texto se compara como texto (ver `comparable_values`).
+    ///
+    pub fn truth_value(&self, row: &[Value], headers: &[&str]) -> Result<Option<bool>, SqlError> {
+        match self {
+            Expr::Compare {
+                left,
+                operator,
+                right,
+            } => {
+                let (value1, value2) = comparable_values(
+                    left,
+                    left.evaluate(row, headers)?,
+                    right,
+                    right.evaluate(row, headers)?,
+                );
+                filter(value1, value2, operator)
+            }
+            Expr::And(left, right) => match left.truth_value(row, headers)? {
+                Some(false) => Ok(Some(false)),
+                left => Ok(match (left, right.truth_value(row, headers)?) {
+                    (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                }),
+            },
+            Expr::Or(left, right) => match left.truth_value(row, headers)? {
+                Some(true) => Ok(Some(true)),
+                left => Ok(match (left, right.truth_value(row, headers)?) {
+                    (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                }),
+            },
+            Expr::Not(expr) => Ok(expr.truth_value(row, headers)?.map(|value| !value)),
+            Expr::IsNull { expr, negated } => {
+                Ok(Some(expr.evaluate(row, headers)?.is_null() != *negated))
+            }
+            Expr::Like {
+                expr,
+                pattern,
+                escape,
+                case_insensitive,
+                negated,
+            } => {
+                let escape = match escape {
+                    Some(escape) => Some(escape.evaluate(row, headers)?),
+                    None => None,
+                };
+                let matches = like(
+                    expr.evaluate(row, headers)?,
+                    pattern.evaluate(row, headers)?,
+                    escape,
+                    *case_insensitive,
+                )?;
+                Ok(matches.map(|matches| matches != *negated))
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let value = expr.evaluate(row, headers)?;
+                let mut found = Some(false);
+                for item in list {
+                    let (value, item_value) =
+                        comparable_values(expr, value.clone(), item, item.evaluate(row, headers)?);
+                    match filter(value, item_value, "=")? {
+                        Some(true) => {
+                            found = Some(true);
+                            break;
+                        }
+                        Some(false) => {}
+                        None => found = None,
+                    }
+                }
+                Ok(found.map(|found| found != *negated))
+            }
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                let value = expr.evaluate(row, headers)?;
+                let (low_value, low) =
+                    comparable_values(expr, value.clone(), low, low.evaluate(row, headers)?);
+                let (high_value, high) =
+                    comparable_values(expr, value, high, high.evaluate(row, headers)?);
+                let inside = match (
+                    filter(low_value, low, ">=")?,
+                    filter(high_value, high, "<=")?,
+                ) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                };
+                Ok(inside.map(|inside| inside != *negated))
+            }
+            _ => match self.evaluate(row, headers)? {
+                Value::Bool(value) => Ok(Some(value)),
+                Value::Null => Ok(None),
+                _ => Err(SqlError::new(
+                    ErrorType::InvalidSyntax,
+                    "Se esperaba una condición en la cláusula WHERE",
+                )),
+            },
+        }
+    }
+}
+
+/// Muestra la expresión como se escribe en SQL, con paréntesis solo donde la precedencia los necesita.
+/// Es el nombre de las columnas calculadas sin alias en el resultado (por ejemplo, `cantidad * 2`).
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precedence = self.precedence();
+        // Los operandos de la derecha también llevan paréntesis con la misma precedencia, porque los
+        // operadores se agrupan de izquierda a derecha.
+        let operand = |expr: &Expr, right: bool| {
+            if expr.precedence() < precedence || (right && expr.precedence() == precedence) {
+                format!("({})", expr)
+            } else {
+                expr.to_string()
+            }
+        };
+        match self {
+            Expr::Column(name) => write!(f, "{}", name),
+            Expr::Literal(value) => write_literal(f, value),
+            Expr::Compare {
+                left,
+                operator,
+                right,
+            }
+            | Expr::Arithmetic {
+                left,
+                operator,
+                right,
+            } => write!(
+                f,
+                "{} {} {}",
+                operand(left, false),
+                operator.to_uppercase(),
+                operand(right, true)
+            ),
+            Expr::And(left, right) => {
+                write!(f, "{} AND {}", operand(left, false), operand(right, true))
+            }
+            Expr::Or(left, right) => {
+                write!(f, "{} OR {}", operand(left, false), operand(right, true))
+            }
+            Expr::Not(expr) => write!(f, "NOT {}", operand(expr, false)),
+            Expr::IsNull { expr, negated } => write!(
+                f,
+                "{} IS {}NULL",
+                operand(expr, false),
+                if *negated { "NOT " } else { "" }
+            ),
+            Expr::Like {
+                expr,
+                pattern,
+                escape,
+                case_insensitive,
+                negated,
+            } => {
+                write!(
+                    f,
+                    "{} {}{} {}",
+                    operand(expr, false),
+                    if *negated { "NOT " } else { "" },
+                    if *case_insensitive { "ILIKE" } else { "LIKE" },
+                    operand(pattern, true)
+                )?;
+                match escape {
+                    Some(escape) => write!(f, " ESCAPE {}", operand(escape, true)),
+                    None => Ok(()),
+                }
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let list: Vec<String> = list.iter().map(Expr::to_string).collect();
+                write!(
+                    f,
+                    "{} {}IN ({})",
+                    operand(expr, false),
+                    if *negated { "NOT " } else { "" },
+                    list.join(", ")
+                )
+            }
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => write!(
+                f,
+                "{} {}BETWEEN {} AND {}",
+                operand(expr, false),
+                if *negated { "NOT " } else { "" },
+                operand(low, true),
+                operand(high, true)
+            ),
+            Expr::Function { name, args } => match (name.as_str(), args.as_slice()) {
+                ("extract", [Expr::Literal(Value::Str(field)), value]) => {
+                    write!(f, "EXTRACT({} FROM {})", field.to_uppercase(), value)
+                }
+                _ => {
+                    let args: Vec<String> = args.iter().map(Expr::to_string).collect();
+                    write!(f, "{}({})", name.to_uppercase(), args.join(", "))
+                }
+            },
+            Expr::Aggregate(aggregate) => write!(f, "{}", aggregate),
+        }
+    }
+}
+
+impl Expr {
+    /// Devuelve la precedencia del operador principal de la expresión (mayor liga más fuerte).
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(_, _) => 1,
+            Expr::And(_, _) => 2,
+            Expr::Not(_) => 3,
+            Expr::Compare { .. }
+            | Expr::IsNull { .. }
+            | Expr::Like { .. }
+            | Expr::InList { .. }
+            | Expr::Between { .. } => 4,
+            Expr::Arithmetic { operator, .. } if operator == "||" => 5,
+            Expr::Arithmetic { operator, .. }
+                if ADDITIVE_OPERATORS.contains(&operator.as_str()) =>
+            {
+                6
+            }
+            Expr::Arithmetic { .. } => 7,
+            _ => 8,
+        }
+    }
+}
+
+/// Escribe un valor como literal de SQL: las cadenas entre comillas simples y las fechas con su tipo.
+fn write_literal(f: &mut fmt::Formatter, value: &Value) -> fmt::Result {
+    match value {
+        Value::Str(s) => write!(f, "'{}'", s.replace('\'', "''")),
+        Value::Bool(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+        Value::Date(_) => write!(f, "DATE '{}'", value),
+        Value::Time(_) => write!(f, "TIME '{}'", value),
+        Value::Timestamp(_) => write!(f, "TIMESTAMP '{}'", value),
+        Value::Interval(_) => write!(f, "INTERVAL '{}'", value),
+        value => write!(f, "{}", value),
+    }
+}
+
+/// Parsea una secuencia de tokens como una única expresión.
+///
+/// # Argumentos
+/// * `tokens` - Los tokens de la expresión (por ejemplo, los que siguen a `WHERE`).
+///
+/// # Retorno
+/// Devuelve el árbol `Expr` de la expresión, o un error de tipo `ErrorType::InvalidSyntax` si la expresión
+/// está incompleta, tiene paréntesis desbalanceados o le sobran tokens.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::expression::{parse_expression, Expr};
+/// use sql::lexer::tokenize;
+///
+/// let tokens = tokenize("a = 1 OR b = 2 AND c = 3").unwrap();
+/// let expr = parse_expression(&tokens).unwrap();
+/// assert!(matches!(expr, Expr::Or(_, _)));
+/// ```
+pub fn parse_expression(tokens: &[Token]) -> Result<Expr, SqlError> {
+    let mut parser = ExpressionParser { tokens, index: 0 };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        return Err(unexpected_token(token));
+    }
+    Ok(expr)
+}
+
+/// Parser descendente recursivo de expresiones. Cada nivel de precedencia tiene su propio método.
+struct ExpressionParser<'a> {
+    tokens: &'a [Token],
+    index: usize,
+}
+
+impl ExpressionParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.index);
+        self.index += 1;
+        token
+    }
+
+    fn next_is_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_keyword(keyword))
+    }
+
+    /// `or_expr := and_expr (OR and_expr)*`
+    fn parse_or(&mut self) -> Result<Expr, SqlError> {
+        let mut expr = self.parse_and()?;
+        while self.next_is_keyword("or") {
+            self.index += 1;
+            let right = self.parse_and()?;
+            expr = Expr::Or(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    /// `and_expr := not_expr (AND not_expr)*`
+    fn parse_and(&mut self) -> Result<Expr, SqlError> {
+        let mut expr = self.parse_not()?;
+        while self.next_is_keyword("and") {
+            self.index += 1;
+            let right = self.parse_not()?;
+            expr = Expr::And(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    /// `not_expr := NOT not_expr | comparison`
+    fn parse_not(&mut self) -> Result<Expr, SqlError> {
+        if self.next_is_keyword("not") {
+            self.index += 1;
+            let expr = self.parse_not()?;
+            return Ok(Expr::Not(Box::new(expr)));
+        }
+        self.parse_comparison()
+    }
+
+    /// `comparison := concat (operador concat | IS [NOT] NULL | [NOT] predicado)?`, donde el predicado es
+    /// `LIKE`, `ILIKE`, `IN` o `BETWEEN`.
+    fn parse_comparison(&mut self) -> Result<Expr, SqlError> {
+        let left = self.parse_concat()?;
+        if self.next_is_keyword("is") {
+            self.index += 1;
+            let negated = self.next_is_keyword("not");
+            if negated {
+                self.index += 1;
+            }
+            return match self.next() {
+                Some(token) if token.is_keyword("null") => Ok(Expr::IsNull {
+                    expr: Box::new(left),
+                    negated,
+                }),
+                Some(token) => Err(unexpected_token(token)),
+                None => Err(SqlError::new(
+                    ErrorType::InvalidSyntax,
+                    "Se esperaba 'NULL' luego de 'IS'",
+                )),
+            };
+        }
+        let negated = self.next_is_keyword("not")
+            && self
+                .tokens
+                .get(self.index + 1)
+                .is_some_and(|token| PREDICATES.iter().any(|k| token.is_keyword(k)));
+        if negated {
+            self.index += 1;
+        }
+        if PREDICATES.iter().any(|k| self.next_is_keyword(k)) {
+            return self.parse_predicate(left, negated);
+        }
+        let operator = match self.peek() {
+            Some(token)
+                if token.kind == TokenKind::Operator
+                    && COMPARISON_OPERATORS.contains(&token.value.as_str()) =>
+            {
+                token.value.to_string()
+            }
+            _ => return Ok(left),
+        };
+        self.index += 1;
+        let right = self.parse_concat()?;
+        Ok(Expr::Compare {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        })
+    }
+
+    /// `predicate := (LIKE | ILIKE) concat [ESCAPE concat] | IN '(' concat (',' concat)* ')'
+    ///             | BETWEEN concat AND concat`
+    fn parse_predicate(&mut self, left: Expr, negated: bool) -> Result<Expr, SqlError> {
+        let Some(keyword) = self.next().map(|token| token.value.to_lowercase()) else {
+            return Err(incomplete_expression());
+        };
+        let expr = Box::new(left);
+        match keyword.as_str() {
+            "in" => {
+                let open = self.next().cloned();
+                match open {
+                    Some(token) if token.kind == TokenKind::LeftParen => {
+                        let mut list = vec![self.parse_concat()?];
+                        while self.peek().map(|token| &token.kind) == Some(&TokenKind::Comma) {
+                            self.index += 1;
+                            list.push(self.parse_concat()?);
+                        }
+                        match self.next() {
+                            Some(close) if close.kind == TokenKind::RightParen => {
+                                Ok(Expr::InList {
+                                    expr,
+                                    list,
+                                    negated,
+                                })
+                            }
+                            _ => Err(SqlError::new(
+                                ErrorType::InvalidSyntax,
+                                "Falta ')' para el paréntesis abierto",
+                            )
+                            .with_position(token.position)),
+                        }
+                    }
+                    Some(token) => Err(unexpected_token(&token)),
+                    None => Err(incomplete_expression()),
+                }
+            }
+            "between" => {
+                let low = Box::new(self.parse_concat()?);
+                match self.next() {
+                    Some(token) if token.is_keyword("and") => {}
+                    Some(token) => return Err(unexpected_token(token)),
+                    None => return Err(incomplete_expression()),
+                }
+                let high = Box::new(self.parse_concat()?);
+                Ok(Expr::Between {
+                    expr,
+                    low,
+                    high,
+                    negated,
+                })
+            }
+            _ => {
+                let pattern = Box::new(self.parse_concat()?);
+                let escape = if self.next_is_keyword("escape") {
+                    self.index += 1;
+                    Some(Box::new(self.parse_concat()?))
+                } else {
+                    None
+                };
+                Ok(Expr::Like {
+                    expr,
+                    pattern,
+                    escape,
+                    case_insensitive: keyword == "ilike",
+                    negated,
+                })
+            }
+        }
+    }
+
+    /// `concat := additive ('||' additive)*`
+    fn parse_concat(&mut self) -> Result<Expr, SqlError> {
+        self.parse_binary(&["||"], Self::parse_additive)
+    }
+
+    /// `additive := multiplicative (('+' | '-') multiplicative)*`
+    fn parse_additive(&mut self) -> Result<Expr, SqlError> {
+        self.parse_binary(&ADDITIVE_OPERATORS, Self::parse_multiplicative)
+    }
+
+    /// `multiplicative := operand (('*' | '/' | '%') operand)*`
+    fn parse_multiplicative(&mut self) -> Result<Expr, SqlError> {
+        self.parse_binary(&MULTIPLICATIVE_OPERATORS, Self::parse_operand)
+    }
+
+    /// Parsea una secuencia de operandos (de un nivel de precedencia más alto, con `parse_next`) unidos por
+    /// alguno de los operadores `operators`, agrupándolos de izquierda a derecha.
+    fn parse_binary(
+        &mut self,
+        operators: &[&str],
+        parse_next: fn(&mut Self) -> Result<Expr, SqlError>,
+    ) -> Result<Expr, SqlError> {
+        let mut expr = parse_next(self)?;
+        while let Some(token) = self.peek() {
+            if token.kind != TokenKind::Operator || !operators.contains(&token.value.as_str()) {
+                break;
+            }
+            let operator = token.value.to_string();
+            self.index += 1;
+            let right = parse_next(self)?;
+            expr = Expr::Arithmetic {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    /// `function := nombre '(' (concat (',' concat)*)? ')' | EXTRACT '(' campo FROM concat ')'`
+    fn parse_function(&mut self, name: &Token) -> Result<Expr, SqlError> {
+        if let Some(function) = AggregateFunction::from_name(&name.value) {
+            return self.parse_aggregate(function);
+        }
+        let function = name.value.to_lowercase();
+        let scalar_function = lookup(&function).ok_or_else(|| {
+            SqlError::new(ErrorType::InvalidSyntax, "Función no soportada")
+                .with_context(&name.value)
+                .with_position(name.position)
+        })?;
+        self.index += 1;
+        let mut args = Vec::new();
+        if function == "extract" {
+            match self.next() {
+                Some(token) if token.kind == TokenKind::Identifier => {
+                    args.push(Expr::Literal(Value::Str(token.value.to_lowercase())))
+                }
+                Some(token) => return Err(unexpected_token(token)),
+                None => return Err(incomplete_expression()),
+            }
+            match self.next() {
+                Some(token) if token.is_keyword("from") => {}
+                Some(token) => return Err(unexpected_token(token)),
+                None => return Err(incomplete_expression()),
+            }
+            args.push(self.parse_concat()?);
+        } else if self.peek().map(|token| &token.kind) != Some(&TokenKind::RightParen) {
+            args.push(self.parse_concat()?);
+            while self.peek().map(|token| &token.kind) == Some(&TokenKind::Comma) {
+                self.index += 1;
+                args.push(self.parse_concat()?);
+            }
+        }
+        match self.next() {
+            Some(token) if token.kind == TokenKind::RightParen => {}
+            _ => {
+                return Err(SqlError::new(
+                    ErrorType::InvalidSyntax,
+                    "Falta ')' para el paréntesis abierto",
+                )
+                .with_position(name.position))
+            }
+        }
+        if !scalar_function.accepts(args.len()) {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "Cantidad de argumentos inválida para la función",
+            )
+            .with_context(&name.value)
+            .with_position(name.position));
+        }
+        Ok(Expr::Function {
+            name: function,
+            args,
+        })
+    }
+
+    /// `aggregate := nombre '(' ('*' | [DISTINCT] columna) ')'`, donde `*` solo se admite en `COUNT`.
+    fn parse_aggregate(&mut self, function: AggregateFunction) -> Result<Expr, SqlError> {
+        self.index += 1;
+        let distinct = self
+            .peek()
+            .is_some_and(|token| token.is_keyword("distinct"));
+        if distinct {
+            self.index += 1;
+        }
+        let column = match self.next() {
+            Some(token)
+                if token.is_operator("*") && function == AggregateFunction::Count && !distinct =>
+            {
+                None
+            }
+            Some(token) if token.kind == TokenKind::Identifier => {
+                let name = token.value.to_string();
+                Some(self.qualified_name(name)?)
+            }
+            Some(token) => return Err(unexpected_token(token)),
+            None => return Err(incomplete_expression()),
+        };
+        match self.next() {
+            Some(token) if token.kind == TokenKind::RightParen => {}
+            Some(token) => return Err(unexpected_token(token)),
+            None => return Err(incomplete_expression()),
+        }
+        Ok(Expr::Aggregate(Aggregate {
+            function,
+            column,
+            distinct,
+        }))
+    }
+
+    /// `qualified_name := nombre ('.' nombre)?`, donde `first` es el nombre ya leído.
+    fn qualified_name(&mut self, first: String) -> Result<String, SqlError> {
+        if self.peek().map(|token| &token.kind) != Some(&TokenKind::Dot) {
+            return Ok(first);
+        }
+        self.index += 1;
+        match self.next() {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                Ok(format!("{}.{}", first, token.value))
+            }
+            Some(token) => Err(unexpected_token(token)),
+            None => Err(incomplete_expression()),
+        }
+    }
+
+    /// `typed_literal := (DATE | TIME | TIMESTAMP | INTERVAL) cadena`
+    fn parse_typed_literal(&mut self, type_name: &Token) -> Result<Expr, SqlError> {
+        let raw = match self.next() {
+            Some(token) => token.value.to_string(),
+            None => return Err(incomplete_expression()),
+        };
+        let value = match type_name.value.to_lowercase().as_str() {
+            "date" => Date::parse(&raw).map(Value::Date),
+            "time" => Time::parse(&raw).map(Value::Time),
+            "timestamp" => Timestamp::parse(&raw).map(Value::Timestamp),
+            _ => Interval::parse(&raw).map(Value::Interval),
+        };
+        value.map(Expr::Literal).ok_or_else(|| {
+            SqlError::new(ErrorType::InvalidSyntax, "Literal de fecha inválido")
+                .with_context(&raw)
+                .with_position(type_name.position)
+        })
+    }
+
+    /// `operand := '(' or_expr ')' | function | typed_literal | columna | literal | NULL | TRUE | FALSE`
+    fn parse_operand(&mut self) -> Result<Expr, SqlError> {
+        let token = match self.next() {
+            Some(token) => token.clone(),
+            None => return Err(incomplete_expression()),
+        };
+        match token.kind {
+            TokenKind::LeftParen => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(token) if token.kind == TokenKind::RightParen => Ok(expr),
+                    _ => Err(SqlError::new(
+                        ErrorType::InvalidSyntax,
+                        "Falta ')' para el paréntesis abierto",
+                    )
+                    .with_position(token.position)),
+                }
+            }
+            TokenKind::Identifier => match self.peek().map(|next| &next.kind) {
+                Some(TokenKind::LeftParen) => self.parse_function(&token),
+                Some(TokenKind::String)
+                    if ["date", "time", "timestamp", "interval"]
+                        .contains(&token.value.to_lowercase().as_str()) =>
+                {
+                    self.parse_typed_literal(&token)
+                }
+                _ => self.qualified_name(token.value).map(Expr::Column),
+            },
+            TokenKind::String => Ok(Expr::Literal(Value::Str(token.value))),
+            TokenKind::Number => Ok(Expr::Literal(cast_to_value(&token.value))),
+            TokenKind::Keyword if token.is_keyword("null") => Ok(Expr::Literal(Value::Null)),
+            TokenKind::Keyword if token.is_keyword("true") || token.is_keyword("false") => {
+                Ok(Expr::Literal(Value::Bool(token.is_keyword("true"))))
+            }
+            _ => Err(unexpected_token(&token)),
+        }
+    }
+}
+
+/// Prepara los valores de una comparación entre `left` y `right`. Si uno de los lados es una columna cuyo valor
+/// es texto (por ejemplo, de una columna `TEXT`) y el otro no lo es, el otro se convierte a texto, de modo que
+/// la columna se compare como texto en lugar de volver a deducir su tipo: `codigo = 7` no coincide con `'007'`.
+fn comparable_values(
+    left: &Expr,
+    left_value: Value,
+    right: &Expr,
+    right_value: Value,
+) -> (Value, Value) {
+    let as_text = |value: Value| match value {
+        Value::Null | Value::Str(_) => value,
+        value => Value::Str(value.to_string()),
+    };
+    match (left, &left_value, right, &right_value) {
+        (Expr::Column(_), Value::Str(_), _, _) => (left_value, as_text(right_value)),
+        (_, _, Expr::Column(_), Value::Str(_)) => (as_text(left_value), right_value),
+        _ => (left_value, right_value),
+    }
+}
+
+/// Crea el error de sintaxis para una expresión a la que le faltan tokens.
+fn incomplete_expression() -> SqlError {
+    SqlError::new(ErrorType::InvalidSyntax, "La expresión está incompleta")
+}
+
+/// Crea el error de sintaxis para un token que no puede aparecer en esa parte de la expresión.
+pub fn unexpected_token(token: &Token) -> SqlError {
+    SqlError::new(ErrorType::InvalidSyntax, "Token inesperado")
+        .with_context(&token.value)
+        .with_position(token.position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    fn parse(condition: &str) -> Result<Expr, ErrorType> {
+        parse_expression(&tokenize(condition).unwrap()).map_err(|error| error.kind())
+    }
+
+    fn compare(column: &str, operator: &str, value: Value) -> Expr {
+        Expr::Compare {
+            left: Box::new(Expr::Column(column.to_string())),
+            operator: operator.to_string(),
+            right: Box::new(Expr::Literal(value)),
+        }
+    }
+
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        let expr = parse("a = 1 or b = 2 and c = 3").unwrap();
+        let expected = Expr::Or(
+            Box::new(compare("a", "=", Value::Int(1))),
+            Box::new(Expr::And(
+                Box::new(compare("b", "=", Value::Int(2))),
+                Box::new(compare("c", "=", Value::Int(3))),
+            )),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_not_applies_to_next_predicate() {
+        let expr = parse("not a = 1 and b = 2").unwrap();
+        let expected = Expr::And(
+            Box::new(Expr::Not(Box::new(compare("a", "=", Value::Int(1))))),
+            Box::new(compare("b", "=", Value::Int(2))),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_parentheses_override_precedence() {
+        let expr = parse("(a = 1 or b = 2) and c = 'x'").unwrap();
+        let expected = Expr::And(
+            Box::new(Expr::Or(
+                Box::new(compare("a", "=", Value::Int(1))),
+                Box::new(compare("b", "=", Value::Int(2))),
+            )),
+            Box::new(compare("c", "=", Value::Str("x".to_string()))),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_unbalanced_parentheses() {
+        assert_eq!(parse("(a = 1 or b = 2"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("a = 1)"), Err(ErrorType::InvalidSyntax));
+    }
+
+    #[test]
+    fn test_unexpected_token_position() {
+        let error = parse_expression(&tokenize("a = 1 b").unwrap()).unwrap_err();
+        assert_eq!(error.context(), Some("b"));
+        assert_eq!(error.position(), Some(6));
+    }
+
+    #[test]
+    fn test_incomplete_expression() {
+        assert_eq!(parse("a = "), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("a = 1 and"), Err(ErrorType::InvalidSyntax));
+    }
+
+    #[test]
+    fn test_is_true_with_precedence() {
+        let headers = vec!["producto", "id_cliente", "cantidad"];
+        let row = vec![
+            Value::Str("Mouse".to_string()),
+            Value::Int(4),
+            Value::Int(1),
+        ];
+        let expr = parse("producto = 'Mouse' or id_cliente = 6 and cantidad > 1").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(true));
+
+        let expr = parse("(producto = 'Mouse' or id_cliente = 6) and cantidad > 1").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(false));
+    }
+
+    #[test]
+    fn test_parse_is_null() {
+        let expr = parse("email is not null and not edad is null").unwrap();
+        let expected = Expr::And(
+            Box::new(Expr::IsNull {
+                expr: Box::new(Expr::Column("email".to_string())),
+                negated: true,
+            }),
+            Box::new(Expr::Not(Box::new(Expr::IsNull {
+                expr: Box::new(Expr::Column("edad".to_string())),
+                negated: false,
+            }))),
+        );
+        assert_eq!(expr, expected);
+        assert_eq!(parse("email is"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("email is 1"), Err(ErrorType::InvalidSyntax));
+    }
+
+    #[test]
+    fn test_three_valued_logic() {
+        let headers = vec!["nombre", "edad"];
+        let row = vec![Value::Str("Ana".to_string()), Value::Null];
+        let truth = |condition: &str| {
+            parse(condition)
+                .unwrap()
+                .truth_value(&row, &headers)
+                .unwrap()
+        };
+
+        assert_eq!(truth("edad = 30"), None);
+        assert_eq!(truth("edad = null"), None);
+        assert_eq!(truth("null = null"), None);
+        assert_eq!(truth("not edad = 30"), None);
+        assert_eq!(truth("edad = 30 and nombre = 'Luis'"), Some(false));
+        assert_eq!(truth("edad = 30 and nombre = 'Ana'"), None);
+        assert_eq!(truth("edad = 30 or nombre = 'Ana'"), Some(true));
+        assert_eq!(truth("edad = 30 or nombre = 'Luis'"), None);
+        assert_eq!(truth("edad is null"), Some(true));
+        assert_eq!(truth("edad is not null"), Some(false));
+        assert_eq!(truth("nombre is not null"), Some(true));
+
+        let expr = parse("not edad = 30").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(false));
+    }
+
+    #[test]
+    fn test_is_true_with_numeric_and_bool_literals() {
+        let headers = vec!["precio", "activo", "id"];
+        let row = vec![
+            Value::Float(19.99),
+            Value::Bool(true),
+            Value::BigInt(3_000_000_000),
+        ];
+        let expr = parse("precio < 20 and activo = true and id > 2147483647").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(true));
+
+        let expr = parse("precio >= 19.995 or activo = FALSE").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(false));
+    }
+
+    #[test]
+    fn test_is_true_unknown_column() {
+        let headers = vec!["producto"];
+        let row = vec![Value::Str("Mouse".to_string())];
+        let expr = parse("precio > 1").unwrap();
+        let error = expr.is_true(&row, &headers).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidColumn);
+        assert_eq!(error.context(), Some("precio"));
+    }
+
+    #[test]
+    fn test_is_true_with_dates() {
+        let headers = vec!["fecha", "creado"];
+        let row = vec![
+            Value::Date(Date::parse("2024-05-17").unwrap()),
+            Value::Timestamp(Timestamp::parse("2024-05-17 08:30:00").unwrap()),
+        ];
+        let conditions = [
+            "fecha >= DATE '2024-01-01'",
+            "fecha + INTERVAL '1 month' = '2024-06-17'",
+            "fecha - 17 < date '2024-05-01'",
+            "EXTRACT(YEAR FROM fecha) = 2024 and extract(hour from creado) = 8",
+            "DATE_TRUNC('month', fecha) = '2024-05-01'",
+            "creado - INTERVAL '30 minutes' = TIMESTAMP '2024-05-17 08:00'",
+            "NOW() > TIMESTAMP '2024-01-01 00:00'",
+        ];
+        for condition in conditions {
+            let expr = parse(condition).unwrap();
+            assert_eq!(
+                expr.is_true(&row, &headers).ok(),
+                Some(true),
+                "{}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_functions_and_date_literals() {
+        assert_eq!(
+            parse("reverse(nombre) = 'A'"),
+            Err(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("upper(nombre, 1) = 'A'"),
+            Err(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(parse("now(1) > fecha"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(
+            parse("extract(year fecha) = 1"),
+            Err(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("fecha = DATE '2024-02-30'"),
+            Err(ErrorType::InvalidSyntax)
+        );
+
+        let headers = vec!["fecha"];
+        let row = vec![Value::Str("abc".to_string())];
+        let expr = parse("date_trunc('siglo', fecha) = fecha").unwrap();
+        assert!(expr.is_true(&row, &headers).is_err());
+    }
+
+    #[test]
+    fn test_date_column_named_like_type() {
+        let headers = vec!["date"];
+        let row = vec![Value::Date(Date::parse("2024-05-17").unwrap())];
+        let expr = parse("date = '2024-05-17'").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(true));
+    }
+
+    #[test]
+    fn test_arithmetic_precedence_and_concatenation() {
+        let headers = vec!["cantidad", "nombre", "apellido"];
+        let row = vec![
+            Value::Int(3),
+            Value::Str("Ana".to_string()),
+            Value::Str("Paz".to_string()),
+        ];
+        let expr = parse("1 + cantidad * 2 - 4 % 3").unwrap();
+        assert_eq!(expr.to_string(), "1 + cantidad * 2 - 4 % 3");
+        assert_eq!(expr.evaluate(&row, &headers).unwrap(), Value::Int(6));
+
+        let expr = parse("(1 + cantidad) * 2").unwrap();
+        assert_eq!(expr.to_string(), "(1 + cantidad) * 2");
+        assert_eq!(expr.evaluate(&row, &headers).unwrap(), Value::Int(8));
+
+        let expr = parse("nombre || ' ' || apellido || cantidad + 1").unwrap();
+        assert_eq!(
+            expr.to_string(),
+            "nombre || ' ' || apellido || cantidad + 1"
+        );
+        assert_eq!(
+            expr.evaluate(&row, &headers).unwrap(),
+            Value::Str("Ana Paz4".to_string())
+        );
+        assert_eq!(expr.columns(), vec!["nombre", "apellido", "cantidad"]);
+
+        let expr = parse("cantidad * 2 > 5 and nombre || 'x' = 'Anax'").unwrap();
+        assert_eq!(expr.is_true(&row, &headers).ok(), Some(true));
+        assert_eq!(parse("cantidad * "), Err(ErrorType::InvalidSyntax));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let conditions = [
+            "a - (b - c) = 1",
+            "NOT (a = 1 OR b IS NULL)",
+            "EXTRACT(YEAR FROM fecha) = 2024",
+            "fecha > DATE '2024-05-17' AND activo = TRUE",
+            "nombre = 'O''Brien'",
+        ];
+        for condition in conditions {
+            let expr = parse(condition).unwrap();
+            assert_eq!(expr.to_string(), condition);
+            assert_eq!(parse(&expr.to_string()).unwrap(), expr);
+        }
+    }
+
+    #[test]
+    fn test_parse_like_in_and_between() {
+        let conditions = [
+            "nombre LIKE 'A%'",
+            "nombre NOT ILIKE '%a!_%' ESCAPE '!'",
+            "id IN (1, 2, 3) AND id NOT IN (2)",
+            "cantidad NOT BETWEEN 1 AND 2 + 3 OR nombre IS NULL",
+            "NOT id IN (1)",
+        ];
+        for condition in conditions {
+            let expr = parse(condition).unwrap();
+            assert_eq!(expr.to_string(), condition);
+        }
+        assert!(matches!(
+            parse("a between 1 and 2 and b = 1").unwrap(),
+            Expr::And(_, _)
+        ));
+        assert_eq!(
+            parse("id in (1, 2) or nombre like 'x'").unwrap().columns(),
+            vec!["id", "nombre"]
+        );
+
+        assert_eq!(parse("id in ()"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("id in (1, 2"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("id in 1"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("id between 1"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("id between 1 or 2"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("nombre like"), Err(ErrorType::InvalidSyntax));
+        assert_eq!(parse("id not = 1"), Err(ErrorType::InvalidSyntax));
+    }
+
+    #[test]
+    fn test_in_and_between_three_valued_logic() {
+        let headers = vec!["id", "cantidad"];
+        let row = vec![Value::Int(2), Value::Null];
+        let truth = |condition: &str| {
+            parse(condition)
+                .unwrap()
+                .truth_value(&row, &headers)
+                .unwrap()
+        };
+
+        assert_eq!(truth("id in (1, 2)"), Some(true));
+        assert_eq!(truth("id in (1, null)"), None);
+        assert_eq!(truth("id in (2, null)"), Some(true));
+        assert_eq!(truth("id not in (1, 3)"), Some(true));
+        assert_eq!(truth("id not in (1, null)"), None);
+        assert_eq!(truth("cantidad in (1)"), None);
+        assert_eq!(truth("id in ('2', 'x')"), Some(true));
+
+        assert_eq!(truth("id between 1 and 2"), Some(true));
+        assert_eq!(truth("id between 3 and 1"), Some(false));
+        assert_eq!(truth("id not between 3 and 5"), Some(true));
+        assert_eq!(truth("id between 1 and null"), None);
+        assert_eq!(truth("id between 3 and null"), Some(false));
+        assert_eq!(truth("cantidad between 1 and 2"), None);
+    }
+}
@@ -1,19 +1,91 @@
-#[derive(Debug, PartialEq)]
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
 
-/// Representa un valor que puede ser un entero (`Int`) o una cadena (`Str`).
+use crate::{
+    csv::CsvReader,
+    datetime::{Date, Interval, Time, Timestamp},
+    error::{ErrorType, SqlError},
+    expression::{parse_expression, Expr},
+    lexer::{Token, TokenKind},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa un valor de una celda o de una expresión.
+///
+/// # Variantes
+/// * `Int` - Un entero de 32 bits.
+/// * `BigInt` - Un entero de 64 bits, para los valores que no entran en un `Int`.
+/// * `Float` - Un número de punto flotante.
+/// * `Bool` - Un valor booleano (`true` o `false`).
+/// * `Date` - Una fecha (`AAAA-MM-DD`).
+/// * `Time` - Una hora del día (`HH:MM:SS`).
+/// * `Timestamp` - Una fecha con hora, en UTC.
+/// * `Interval` - Un intervalo de tiempo, para sumar o restar a fechas.
+/// * `Str` - Una cadena de texto.
+/// * `Null` - La ausencia de valor.
 pub enum Value {
     Int(i32),
+    BigInt(i64),
+    Float(f64),
+    Bool(bool),
+    Date(Date),
+    Time(Time),
+    Timestamp(Timestamp),
+    Interval(Interval),
     Str(String),
+    Null,
+}
+
+impl Value {
+    /// Indica si el valor es `NULL`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Convierte el valor en un campo del CSV: `None` si es `NULL`, o su texto en otro caso.
+    pub fn to_field(&self) -> Option<String> {
+        match self {
+            Value::Null => None,
+            value => Some(value.to_string()),
+        }
+    }
+}
+
+/// Muestra el valor tal como se guarda en la tabla. `NULL` se muestra con su nombre, y los números de punto
+/// flotante conservan su parte decimal (`3.0`) para no leerse luego como enteros.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{}", v),
+            Value::BigInt(v) => write!(f, "{}", v),
+            Value::Float(v) => write!(f, "{:?}", v),
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::Date(v) => write!(f, "{}", v),
+            Value::Time(v) => write!(f, "{}", v),
+            Value::Timestamp(v) => write!(f, "{}", v),
+            Value::Interval(v) => write!(f, "{}", v),
+            Value::Str(v) => write!(f, "{}", v),
+            Value::Null => write!(f, "NULL"),
+        }
+    }
 }
 
-/// Convierte una cadena de texto en un `Value`. Si la cadena puede convertirse en un entero,
-/// devuelve un `Value::Int`. De lo contrario, devuelve un `Value::Str`.
+/// Convierte una cadena de texto en un `Value`, deduciendo su tipo. Si la cadena puede convertirse en un entero,
+/// devuelve un `Value::Int` (o un `Value::BigInt` si no entra en 32 bits); si es un número con decimales o exponente,
+/// un `Value::Float`; si es `true` o `false` (sin distinguir mayúsculas de minúsculas), un `Value::Bool`; y si es
+/// una fecha, un timestamp o una hora en formato ISO-8601, un `Value::Date`, `Value::Timestamp` o `Value::Time`.
+/// De lo contrario, devuelve un `Value::Str`.
 ///
 /// # Argumentos
 /// * `s` - La cadena de texto a convertir.
 ///
 /// # Retorna
-/// * Un `Value` que puede ser un entero o una cadena.
+/// * Un `Value` que puede ser un entero, un número de punto flotante, un booleano o una cadena.
 ///
 /// # Ejemplo
 /// ```rust
@@ -22,6 +94,11 @@ pub enum Value {
 ///
 /// let v = cast_to_value("42");
 /// assert_eq!(v, Value::Int(42));
+/// assert_eq!(cast_to_value("3000000000"), Value::BigInt(3_000_000_000));
+/// assert_eq!(cast_to_value("19.99"), Value::Float(19.99));
+/// assert_eq!(cast_to_value("TRUE"), Value::Bool(true));
+/// assert_eq!(cast_to_value("2024-01-15").to_string(), "2024-01-15");
+/// assert!(matches!(cast_to_value("2024-01-15T10:30:00Z"), Value::Timestamp(_)));
 /// let v = cast_to_value("hello");
 /// assert_eq!(v, Value::Str("hello".to_string()));
 /// ```
@@ -29,19 +106,52 @@ pub enum Value {
 pub fn cast_to_value(s: &str) -> Value {
     if let Ok(int_value) = s.parse::<i32>() {
         Value::Int(int_value)
+    } else if let Ok(big_value) = s.parse::<i64>() {
+        Value::BigInt(big_value)
+    } else if let Some(float_value) = parse_float(s) {
+        Value::Float(float_value)
+    } else if s.eq_ignore_ascii_case("true") {
+        Value::Bool(true)
+    } else if s.eq_ignore_ascii_case("false") {
+        Value::Bool(false)
+    } else if let Some(date) = Date::parse(s) {
+        Value::Date(date)
+    } else if let Some(timestamp) = Timestamp::parse(s) {
+        Value::Timestamp(timestamp)
+    } else if let Some(time) = Time::parse(s) {
+        Value::Time(time)
     } else {
         Value::Str(s.to_string())
     }
 }
 
-/// Obtiene el valor entero de un `Value`, si es de tipo `Int`. Si es de tipo `Str`, devuelve `None`.
+/// Convierte una cadena en un número de punto flotante finito, aceptando solo dígitos, signo, punto decimal
+/// y exponente (a diferencia de `str::parse`, no acepta `inf` ni `NaN`).
+///
+/// # Argumentos
+/// * `s` - La cadena de texto a convertir.
+///
+/// # Retorna
+/// * `Some(f64)` si la cadena es un número, o `None` en caso contrario.
+///
+pub fn parse_float(s: &str) -> Option<f64> {
+    let is_numeric = s.chars().any(|c| c.is_ascii_digit())
+        && s.chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'));
+    if !is_numeric {
+        return None;
+    }
+    s.parse::<f64>().ok().filter(|value| value.is_finite())
+}
+
+/// Obtiene el valor entero de un `Value`, si es de tipo `Int`. En otro caso, devuelve `None`.
 ///
 /// # Argumentos
 /// * `value` - El `Value` del que se quiere obtener el valor entero.
 ///
 /// # Retorna
 /// * `Some(i32)` si el `Value` es de tipo `Int`.
-/// * `None` si el `Value` es de tipo `Str`.
+/// * `None` si el `Value` es de tipo `Str` o `Null`.
 ///
 /// # Ejemplo
 /// ```rust
@@ -55,81 +165,371 @@ pub fn cast_to_value(s: &str) -> Value {
 pub fn get_int_value(value: &Value) -> Option<i32> {
     match value {
         Value::Int(v) => Some(*v),
-        Value::Str(_) => None,
+        _ => None,
     }
 }
 
-/// Obtiene el valor de una cadena de un `Value`, si es de tipo `Str`, y lo convierte a minúsculas.
-/// Si el `Value` es de tipo `Int`, devuelve `None`.
+/// Obtiene el valor de una cadena de un `Value`, si es de tipo `Str`, conservando su capitalización.
+/// En otro caso, devuelve `None`.
 ///
 /// # Argumentos
 /// * `value` - El `Value` del que se quiere obtener la cadena.
 ///
 /// # Retorna
 /// * `Some(String)` si el `Value` es de tipo `Str`.
-/// * `None` si el `Value` es de tipo `Int`.
+/// * `None` si el `Value` es de tipo `Int` o `Null`.
 ///
 /// # Ejemplo
 /// ```rust
 /// use sql::extras::Value;
 /// use sql::extras::get_str_value;
-/// let v = Value::Str("HELLO".to_string());
-/// assert_eq!(get_str_value(&v), Some("hello".to_string()));
+/// let v = Value::Str("Hello".to_string());
+/// assert_eq!(get_str_value(&v), Some("Hello".to_string()));
 /// let v = Value::Int(42);
 /// assert_eq!(get_str_value(&v), None);
 /// ```
 ///
 pub fn get_str_value(value: &Value) -> Option<String> {
     match value {
-        Value::Int(_) => None,
-        Value::Str(v) => Some(v.to_string().to_lowercase()),
+        Value::Str(v) => Some(v.to_string()),
+        _ => None,
+    }
+}
+
+/// Obtiene el valor numérico de un `Value` como `f64`, si es de tipo `Int`, `BigInt` o `Float`.
+/// En otro caso, devuelve `None`.
+///
+/// # Argumentos
+/// * `value` - El `Value` del que se quiere obtener el número.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::extras::Value;
+/// use sql::extras::get_float_value;
+/// assert_eq!(get_float_value(&Value::Int(2)), Some(2.0));
+/// assert_eq!(get_float_value(&Value::Float(0.5)), Some(0.5));
+/// assert_eq!(get_float_value(&Value::Str("2".to_string())), None);
+/// ```
+///
+pub fn get_float_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(v) => Some(f64::from(*v)),
+        Value::BigInt(v) => Some(*v as f64),
+        Value::Float(v) => Some(*v),
+        _ => None,
     }
 }
 
-/// Extrae las columnas de una consulta SQL parseada, deteniéndose antes de la palabra clave "from".
-/// Si la consulta comienza con "update", buscará "set" antes de empezar a extraer columnas.
+/// Convierte un entero de 64 bits en el `Value` entero más chico que lo representa.
+///
+/// # Argumentos
+/// * `value` - El entero a convertir.
+///
+/// # Retorna
+/// * `Value::Int` si el valor entra en 32 bits, o `Value::BigInt` en otro caso.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::extras::{integer_value, Value};
+/// assert_eq!(integer_value(7), Value::Int(7));
+/// assert_eq!(integer_value(3_000_000_000), Value::BigInt(3_000_000_000));
+/// ```
+pub fn integer_value(value: i64) -> Value {
+    i32::try_from(value).map_or(Value::BigInt(value), Value::Int)
+}
+
+/// Extrae las columnas de una consulta `SELECT` tokenizada, deteniéndose antes de la palabra clave "from".
 ///
 /// # Argumentos
-/// * `parsed_query` - Una consulta SQL parseada como un vector de cadenas.
+/// * `tokens` - La consulta SQL tokenizada.
 ///
 /// # Retorna
-/// * Un vector de cadenas con los nombres de las columnas.
-///
-pub fn get_columns(parsed_query: &[String]) -> Vec<String> {
-    let mut columns = Vec::new();
-    let mut index = 1;
-    if parsed_query[0] == "update" {
-        while parsed_query[index] != "set" {
-            index += 1;
+/// * Un vector de cadenas con los nombres de las columnas (sin las comas que las separan).
+///
+pub fn get_columns(tokens: &[Token]) -> Vec<String> {
+    tokens
+        .iter()
+        .skip(1)
+        .take_while(|token| !token.is_keyword("from"))
+        .filter(|token| token.kind != TokenKind::Comma)
+        .map(|token| token.value.to_string())
+        .collect()
+}
+
+/// Extrae y parsea la condición que aparece después de la palabra clave "where" en una consulta SQL tokenizada,
+/// deteniéndose antes de la primera de las cláusulas `clauses` que aparezca.
+///
+/// # Argumentos
+/// * `tokens` - La consulta SQL tokenizada.
+/// * `clauses` - Las palabras clave de las cláusulas que pueden seguir a la condición (por ejemplo `order` o
+///   `limit` en un `SELECT`). Con una lista vacía, la condición llega hasta el final de la consulta y cualquier
+///   token que sobre es un error de sintaxis.
+///
+/// # Retorna
+/// * `Ok(Some(Expr))` con el árbol de la condición, `Ok(None)` si la consulta no tiene "where",
+///   o un error de tipo `ErrorType::InvalidSyntax` si la condición está mal formada o usa funciones
+///   de agregación (que solo tienen valor luego de agrupar las filas).
+///
+pub fn get_condition(tokens: &[Token], clauses: &[&str]) -> Result<Option<Expr>, SqlError> {
+    let start = match tokens.iter().position(|token| token.is_keyword("where")) {
+        Some(index) => index + 1,
+        None => return Ok(None),
+    };
+    let end = tokens[start..]
+        .iter()
+        .position(|token| clauses.iter().any(|k| token.is_keyword(k)))
+        .map_or(tokens.len(), |index| start + index);
+    let condition = parse_expression(&tokens[start..end])?;
+    if let Some(aggregate) = condition.aggregates().first() {
+        return Err(SqlError::new(
+            ErrorType::InvalidSyntax,
+            "No se permiten funciones de agregación en la cláusula WHERE",
+        )
+        .with_context(&aggregate.to_string()));
+    }
+    Ok(Some(condition))
+}
+
+/// Extrae una lista de valores separados por comas y encerrados entre paréntesis, por ejemplo
+/// `(id, nombre)` o `(1, 'Laptop')`.
+///
+/// # Argumentos
+/// * `tokens` - La consulta SQL tokenizada.
+/// * `start` - El índice del token donde debe comenzar la lista (el paréntesis de apertura).
+///
+/// # Retorna
+/// * Una tupla con los valores de la lista y el índice del token siguiente al paréntesis de cierre,
+///   o un error de tipo `ErrorType::InvalidSyntax` si la lista está mal formada.
+///
+pub fn get_parenthesized_values(
+    tokens: &[Token],
+    start: usize,
+) -> Result<(Vec<String>, usize), SqlError> {
+    parenthesized_list(tokens, start, |token| {
+        (token.kind == TokenKind::Identifier || token.is_literal()).then(|| token.value.to_string())
+    })
+}
+
+/// Extrae una lista de valores entre paréntesis como `get_parenthesized_values`, aceptando además
+/// la palabra clave `NULL`, por ejemplo `(1, NULL, 'Laptop')`.
+///
+/// # Argumentos
+/// * `tokens` - La consulta SQL tokenizada.
+/// * `start` - El índice del token donde debe comenzar la lista (el paréntesis de apertura).
+///
+/// # Retorna
+/// * Una tupla con los valores de la lista (`None` para cada `NULL`) y el índice del token siguiente
+///   al paréntesis de cierre, o un error de tipo `ErrorType::InvalidSyntax` si la lista está mal formada.
+///
+pub fn get_parenthesized_fields(
+    tokens: &[Token],
+    start: usize,
+) -> Result<(Vec<Option<String>>, usize), SqlError> {
+    parenthesized_list(tokens, start, token_field)
+}
+
+/// Obtiene el valor de un token usado como valor a escribir en la tabla: `Some(None)` si es `NULL`,
+/// `Some(Some(texto))` si es un literal, `TRUE`, `FALSE` o un identificador, o `None` si el token no es un valor.
+pub fn token_field(token: &Token) -> Option<Option<String>> {
+    if token.is_keyword("null") {
+        Some(None)
+    } else if token.kind == TokenKind::Identifier
+        || token.is_literal()
+        || token.is_keyword("true")
+        || token.is_keyword("false")
+    {
+        Some(Some(token.value.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Recorre una lista separada por comas y encerrada entre paréntesis, convirtiendo cada elemento con `item`.
+fn parenthesized_list<T, F>(
+    tokens: &[Token],
+    start: usize,
+    item: F,
+) -> Result<(Vec<T>, usize), SqlError>
+where
+    F: Fn(&Token) -> Option<T>,
+{
+    if tokens.get(start).map(|token| &token.kind) != Some(&TokenKind::LeftParen) {
+        return Err(syntax_error_at(tokens, start, "Se esperaba '('"));
+    }
+    let mut values = Vec::new();
+    let mut index = start + 1;
+    loop {
+        match tokens.get(index).and_then(&item) {
+            Some(value) => values.push(value),
+            None => {
+                return Err(syntax_error_at(
+                    tokens,
+                    index,
+                    "Se esperaba un valor en la lista",
+                ))
+            }
         }
         index += 1;
+        match tokens.get(index).map(|token| &token.kind) {
+            Some(TokenKind::Comma) => index += 1,
+            Some(TokenKind::RightParen) => return Ok((values, index + 1)),
+            _ => return Err(syntax_error_at(tokens, index, "Se esperaba ',' o ')'")),
+        }
     }
-    while index < parsed_query.len() && parsed_query[index] != "from" {
-        columns.push(parsed_query[index].to_string());
-        index += 1;
+}
+
+/// Crea un error de sintaxis que señala el token ubicado en `index`, o el final de la consulta
+/// si no quedan más tokens.
+///
+/// # Argumentos
+/// * `tokens` - La consulta SQL tokenizada.
+/// * `index` - El índice del token donde se detectó el error.
+/// * `message` - La descripción del error.
+///
+/// # Retorna
+/// * Un `SqlError` de tipo `ErrorType::InvalidSyntax` con el token y su posición, si existe.
+///
+pub fn syntax_error_at(tokens: &[Token], index: usize, message: &str) -> SqlError {
+    let error = SqlError::new(ErrorType::InvalidSyntax, message);
+    match tokens.get(index) {
+        Some(token) => error
+            .with_context(&token.value)
+            .with_position(token.position),
+        None => error,
     }
-    columns
 }
 
-/// Extrae las columnas que aparecen después de la palabra clave "where" en una consulta SQL parseada.
+/// Obtiene la ruta del archivo CSV que almacena una tabla dentro del directorio de la base de datos.
 ///
 /// # Argumentos
-/// * `parsed_query` - Una consulta SQL parseada como un vector de cadenas.
+/// * `path` - La ruta del directorio que contiene las tablas.
+/// * `table_name` - El nombre de la tabla.
 ///
 /// # Retorna
-/// * Un vector de cadenas con las columnas que aparecen en las condiciones.
+/// * La ruta `<path>/<table_name>.csv`, o un error de tipo `ErrorType::InvalidTable` si el archivo no existe
+///   o el nombre no es válido (ver `table_file_path`).
 ///
-pub fn get_condition_columns(parsed_query: &[String]) -> Vec<String> {
-    let mut condition_columns = Vec::new();
-    let index = parsed_query.iter().position(|x| x == "where");
-    if let Some(mut index) = index {
-        index += 1;
-        while index < parsed_query.len() {
-            condition_columns.push(parsed_query[index].to_string());
-            index += 1;
-        }
+pub fn get_table_path(path: &str, table_name: &str) -> Result<String, SqlError> {
+    let table_path = table_file_path(path, table_name)?;
+    if !Path::new(&table_path).is_file() {
+        return Err(
+            SqlError::new(ErrorType::InvalidTable, "La tabla no existe").with_context(table_name)
+        );
+    }
+    Ok(table_path)
+}
+
+/// Arma la ruta `<path>/<table_name>.csv` de una tabla, exista o no el archivo.
+///
+/// # Argumentos
+/// * `path` - La ruta del directorio que contiene las tablas.
+/// * `table_name` - El nombre de la tabla.
+///
+/// # Retorna
+/// * La ruta del archivo, o un error de tipo `ErrorType::InvalidTable` si el nombre está vacío o contiene
+///   `/`, `\` o `..`, ya que la ruta resultante podría quedar fuera del directorio de la base de datos.
+///
+pub fn table_file_path(path: &str, table_name: &str) -> Result<String, SqlError> {
+    if table_name.is_empty() || table_name.contains(['/', '\\']) || table_name.contains("..") {
+        return Err(
+            SqlError::new(ErrorType::InvalidTable, "Nombre de tabla inválido")
+                .with_context(table_name),
+        );
+    }
+    Ok(Path::new(path)
+        .join(format!("{}.csv", table_name))
+        .to_string_lossy()
+        .to_string())
+}
+
+/// Abre el archivo CSV de una tabla y lee su encabezado.
+///
+/// # Argumentos
+/// * `path` - La ruta del archivo CSV (ya resuelta con `get_table_path`).
+///
+/// # Retorna
+/// * Una tupla con los nombres de las columnas y el lector posicionado en la primera fila de datos,
+///   o un error de tipo `ErrorType::InvalidTable` si el archivo no se puede abrir o leer. El lector devuelve
+///   un error para cada fila que no tenga tantos campos como el encabezado (ver `CsvReader::expect_fields`).
+///
+pub fn open_table(path: &str) -> Result<(Vec<String>, CsvReader<BufReader<File>>), SqlError> {
+    let file = File::open(path).map_err(|e| {
+        SqlError::new(ErrorType::InvalidTable, "No se pudo abrir el archivo")
+            .with_context(path)
+            .with_source(e)
+    })?;
+    let mut reader = CsvReader::new(BufReader::new(file));
+    let headers: Vec<String> = reader
+        .read_record()
+        .map_err(|e| read_error(path, e))?
+        .unwrap_or_default()
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect();
+    reader.expect_fields(headers.len());
+    Ok((headers, reader))
+}
+
+/// Reemplaza el contenido del archivo de una tabla en una sola pasada.
+///
+/// # Argumentos
+/// * `path` - La ruta del archivo CSV de la tabla.
+/// * `write_rows` - Función que escribe el nuevo contenido completo de la tabla (encabezado incluido)
+///   y devuelve la cantidad de filas afectadas.
+///
+/// # Retorna
+/// * La cantidad de filas afectadas que devolvió `write_rows`, o un `SqlError` si falla la escritura.
+///
+/// # Notas
+/// El contenido se escribe en el archivo temporal `<path>.tmp`, que al terminar se renombra sobre el archivo
+/// original, por lo que la tabla nunca queda escrita a medias. Si ocurre un error, se borra el archivo temporal
+/// y la tabla queda sin cambios.
+///
+pub fn replace_table<F>(path: &str, write_rows: F) -> Result<usize, SqlError>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<usize, SqlError>,
+{
+    let temp_path = format!("{}.tmp", path);
+    let result = File::create(&temp_path)
+        .map_err(|e| write_error(&temp_path, e))
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            let affected_rows = write_rows(&mut writer)?;
+            writer.flush().map_err(|e| write_error(&temp_path, e))?;
+            Ok(affected_rows)
+        })
+        .and_then(|affected_rows| {
+            fs::rename(&temp_path, path).map_err(|e| write_error(path, e))?;
+            Ok(affected_rows)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
     }
-    condition_columns
+    result
+}
+
+/// Crea el error que se devuelve cuando no se puede leer el archivo de una tabla.
+///
+/// # Argumentos
+/// * `path` - La ruta del archivo que se estaba leyendo.
+/// * `source` - El error de entrada/salida que se produjo.
+///
+pub fn read_error(path: &str, source: io::Error) -> SqlError {
+    SqlError::new(ErrorType::InvalidTable, "No se pudo leer el archivo")
+        .with_context(path)
+        .with_source(source)
+}
+
+/// Crea el error que se devuelve cuando no se puede escribir el archivo de una tabla.
+///
+/// # Argumentos
+/// * `path` - La ruta del archivo que se estaba escribiendo.
+/// * `source` - El error de entrada/salida que se produjo.
+///
+pub fn write_error(path: &str, source: io::Error) -> SqlError {
+    SqlError::new(ErrorType::InvalidTable, "No se pudo escribir en el archivo")
+        .with_context(path)
+        .with_source(source)
 }
 
 /// Busca el índice de una columna en el encabezado de la tabla.
@@ -150,34 +550,83 @@ pub fn get_column_index(headers: &[String], column_name: &str) -> isize {
         .unwrap_or(-1)
 }
 
-/// Limpia los valores de las columnas eliminando caracteres como paréntesis, comas, apóstrofes y punto y coma.
+/// Busca una columna por su nombre, que puede estar calificado con el nombre o el alias de su tabla
+/// (`ordenes.id`, `c.nombre`).
 ///
 /// # Argumentos
-/// * `columns` - Un vector de cadenas que representan las columnas.
+/// * `headers` - Los nombres de las columnas. En las consultas con `JOIN` están calificados (`tabla.columna`).
+/// * `name` - El nombre de la columna buscada.
 ///
 /// # Retorna
-/// * Un nuevo vector con las columnas limpias.
+/// * El índice de la columna cuyo nombre coincide exactamente, o si no hay ninguna y `name` no está calificado,
+///   el de la única columna calificada con ese nombre.
+/// * Un error de tipo `ErrorType::InvalidColumn` si la columna no existe o si el nombre sin calificar es ambiguo
+///   (por ejemplo, `id` cuando las dos tablas del `JOIN` tienen esa columna).
 ///
-pub fn cleaned_values(columns: Vec<String>) -> Vec<String> {
-    columns
-        .iter()
-        .map(|col| {
-            col.trim_matches(|c| c == '(' || c == ')' || c == ',' || c == '\'' || c == ';')
-                .trim()
-                .to_string()
-        })
-        .collect()
+/// # Ejemplo
+/// ```rust
+/// use sql::extras::find_column;
+///
+/// let headers = ["o.id", "o.producto", "c.id", "c.nombre"];
+/// assert_eq!(find_column(&headers, "c.id").ok(), Some(2));
+/// assert_eq!(find_column(&headers, "nombre").ok(), Some(3));
+/// assert!(find_column(&headers, "id").is_err());
+/// ```
+pub fn find_column(headers: &[&str], name: &str) -> Result<usize, SqlError> {
+    if let Some(index) = headers.iter().position(|header| *header == name) {
+        return Ok(index);
+    }
+    let mut matches = headers.iter().enumerate().filter(|(_, header)| {
+        !name.contains('.')
+            && header
+                .split_once('.')
+                .is_some_and(|(_, column)| column == name)
+    });
+    match (matches.next(), matches.next()) {
+        (Some((index, _)), None) => Ok(index),
+        (Some(_), Some(_)) => Err(SqlError::new(
+            ErrorType::InvalidColumn,
+            "La columna es ambigua, debe calificarse con el nombre de la tabla",
+        )
+        .with_context(name)),
+        _ => {
+            Err(SqlError::new(ErrorType::InvalidColumn, "La columna no existe").with_context(name))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::tokenize;
+
+    const SELECT_CLAUSES: &[&str] = &["group", "having", "order", "limit", "offset"];
 
     #[test]
     fn test_cast_to_value_int() {
         assert_eq!(cast_to_value("42"), Value::Int(42));
     }
 
+    #[test]
+    fn test_cast_to_value_numbers_and_booleans() {
+        assert_eq!(cast_to_value("-2147483649"), Value::BigInt(-2_147_483_649));
+        assert_eq!(cast_to_value("0.5"), Value::Float(0.5));
+        assert_eq!(cast_to_value("1e3"), Value::Float(1000.0));
+        assert_eq!(cast_to_value("false"), Value::Bool(false));
+        assert_eq!(cast_to_value("inf"), Value::Str("inf".to_string()));
+        assert_eq!(cast_to_value("NaN"), Value::Str("NaN".to_string()));
+        assert_eq!(cast_to_value("1.2.3"), Value::Str("1.2.3".to_string()));
+    }
+
+    #[test]
+    fn test_display_float_keeps_decimals() {
+        assert_eq!(Value::Float(3.0).to_string(), "3.0");
+        assert_eq!(
+            cast_to_value(&Value::Float(3.0).to_string()),
+            Value::Float(3.0)
+        );
+    }
+
     #[test]
     fn test_cast_to_value_str() {
         assert_eq!(cast_to_value("hello"), Value::Str("hello".to_string()));
@@ -197,11 +646,8 @@ mod tests {
 
     #[test]
     fn test_get_str_value_some() {
-        let value = Value::Str("hello".to_string());
-        assert_eq!(
-            get_str_value(&value),
-            Some("hello".to_string().to_lowercase())
-        );
+        let value = Value::Str("Hello".to_string());
+        assert_eq!(get_str_value(&value), Some("Hello".to_string()));
     }
 
     #[test]
@@ -212,32 +658,118 @@ mod tests {
 
     #[test]
     fn test_get_columns() {
-        let parsed_query = vec!["select", "column1", "column2", "from", "table"]
-            .iter()
-            .map(|&s| s.to_string())
-            .collect::<Vec<String>>();
-        assert_eq!(get_columns(&parsed_query), vec!["column1", "column2"]);
+        let tokens = tokenize("select column1, column2 from table").unwrap();
+        assert_eq!(get_columns(&tokens), vec!["column1", "column2"]);
     }
 
     #[test]
-    fn test_get_condition_columns_with_where() {
-        let parsed_query = vec![
-            "select", "column1", "from", "table", "where", "column1", "=", "42",
-        ]
-        .iter()
-        .map(|&s| s.to_string())
-        .collect::<Vec<String>>();
-        let length = 3;
-        assert_eq!(get_condition_columns(&parsed_query).len(), length);
+    fn test_get_condition_with_where() {
+        let tokens = tokenize("select column1 from table where column1 = 42").unwrap();
+        let expected = Expr::Compare {
+            left: Box::new(Expr::Column("column1".to_string())),
+            operator: "=".to_string(),
+            right: Box::new(Expr::Literal(Value::Int(42))),
+        };
+        assert_eq!(
+            get_condition(&tokens, SELECT_CLAUSES).ok(),
+            Some(Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_get_condition_stops_at_order_by() {
+        let tokens =
+            tokenize("select column1 from table where nombre = 'New York' order by column1")
+                .unwrap();
+        let expected = Expr::Compare {
+            left: Box::new(Expr::Column("nombre".to_string())),
+            operator: "=".to_string(),
+            right: Box::new(Expr::Literal(Value::Str("New York".to_string()))),
+        };
+        assert_eq!(
+            get_condition(&tokens, SELECT_CLAUSES).ok(),
+            Some(Some(expected))
+        );
     }
 
     #[test]
-    fn test_get_condition_columns_without_where() {
-        let parsed_query = vec!["select", "column1", "column2", "from", "table"]
-            .iter()
-            .map(|&s| s.to_string())
-            .collect::<Vec<String>>();
-        assert_eq!(get_condition_columns(&parsed_query), Vec::<String>::new());
+    fn test_get_condition_rejects_trailing_tokens() {
+        let tokens = tokenize("delete from ordenes where id = 101 limit 1").unwrap();
+        assert_eq!(
+            get_condition(&tokens, &[]).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_get_condition_without_where() {
+        let tokens = tokenize("select column1, column2 from table").unwrap();
+        assert_eq!(get_condition(&tokens, SELECT_CLAUSES).ok(), Some(None));
+    }
+
+    #[test]
+    fn test_get_condition_empty_where() {
+        let tokens = tokenize("select column1 from table where").unwrap();
+        assert_eq!(
+            get_condition(&tokens, SELECT_CLAUSES).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_get_parenthesized_values() {
+        let tokens = tokenize("(1,'Laptop', 3) values").unwrap();
+        let (values, next) = get_parenthesized_values(&tokens, 0).unwrap();
+        assert_eq!(values, vec!["1", "Laptop", "3"]);
+        assert!(tokens[next].is_keyword("values"));
+    }
+
+    #[test]
+    fn test_get_parenthesized_fields_with_null() {
+        let tokens = tokenize("(1, NULL, 'NULL')").unwrap();
+        let (values, next) = get_parenthesized_fields(&tokens, 0).unwrap();
+        assert_eq!(
+            values,
+            vec![Some("1".to_string()), None, Some("NULL".to_string())]
+        );
+        assert_eq!(next, tokens.len());
+        assert!(get_parenthesized_values(&tokens, 0).is_err());
+    }
+
+    #[test]
+    fn test_get_parenthesized_values_unclosed() {
+        let tokens = tokenize("(1, 2").unwrap();
+        assert_eq!(
+            get_parenthesized_values(&tokens, 0).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_get_table_path_found() {
+        let table_path = get_table_path("tests", "ordenes").unwrap();
+        assert!(table_path.ends_with("ordenes.csv"));
+    }
+
+    #[test]
+    fn test_get_table_path_missing_table() {
+        assert_eq!(
+            get_table_path("tests", "inexistente").map_err(|error| error.kind()),
+            Err(ErrorType::InvalidTable)
+        );
+    }
+
+    #[test]
+    fn test_table_file_path_rejects_paths() {
+        for table_name in ["../escaped", "a/b", "a\\b", "/tmp/x", "..", ""] {
+            assert_eq!(
+                table_file_path("tests", table_name).map_err(|error| error.kind()),
+                Err(ErrorType::InvalidTable),
+                "{}",
+                table_name
+            );
+        }
+        assert!(table_file_path("tests", "mis.ordenes").is_ok());
     }
 
     #[test]
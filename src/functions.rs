@@ -0,0 +1,491 @@
+//! Funciones escalares (`UPPER`, `SUBSTR`, `COALESCE`, `ROUND`, `NOW`, ...), que calculan un valor por fila.
+//!
+//! Cada función se registra en `FUNCTIONS` con la cantidad de argumentos que admite y su implementación, que
+//! recibe los valores de los argumentos ya evaluados. El parser de expresiones consulta el registro para validar
+//! las llamadas, por lo que agregar una función solo requiere sumarla a la tabla.
+use std::cmp::Ordering;
+
+use crate::{
+    datetime::{date_trunc, extract, now},
+    error::{ErrorType, SqlError},
+    extras::{cast_to_value, integer_value, Value},
+    operations::compare_values,
+};
+
+/// Representa una función escalar del registro, con los campos:
+///
+/// * `name` - El nombre de la función, en minúsculas.
+/// * `min_args` - La cantidad mínima de argumentos.
+/// * `max_args` - La cantidad máxima de argumentos (`usize::MAX` si no tiene límite).
+/// * `strict` - Si la función devuelve `NULL` cuando alguno de sus argumentos es `NULL`, sin llamarla.
+/// * `implementation` - La función que calcula el resultado a partir de los argumentos.
+///
+pub struct ScalarFunction {
+    pub name: &'static str,
+    pub min_args: usize,
+    pub max_args: usize,
+    strict: bool,
+    implementation: fn(&[Value]) -> Result<Value, SqlError>,
+}
+
+impl ScalarFunction {
+    /// Indica si la función admite la cantidad de argumentos dada.
+    pub fn accepts(&self, count: usize) -> bool {
+        (self.min_args..=self.max_args).contains(&count)
+    }
+
+    /// Llama a la función con los valores de sus argumentos.
+    ///
+    /// # Argumentos
+    /// * `args` - Los valores de los argumentos, ya evaluados.
+    ///
+    /// # Retorno
+    /// Devuelve el resultado de la función, o un error de tipo `ErrorType::Error` si la cantidad o el tipo
+    /// de los argumentos no es válido.
+    ///
+    pub fn call(&self, args: &[Value]) -> Result<Value, SqlError> {
+        if !self.accepts(args.len()) {
+            return Err(invalid_arguments(self.name));
+        }
+        if self.strict && args.iter().any(Value::is_null) {
+            return Ok(Value::Null);
+        }
+        (self.implementation)(args).map_err(|error| match error.context() {
+            Some(_) => error,
+            None => error.with_context(self.name),
+        })
+    }
+}
+
+/// Registro de las funciones escalares soportadas.
+static FUNCTIONS: [ScalarFunction; 14] = [
+    ScalarFunction {
+        name: "now",
+        min_args: 0,
+        max_args: 0,
+        strict: false,
+        implementation: |_| Ok(now()),
+    },
+    ScalarFunction {
+        name: "date_trunc",
+        min_args: 2,
+        max_args: 2,
+        strict: false,
+        implementation: |args| match args {
+            [Value::Str(unit), value] => date_trunc(unit, value),
+            _ => Err(invalid_arguments("date_trunc")),
+        },
+    },
+    ScalarFunction {
+        name: "extract",
+        min_args: 2,
+        max_args: 2,
+        strict: false,
+        implementation: |args| match args {
+            [Value::Str(field), value] => extract(field, value),
+            _ => Err(invalid_arguments("extract")),
+        },
+    },
+    ScalarFunction {
+        name: "upper",
+        min_args: 1,
+        max_args: 1,
+        strict: true,
+        implementation: |args| Ok(Value::Str(text(&args[0]).to_uppercase())),
+    },
+    ScalarFunction {
+        name: "lower",
+        min_args: 1,
+        max_args: 1,
+        strict: true,
+        implementation: |args| Ok(Value::Str(text(&args[0]).to_lowercase())),
+    },
+    ScalarFunction {
+        name: "length",
+        min_args: 1,
+        max_args: 1,
+        strict: true,
+        implementation: |args| Ok(integer_value(text(&args[0]).chars().count() as i64)),
+    },
+    ScalarFunction {
+        name: "trim",
+        min_args: 1,
+        max_args: 1,
+        strict: true,
+        implementation: |args| Ok(Value::Str(text(&args[0]).trim().to_string())),
+    },
+    ScalarFunction {
+        name: "substr",
+        min_args: 2,
+        max_args: 3,
+        strict: true,
+        implementation: substr,
+    },
+    ScalarFunction {
+        name: "replace",
+        min_args: 3,
+        max_args: 3,
+        strict: true,
+        implementation: replace,
+    },
+    ScalarFunction {
+        name: "concat",
+        min_args: 1,
+        max_args: usize::MAX,
+        strict: false,
+        implementation: |args| {
+            Ok(Value::Str(
+                args.iter()
+                    .filter(|value| !value.is_null())
+                    .map(text)
+                    .collect(),
+            ))
+        },
+    },
+    ScalarFunction {
+        name: "coalesce",
+        min_args: 1,
+        max_args: usize::MAX,
+        strict: false,
+        implementation: |args| {
+            Ok(args
+                .iter()
+                .find(|value| !value.is_null())
+                .cloned()
+                .unwrap_or(Value::Null))
+        },
+    },
+    ScalarFunction {
+        name: "nullif",
+        min_args: 2,
+        max_args: 2,
+        strict: false,
+        implementation: |args| match compare_values(&args[0], &args[1]) {
+            Some(Ordering::Equal) => Ok(Value::Null),
+            _ => Ok(args[0].clone()),
+        },
+    },
+    ScalarFunction {
+        name: "abs",
+        min_args: 1,
+        max_args: 1,
+        strict: true,
+        implementation: abs,
+    },
+    ScalarFunction {
+        name: "round",
+        min_args: 1,
+        max_args: 2,
+        strict: true,
+        implementation: round,
+    },
+];
+
+/// Busca una función escalar por su nombre, sin distinguir mayúsculas de minúsculas.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::functions::lookup;
+///
+/// let function = lookup("SUBSTR").unwrap();
+/// assert!(function.accepts(2) && function.accepts(3));
+/// assert!(lookup("sum").is_none());
+/// ```
+pub fn lookup(name: &str) -> Option<&'static ScalarFunction> {
+    FUNCTIONS
+        .iter()
+        .find(|function| function.name.eq_ignore_ascii_case(name))
+}
+
+/// Llama a una función escalar por su nombre.
+///
+/// # Argumentos
+/// * `name` - El nombre de la función.
+/// * `args` - Los valores de los argumentos. Para `DATE_TRUNC` y `EXTRACT`, el primero es la unidad.
+///
+/// # Retorno
+/// Devuelve el resultado de la función, o un `SqlError` si la función no existe o sus argumentos no son válidos.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::extras::Value;
+/// use sql::functions::call_function;
+///
+/// let name = Value::Str("  Ana ".to_string());
+/// assert_eq!(call_function("trim", &[name]).unwrap(), Value::Str("Ana".to_string()));
+/// assert_eq!(call_function("upper", &[Value::Null]).unwrap(), Value::Null);
+/// assert_eq!(call_function("round", &[Value::Float(2.456), Value::Int(2)]).unwrap(), Value::Float(2.46));
+/// ```
+pub fn call_function(name: &str, args: &[Value]) -> Result<Value, SqlError> {
+    match lookup(name) {
+        Some(function) => function.call(args),
+        None => Err(SqlError::new(ErrorType::Error, "Función no soportada").with_context(name)),
+    }
+}
+
+/// Crea el error para una llamada con argumentos que la función no admite.
+fn invalid_arguments(name: &str) -> SqlError {
+    SqlError::new(ErrorType::Error, "Argumentos inválidos para la función").with_context(name)
+}
+
+/// Devuelve el texto de un valor: las cadenas tal cual y el resto como se muestran en la tabla.
+fn text(value: &Value) -> String {
+    match value {
+        Value::Str(s) => s.to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Convierte un argumento en número, leyendo las cadenas como en la tabla (`'12'` es `12`).
+fn number(value: &Value) -> Result<Value, SqlError> {
+    let value = match value {
+        Value::Str(s) => cast_to_value(s),
+        value => value.clone(),
+    };
+    match value {
+        Value::Int(_) | Value::BigInt(_) | Value::Float(_) => Ok(value),
+        _ => {
+            Err(SqlError::new(ErrorType::Error, "Se esperaba un número")
+                .with_context(&text(&value)))
+        }
+    }
+}
+
+/// Convierte un argumento en entero, como las posiciones de `SUBSTR` o los decimales de `ROUND`.
+fn integer(value: &Value) -> Result<i64, SqlError> {
+    match number(value)? {
+        Value::Int(v) => Ok(i64::from(v)),
+        Value::BigInt(v) => Ok(v),
+        value => Err(
+            SqlError::new(ErrorType::Error, "Se esperaba un número entero")
+                .with_context(&text(&value)),
+        ),
+    }
+}
+
+/// Implementa `SUBSTR(texto, inicio [, largo])`: devuelve los caracteres desde la posición `inicio` (contando
+/// desde 1), hasta el final o hasta completar `largo`. Como en SQL, las posiciones anteriores a 1 no cuentan
+/// dentro del largo pero no agregan caracteres (`SUBSTR('abc', 0, 2)` es `'a'`).
+fn substr(args: &[Value]) -> Result<Value, SqlError> {
+    let start = integer(&args[1])?;
+    let end = match args.get(2) {
+        Some(length) => {
+            let length = integer(length)?;
+            if length < 0 {
+                return Err(
+                    SqlError::new(ErrorType::Error, "El largo no puede ser negativo")
+                        .with_context(&length.to_string()),
+                );
+            }
+            start.saturating_add(length)
+        }
+        None => i64::MAX,
+    };
+    let first = start.max(1);
+    let skip = usize::try_from(first - 1).unwrap_or(usize::MAX);
+    let take = usize::try_from(end.saturating_sub(first).max(0)).unwrap_or(usize::MAX);
+    Ok(Value::Str(
+        text(&args[0]).chars().skip(skip).take(take).collect(),
+    ))
+}
+
+/// Implementa `REPLACE(texto, buscado, reemplazo)`, reemplazando todas las apariciones de `buscado`.
+fn replace(args: &[Value]) -> Result<Value, SqlError> {
+    let (value, from, to) = (text(&args[0]), text(&args[1]), text(&args[2]));
+    if from.is_empty() {
+        return Ok(Value::Str(value));
+    }
+    Ok(Value::Str(value.replace(&from, &to)))
+}
+
+/// Implementa `ABS(numero)`, conservando el tipo del número.
+fn abs(args: &[Value]) -> Result<Value, SqlError> {
+    match number(&args[0])? {
+        Value::Int(v) => Ok(integer_value(i64::from(v).abs())),
+        Value::BigInt(v) => v.checked_abs().map(Value::BigInt).ok_or_else(|| {
+            SqlError::new(ErrorType::Error, "El resultado está fuera de rango")
+                .with_context(&v.to_string())
+        }),
+        Value::Float(v) => Ok(Value::Float(v.abs())),
+        value => Err(invalid_arguments(&text(&value))),
+    }
+}
+
+/// Implementa `ROUND(numero [, decimales])`, redondeando la mitad hacia afuera del cero. Con decimales
+/// negativos redondea a decenas, centenas, etc. (`ROUND(1250, -2)` es `1300`). Los enteros siguen siendo enteros.
+fn round(args: &[Value]) -> Result<Value, SqlError> {
+    let digits = match args.get(1) {
+        Some(digits) => integer(digits)?,
+        None => 0,
+    };
+    match number(&args[0])? {
+        Value::Float(v) => {
+            let factor = 10f64.powi(digits.clamp(-308, 308) as i32);
+            let rounded = (v * factor).round() / factor;
+            Ok(Value::Float(if rounded.is_finite() { rounded } else { v }))
+        }
+        Value::Int(v) => round_integer(i64::from(v), digits),
+        Value::BigInt(v) => round_integer(v, digits),
+        value => Err(invalid_arguments(&text(&value))),
+    }
+}
+
+/// Redondea un entero a la cantidad de decimales dada, que solo lo modifica si es negativa.
+fn round_integer(value: i64, digits: i64) -> Result<Value, SqlError> {
+    if digits >= 0 {
+        return Ok(integer_value(value));
+    }
+    // Con 20 o más decimales negativos cualquier `i64` redondea a 0.
+    let factor = 10i128.pow(digits.unsigned_abs().min(20) as u32);
+    let value = i128::from(value);
+    let rounded = (value.abs() + factor / 2) / factor * factor * value.signum();
+    i64::try_from(rounded).map(integer_value).map_err(|_| {
+        SqlError::new(ErrorType::Error, "El resultado está fuera de rango")
+            .with_context(&value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_value(s: &str) -> Value {
+        Value::Str(s.to_string())
+    }
+
+    fn call(name: &str, args: &[Value]) -> Value {
+        call_function(name, args).unwrap()
+    }
+
+    #[test]
+    fn test_string_functions() {
+        let name = || text_value("  José Pérez ");
+        assert_eq!(call("upper", &[name()]), text_value("  JOSÉ PÉREZ "));
+        assert_eq!(call("lower", &[name()]), text_value("  josé pérez "));
+        assert_eq!(call("trim", &[name()]), text_value("José Pérez"));
+        assert_eq!(call("length", &[name()]), Value::Int(13));
+        assert_eq!(call("length", &[Value::Int(12345)]), Value::Int(5));
+        assert_eq!(
+            call(
+                "replace",
+                &[text_value("a-b-c"), text_value("-"), text_value("+")]
+            ),
+            text_value("a+b+c")
+        );
+        assert_eq!(
+            call(
+                "replace",
+                &[text_value("abc"), text_value(""), text_value("x")]
+            ),
+            text_value("abc")
+        );
+        assert_eq!(
+            call(
+                "concat",
+                &[
+                    text_value("a"),
+                    Value::Null,
+                    Value::Int(1),
+                    Value::Bool(true)
+                ]
+            ),
+            text_value("a1true")
+        );
+    }
+
+    #[test]
+    fn test_substr() {
+        let value = text_value("Ñandú");
+        assert_eq!(
+            call("substr", &[value.clone(), Value::Int(2)]),
+            text_value("andú")
+        );
+        assert_eq!(
+            call("substr", &[value.clone(), Value::Int(2), Value::Int(3)]),
+            text_value("and")
+        );
+        assert_eq!(
+            call("substr", &[value.clone(), Value::Int(0), Value::Int(2)]),
+            text_value("Ñ")
+        );
+        assert_eq!(
+            call("substr", &[value.clone(), Value::Int(9)]),
+            text_value("")
+        );
+        assert_eq!(
+            call("substr", &[value.clone(), text_value("2"), text_value("1")]),
+            text_value("a")
+        );
+        assert!(call_function("substr", &[value.clone(), Value::Int(1), Value::Int(-1)]).is_err());
+        assert!(call_function("substr", &[value, text_value("dos")]).is_err());
+    }
+
+    #[test]
+    fn test_null_handling() {
+        assert_eq!(call("upper", &[Value::Null]), Value::Null);
+        assert_eq!(
+            call("substr", &[text_value("abc"), Value::Null]),
+            Value::Null
+        );
+        assert_eq!(
+            call("coalesce", &[Value::Null, Value::Int(2), Value::Int(3)]),
+            Value::Int(2)
+        );
+        assert_eq!(call("coalesce", &[Value::Null]), Value::Null);
+        assert_eq!(call("nullif", &[Value::Int(0), Value::Int(0)]), Value::Null);
+        assert_eq!(
+            call("nullif", &[text_value("a"), text_value("b")]),
+            text_value("a")
+        );
+        assert_eq!(call("concat", &[Value::Null]), text_value(""));
+    }
+
+    #[test]
+    fn test_numeric_functions() {
+        assert_eq!(call("abs", &[Value::Int(-3)]), Value::Int(3));
+        assert_eq!(
+            call("abs", &[Value::Int(i32::MIN)]),
+            Value::BigInt(2_147_483_648)
+        );
+        assert_eq!(call("abs", &[text_value("-1.5")]), Value::Float(1.5));
+        assert!(call_function("abs", &[Value::BigInt(i64::MIN)]).is_err());
+        assert_eq!(call("round", &[Value::Float(2.5)]), Value::Float(3.0));
+        assert_eq!(call("round", &[Value::Float(-2.5)]), Value::Float(-3.0));
+        assert_eq!(
+            call("round", &[Value::Float(1.23456), Value::Int(2)]),
+            Value::Float(1.23)
+        );
+        assert_eq!(
+            call("round", &[Value::Int(7), Value::Int(2)]),
+            Value::Int(7)
+        );
+        assert_eq!(
+            call("round", &[Value::Int(1250), Value::Int(-2)]),
+            Value::Int(1300)
+        );
+        assert_eq!(
+            call("round", &[Value::Int(-1249), Value::Int(-2)]),
+            Value::Int(-1200)
+        );
+        assert_eq!(
+            call("round", &[Value::Int(5), Value::Int(-30)]),
+            Value::Int(0)
+        );
+
+        let error = call_function("abs", &[text_value("abc")]).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+    }
+
+    #[test]
+    fn test_registry() {
+        assert!(lookup("Upper").is_some());
+        assert!(lookup("count").is_none());
+        assert!(lookup("concat").is_some_and(|function| function.accepts(5)));
+        assert!(lookup("round").is_some_and(|function| !function.accepts(3)));
+
+        let error = call_function("upper", &[]).unwrap_err();
+        assert_eq!(error.context(), Some("upper"));
+        let error = call_function("reverse", &[Value::Null]).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+    }
+}
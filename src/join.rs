@@ -0,0 +1,615 @@
+use std::{cell::Cell, collections::HashMap};
+
+use crate::{
+    error::{ErrorType, SqlError},
+    expression::Expr,
+    extras::{cast_to_value, find_column, get_float_value, Value},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+
+/// Representa el tipo de un `JOIN`.
+///
+/// # Variantes
+/// * `Inner` - Solo las combinaciones de filas que cumplen la condición (`[INNER] JOIN`).
+/// * `Left` - Como `Inner`, pero las filas de la izquierda sin ninguna combinación se conservan con las
+///   columnas de la derecha en `NULL` (`LEFT [OUTER] JOIN`).
+/// * `Right` - Como `Inner`, pero las filas de la derecha sin ninguna combinación se conservan con las
+///   columnas de la izquierda en `NULL` (`RIGHT [OUTER] JOIN`).
+/// * `Full` - Conserva las filas sin combinación de ambos lados (`FULL [OUTER] JOIN`).
+/// * `Cross` - Todas las combinaciones de filas, sin condición (`CROSS JOIN`).
+pub enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+impl JoinKind {
+    /// Indica si las filas de la izquierda sin combinación se conservan.
+    fn keeps_left(&self) -> bool {
+        matches!(self, JoinKind::Left | JoinKind::Full)
+    }
+
+    /// Indica si las filas de la derecha sin combinación se conservan.
+    fn keeps_right(&self) -> bool {
+        matches!(self, JoinKind::Right | JoinKind::Full)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa la condición con la que se combinan las filas de un `JOIN`.
+///
+/// # Variantes
+/// * `On` - Una condición cualquiera (`ON o.id_cliente = c.id`).
+/// * `Using` - Columnas con el mismo nombre en ambos lados que deben ser iguales (`USING (id)`).
+/// * `Natural` - Como `Using`, con todas las columnas que tienen el mismo nombre en ambos lados (`NATURAL JOIN`).
+pub enum JoinCondition {
+    On(Expr),
+    Using(Vec<String>),
+    Natural,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa un `JOIN` de una consulta `SELECT`, con los parámetros:
+///
+/// * `kind` - El tipo de `JOIN`.
+/// * `table_name` - Nombre de la tabla que se combina con las anteriores.
+/// * `alias` - Nombre con el que se referencia a la tabla en la consulta, si tiene alias (`clientes c`).
+/// * `condition` - La condición del `JOIN`, que puede usar columnas de esta tabla y de las anteriores
+///   (`None` en un `CROSS JOIN`).
+///
+pub struct Join {
+    pub kind: JoinKind,
+    pub table_name: String,
+    pub alias: Option<String>,
+    pub condition: Option<JoinCondition>,
+}
+
+impl Join {
+    /// Devuelve el nombre con el que se califican las columnas de la tabla: su alias o, si no tiene, su nombre.
+    pub fn name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.table_name)
+    }
+}
+
+/// Califica cada columna de una tabla con el nombre de la tabla en la consulta (`c` y `nombre` dan `c.nombre`).
+pub fn qualify(name: &str, header: &[String]) -> Vec<String> {
+    header
+        .iter()
+        .map(|column| format!("{}.{}", name, column))
+        .collect()
+}
+
+/// Ejecuta un `JOIN` con una tabla hash: las filas de la tabla de la derecha se leen una sola vez y se
+/// agrupan por los valores de sus columnas de igualdad, y cada fila de la izquierda se combina solo con las
+/// filas de su grupo.
+///
+/// # Notas
+/// Las columnas de igualdad son las comparaciones `izquierda = derecha` entre columnas de ambos lados unidas
+/// por `AND` en la condición (por ejemplo, `o.id_cliente = c.id`, o cada columna de `USING`). Cada combinación
+/// se vuelve a evaluar contra la condición completa, por lo que el resto de la condición puede ser cualquier
+/// expresión. Si la condición no tiene ninguna igualdad entre columnas, cada fila se combina con todas las de la derecha.
+/// Con `USING` o `NATURAL`, cada par de columnas iguales se une en una sola columna sin calificar (`id`), que
+/// toma el valor del lado que no es `NULL`.
+pub struct HashJoin {
+    kind: JoinKind,
+    condition: Option<Expr>,
+    left_width: usize,
+    right_width: usize,
+    width: usize,
+    right_rows: Vec<Vec<Value>>,
+    keys: Vec<(usize, usize)>,
+    buckets: HashMap<Vec<String>, Vec<usize>>,
+    merged: Vec<(usize, usize, usize)>,
+    matched: Vec<Cell<bool>>,
+}
+
+impl HashJoin {
+    /// Crea el `JOIN`, agrega las columnas de la derecha a las de la consulta y arma la tabla hash.
+    ///
+    /// # Argumentos
+    /// * `join` - El `JOIN` a ejecutar.
+    /// * `headers` - Las columnas de la izquierda, calificadas; se le agregan las de la derecha y las unidas
+    ///   por `USING` o `NATURAL`.
+    /// * `visible` - Los índices de las columnas que reemplazan a `*`; se actualiza para mostrar una sola vez
+    ///   cada columna unida.
+    /// * `right_header` - Las columnas de la tabla de la derecha, sin calificar.
+    /// * `right_rows` - Todas las filas de la tabla de la derecha, ya tipadas.
+    ///
+    /// # Retorno
+    /// Devuelve el `HashJoin`, o un `SqlError` de tipo `ErrorType::InvalidColumn` si alguna columna de la
+    /// condición no existe o es ambigua.
+    ///
+    pub fn new(
+        join: &Join,
+        headers: &mut Vec<String>,
+        visible: &mut Vec<usize>,
+        right_header: &[String],
+        right_rows: Vec<Vec<Value>>,
+    ) -> Result<HashJoin, SqlError> {
+        let left_width = headers.len();
+        let right_headers = qualify(join.name(), right_header);
+        let mut condition = match &join.condition {
+            Some(JoinCondition::On(condition)) => Some(condition.clone()),
+            _ => None,
+        };
+        let pairs = shared_columns(join, headers, right_header)?;
+        for &(_, left, right) in &pairs {
+            let equality = Expr::Compare {
+                left: Box::new(Expr::Column(headers[left].to_string())),
+                operator: "=".to_string(),
+                right: Box::new(Expr::Column(right_headers[right].to_string())),
+            };
+            condition = Some(match condition {
+                Some(condition) => Expr::And(Box::new(condition), Box::new(equality)),
+                None => equality,
+            });
+        }
+
+        headers.extend(right_headers);
+        let mut merged = Vec::new();
+        for (column, left, right) in pairs {
+            // Si la columna de la izquierda ya es una columna unida, se actualiza en lugar de repetirse.
+            let target = if headers[left].contains('.') {
+                headers.push(column);
+                headers.len() - 1
+            } else {
+                left
+            };
+            merged.push((target, left, right));
+        }
+        *visible = merged_visible(visible, &merged, left_width, right_header.len());
+
+        let mut keys = Vec::new();
+        if let Some(condition) = &condition {
+            let headers: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
+            equality_keys(condition, &headers, left_width, &mut keys)?;
+        }
+        let mut buckets: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+        if !keys.is_empty() {
+            for (index, row) in right_rows.iter().enumerate() {
+                let key = keys
+                    .iter()
+                    .map(|&(_, right)| join_key(&row[right]))
+                    .collect();
+                if let Some(key) = key {
+                    buckets.entry(key).or_default().push(index);
+                }
+            }
+        }
+        Ok(HashJoin {
+            kind: join.kind,
+            condition,
+            left_width,
+            right_width: right_header.len(),
+            width: headers.len(),
+            matched: vec![Cell::new(false); right_rows.len()],
+            right_rows,
+            keys,
+            buckets,
+            merged,
+        })
+    }
+
+    /// Combina una fila de la izquierda con las filas de la derecha que cumplen la condición.
+    ///
+    /// # Argumentos
+    /// * `left` - Los valores de la fila de la izquierda.
+    /// * `headers` - Las columnas de la consulta; se usan las que existen hasta este `JOIN`.
+    ///
+    /// # Retorno
+    /// Devuelve las filas combinadas (vacío si ninguna cumple la condición, o la fila con las columnas de la
+    /// derecha en `NULL` en un `LEFT` o `FULL JOIN`), o un `SqlError` si la condición no puede evaluarse.
+    ///
+    pub fn probe(&self, left: &[Value], headers: &[&str]) -> Result<Vec<Vec<Value>>, SqlError> {
+        let headers = &headers[..self.width];
+        let mut rows = Vec::new();
+        for index in self.candidates(left) {
+            let row = self.combine(Some(left), Some(&self.right_rows[index]));
+            let matches = match &self.condition {
+                Some(condition) => condition.is_true(&row, headers)?,
+                None => true,
+            };
+            if matches {
+                self.matched[index].set(true);
+                rows.push(self.merge(row));
+            }
+        }
+        if rows.is_empty() && self.kind.keeps_left() {
+            rows.push(self.merge(self.combine(Some(left), None)));
+        }
+        Ok(rows)
+    }
+
+    /// Devuelve las filas de la derecha que no se combinaron con ninguna fila de la izquierda, con las columnas
+    /// de la izquierda en `NULL`, si el `JOIN` las conserva (`RIGHT` o `FULL`). Debe llamarse luego de combinar
+    /// todas las filas de la izquierda.
+    pub fn unmatched(&self) -> Vec<Vec<Value>> {
+        if !self.kind.keeps_right() {
+            return Vec::new();
+        }
+        self.right_rows
+            .iter()
+            .zip(&self.matched)
+            .filter(|(_, matched)| !matched.get())
+            .map(|(right, _)| self.merge(self.combine(None, Some(right))))
+            .collect()
+    }
+
+    /// Devuelve los índices de las filas de la derecha que pueden combinarse con la fila de la izquierda.
+    fn candidates(&self, left: &[Value]) -> Vec<usize> {
+        if self.keys.is_empty() {
+            return (0..self.right_rows.len()).collect();
+        }
+        let key: Option<Vec<String>> = self
+            .keys
+            .iter()
+            .map(|&(left_index, _)| join_key(&left[left_index]))
+            .collect();
+        key.and_then(|key| self.buckets.get(&key))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Arma la fila combinada, con `NULL` en las columnas del lado que falta y en las columnas unidas.
+    fn combine(&self, left: Option<&[Value]>, right: Option<&[Value]>) -> Vec<Value> {
+        let mut row = Vec::with_capacity(self.width);
+        match left {
+            Some(left) => row.extend_from_slice(left),
+            None => row.resize(self.left_width, Value::Null),
+        }
+        match right {
+            Some(right) => row.extend_from_slice(right),
+            None => row.resize(self.left_width + self.right_width, Value::Null),
+        }
+        row.resize(self.width, Value::Null);
+        row
+    }
+
+    /// Completa las columnas unidas por `USING` o `NATURAL` con el valor del lado que no es `NULL`.
+    fn merge(&self, mut row: Vec<Value>) -> Vec<Value> {
+        for &(target, left, right) in &self.merged {
+            let value = match &row[left] {
+                Value::Null => row[self.left_width + right].clone(),
+                value => value.clone(),
+            };
+            row[target] = value;
+        }
+        row
+    }
+}
+
+/// Resuelve las columnas de `USING` o `NATURAL`: devuelve, para cada una, su nombre, su índice entre las
+/// columnas de la izquierda y su índice en la tabla de la derecha. Con `NATURAL`, son las columnas de la
+/// derecha cuyo nombre (sin calificar) también está a la izquierda.
+fn shared_columns(
+    join: &Join,
+    headers: &[String],
+    right_header: &[String],
+) -> Result<Vec<(String, usize, usize)>, SqlError> {
+    let columns = match &join.condition {
+        Some(JoinCondition::Using(columns)) => columns.clone(),
+        Some(JoinCondition::Natural) => right_header
+            .iter()
+            .filter(|column| {
+                headers
+                    .iter()
+                    .any(|header| header.rsplit('.').next() == Some(column.as_str()))
+            })
+            .cloned()
+            .collect(),
+        _ => return Ok(Vec::new()),
+    };
+    let headers: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
+    columns
+        .into_iter()
+        .map(|column| {
+            let left = find_column(&headers, &column)?;
+            let right = right_header
+                .iter()
+                .position(|header| *header == column)
+                .ok_or_else(|| {
+                    SqlError::new(ErrorType::InvalidColumn, "La columna no existe")
+                        .with_context(&format!("{}.{}", join.name(), column))
+                })?;
+            Ok((column, left, right))
+        })
+        .collect()
+}
+
+/// Calcula las columnas que reemplazan a `*` luego de un `JOIN`: como en SQL, primero las columnas unidas por
+/// `USING` o `NATURAL`, luego las de la izquierda y luego las de la derecha, sin las columnas que se unieron.
+fn merged_visible(
+    visible: &[usize],
+    merged: &[(usize, usize, usize)],
+    left_width: usize,
+    right_width: usize,
+) -> Vec<usize> {
+    let new_columns = merged
+        .iter()
+        .map(|&(target, _, _)| target)
+        .filter(|&target| target >= left_width);
+    let left_columns = visible.iter().copied().filter(|&index| {
+        merged
+            .iter()
+            .all(|&(target, left, _)| index != left || target == left)
+    });
+    let right_columns = (0..right_width)
+        .filter(|&index| merged.iter().all(|&(_, _, right)| index != right))
+        .map(|index| left_width + index);
+    new_columns
+        .chain(left_columns)
+        .chain(right_columns)
+        .collect()
+}
+
+/// Busca en la condición las igualdades entre una columna de la izquierda y una de la derecha unidas por
+/// `AND`, y agrega a `keys` el índice de cada columna en su propia fila.
+fn equality_keys(
+    condition: &Expr,
+    headers: &[&str],
+    left_width: usize,
+    keys: &mut Vec<(usize, usize)>,
+) -> Result<(), SqlError> {
+    match condition {
+        Expr::And(left, right) => {
+            equality_keys(left, headers, left_width, keys)?;
+            equality_keys(right, headers, left_width, keys)
+        }
+        Expr::Compare {
+            left,
+            operator,
+            right,
+        } if operator == "=" => {
+            if let (Expr::Column(a), Expr::Column(b)) = (left.as_ref(), right.as_ref()) {
+                let (a, b) = (find_column(headers, a)?, find_column(headers, b)?);
+                if a < left_width && b >= left_width {
+                    keys.push((a, b - left_width));
+                } else if b < left_width && a >= left_width {
+                    keys.push((b, a - left_width));
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Devuelve la clave con la que se agrupa un valor en la tabla hash, o `None` si es `NULL` (que nunca es igual
+/// a otro valor). Dos valores iguales según `operations::compare_values` tienen siempre la misma clave: los
+/// números se comparan como `f64`, las cadenas que representan otro tipo se convierten, y las fechas con hora
+/// se agrupan por su fecha. Dos valores distintos pueden compartir clave, ya que la condición se vuelve a evaluar.
+fn join_key(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::Str(s) => match cast_to_value(s.trim()) {
+            Value::Str(_) => Some(s.to_string()),
+            value => join_key(&value),
+        },
+        Value::Int(_) | Value::BigInt(_) | Value::Float(_) => {
+            get_float_value(value).map(|v| (v + 0.0).to_string())
+        }
+        Value::Timestamp(timestamp) => Some(timestamp.date().to_string()),
+        Value::Interval(_) => Some("interval".to_string()),
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{expression::parse_expression, lexer::tokenize};
+
+    fn join(kind: JoinKind, condition: Option<JoinCondition>) -> Join {
+        Join {
+            kind,
+            table_name: "clientes".to_string(),
+            alias: Some("c".to_string()),
+            condition,
+        }
+    }
+
+    fn on(condition: &str) -> Option<JoinCondition> {
+        Some(JoinCondition::On(
+            parse_expression(&tokenize(condition).unwrap()).unwrap(),
+        ))
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn clientes() -> Vec<Vec<Value>> {
+        vec![
+            vec![Value::Int(1), Value::Str("Juan".to_string())],
+            vec![Value::Int(2), Value::Str("Ana".to_string())],
+            vec![Value::Null, Value::Str("Sin id".to_string())],
+        ]
+    }
+
+    /// Crea el `JOIN` de una tabla de órdenes `o (id, id_cliente)` con `clientes c (id, nombre)`.
+    fn hash_join(join: &Join) -> Result<(HashJoin, Vec<String>, Vec<usize>), SqlError> {
+        let mut headers = strings(&["o.id", "o.id_cliente"]);
+        let mut visible = vec![0, 1];
+        let hash_join = HashJoin::new(
+            join,
+            &mut headers,
+            &mut visible,
+            &strings(&["id", "nombre"]),
+            clientes(),
+        )?;
+        Ok((hash_join, headers, visible))
+    }
+
+    fn probe(hash_join: &HashJoin, headers: &[String], left: &[Value]) -> Vec<Vec<Value>> {
+        let headers: Vec<&str> = headers.iter().map(|s| s.as_str()).collect();
+        hash_join.probe(left, &headers).unwrap()
+    }
+
+    #[test]
+    fn test_inner_join_matches_by_key() {
+        let join = join(JoinKind::Inner, on("o.id_cliente = c.id"));
+        let (hash_join, headers, visible) = hash_join(&join).unwrap();
+        assert_eq!(hash_join.keys, vec![(1, 0)]);
+        assert_eq!(
+            headers,
+            strings(&["o.id", "o.id_cliente", "c.id", "c.nombre"])
+        );
+        assert_eq!(visible, vec![0, 1, 2, 3]);
+
+        let rows = probe(&hash_join, &headers, &[Value::Int(101), Value::Int(2)]);
+        assert_eq!(
+            rows,
+            vec![vec![
+                Value::Int(101),
+                Value::Int(2),
+                Value::Int(2),
+                Value::Str("Ana".to_string())
+            ]]
+        );
+        assert!(probe(&hash_join, &headers, &[Value::Int(102), Value::Int(7)]).is_empty());
+        assert!(hash_join.unmatched().is_empty());
+    }
+
+    #[test]
+    fn test_null_keys_never_match() {
+        let join = join(JoinKind::Inner, on("c.id = o.id_cliente"));
+        let (hash_join, headers, _) = hash_join(&join).unwrap();
+        assert_eq!(hash_join.keys, vec![(1, 0)]);
+        assert!(probe(&hash_join, &headers, &[Value::Int(101), Value::Null]).is_empty());
+    }
+
+    #[test]
+    fn test_left_join_pads_with_nulls() {
+        let join = join(JoinKind::Left, on("o.id_cliente = c.id"));
+        let (hash_join, headers, _) = hash_join(&join).unwrap();
+        let rows = probe(&hash_join, &headers, &[Value::Int(101), Value::Int(9)]);
+        assert_eq!(
+            rows,
+            vec![vec![
+                Value::Int(101),
+                Value::Int(9),
+                Value::Null,
+                Value::Null
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_right_and_full_join_return_unmatched_rows() {
+        for kind in [JoinKind::Right, JoinKind::Full] {
+            let join = join(kind, on("o.id_cliente = c.id"));
+            let (hash_join, headers, _) = hash_join(&join).unwrap();
+            let rows = probe(&hash_join, &headers, &[Value::Int(101), Value::Int(9)]);
+            assert_eq!(rows.len(), usize::from(kind == JoinKind::Full));
+            probe(&hash_join, &headers, &[Value::Int(102), Value::Int(1)]);
+
+            let unmatched = hash_join.unmatched();
+            assert_eq!(
+                unmatched,
+                vec![
+                    vec![
+                        Value::Null,
+                        Value::Null,
+                        Value::Int(2),
+                        Value::Str("Ana".to_string())
+                    ],
+                    vec![
+                        Value::Null,
+                        Value::Null,
+                        Value::Null,
+                        Value::Str("Sin id".to_string())
+                    ],
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cross_join_combines_every_row() {
+        let join = join(JoinKind::Cross, None);
+        let (hash_join, headers, _) = hash_join(&join).unwrap();
+        let rows = probe(&hash_join, &headers, &[Value::Int(101), Value::Int(1)]);
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn test_using_merges_shared_columns() {
+        let join = join(
+            JoinKind::Full,
+            Some(JoinCondition::Using(vec!["id".to_string()])),
+        );
+        let (hash_join, headers, visible) = hash_join(&join).unwrap();
+        assert_eq!(
+            headers,
+            strings(&["o.id", "o.id_cliente", "c.id", "c.nombre", "id"])
+        );
+        assert_eq!(visible, vec![4, 1, 3]);
+        assert_eq!(hash_join.keys, vec![(0, 0)]);
+
+        let rows = probe(&hash_join, &headers, &[Value::Int(9), Value::Int(1)]);
+        assert_eq!(rows[0][4], Value::Int(9));
+        let unmatched = hash_join.unmatched();
+        assert_eq!(unmatched[0][4], Value::Int(1));
+    }
+
+    #[test]
+    fn test_natural_join_uses_columns_with_the_same_name() {
+        let join = join(JoinKind::Inner, Some(JoinCondition::Natural));
+        let (hash_join, headers, _) = hash_join(&join).unwrap();
+        assert_eq!(hash_join.keys, vec![(0, 0)]);
+        assert_eq!(
+            probe(&hash_join, &headers, &[Value::Int(2), Value::Int(1)]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_join_rechecks_full_condition() {
+        let join = join(
+            JoinKind::Inner,
+            on("o.id_cliente = c.id AND c.nombre = 'Juan'"),
+        );
+        let (hash_join, headers, _) = hash_join(&join).unwrap();
+        assert_eq!(
+            probe(&hash_join, &headers, &[Value::Int(101), Value::Int(1)]).len(),
+            1
+        );
+        assert!(probe(&hash_join, &headers, &[Value::Int(102), Value::Int(2)]).is_empty());
+    }
+
+    #[test]
+    fn test_join_without_equality_compares_every_row() {
+        let join = join(JoinKind::Inner, on("o.id_cliente < c.id"));
+        let (hash_join, headers, _) = hash_join(&join).unwrap();
+        assert!(hash_join.keys.is_empty());
+        let rows = probe(&hash_join, &headers, &[Value::Int(101), Value::Int(1)]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][2], Value::Int(2));
+    }
+
+    #[test]
+    fn test_join_key_matches_equal_values_of_different_types() {
+        assert_eq!(join_key(&Value::Int(1)), join_key(&Value::Float(1.0)));
+        assert_eq!(
+            join_key(&Value::Int(1)),
+            join_key(&Value::Str("1".to_string()))
+        );
+        assert_eq!(join_key(&Value::Null), None);
+    }
+
+    #[test]
+    fn test_join_with_unknown_column_fails() {
+        let join_on = join(JoinKind::Inner, on("o.id_cliente = c.apellido"));
+        assert!(hash_join(&join_on).is_err());
+        let join_using = join(
+            JoinKind::Inner,
+            Some(JoinCondition::Using(vec!["id_cliente".to_string()])),
+        );
+        assert!(hash_join(&join_using).is_err());
+    }
+}
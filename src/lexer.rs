@@ -0,0 +1,349 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crate::error::{ErrorType, SqlError};
+
+/// Palabras reservadas del lenguaje. Se reconocen sin distinguir mayúsculas de minúsculas.
+const KEYWORDS: [&str; 52] = [
+    "select", "from", "where", "insert", "into", "values", "update", "set", "delete", "and", "or",
+    "not", "order", "by", "asc", "desc", "ilike", "create", "drop", "table", "if", "exists",
+    "alter", "add", "column", "rename", "to", "default", "is", "null", "true", "false", "group",
+    "having", "limit", "offset", "distinct", "join", "inner", "left", "outer", "on", "as", "right",
+    "full", "cross", "natural", "using", "like", "in", "between", "escape",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa las distintas clases de tokens que produce el lexer.
+///
+/// # Variantes
+/// * `Keyword` - Palabra reservada (`SELECT`, `FROM`, `WHERE`, ...).
+/// * `Identifier` - Nombre de tabla o de columna.
+/// * `String` - Literal de cadena delimitado por comillas simples.
+/// * `Number` - Literal numérico.
+/// * `Operator` - Operador (`=`, `<`, `>=`, `<>`, `*`, ...).
+/// * `Comma`, `LeftParen`, `RightParen`, `Semicolon`, `Dot` - Signos de puntuación.
+pub enum TokenKind {
+    Keyword,
+    Identifier,
+    String,
+    Number,
+    Operator,
+    Comma,
+    LeftParen,
+    RightParen,
+    Semicolon,
+    Dot,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa un token de la consulta, con los parámetros:
+///
+/// * `kind` - La clase de token.
+/// * `value` - El texto del token. Las palabras reservadas se guardan en minúsculas y los
+///   literales de cadena sin las comillas que los delimitan.
+/// * `position` - La posición (en bytes) donde comienza el token dentro de la consulta.
+///
+pub struct Token {
+    pub kind: TokenKind,
+    pub value: String,
+    pub position: usize,
+}
+
+impl Token {
+    /// Devuelve `true` si el token es la palabra reservada indicada (en minúsculas).
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Keyword && self.value == keyword
+    }
+
+    /// Devuelve `true` si el token es el operador indicado.
+    pub fn is_operator(&self, operator: &str) -> bool {
+        self.kind == TokenKind::Operator && self.value == operator
+    }
+
+    /// Devuelve `true` si el token es un literal (cadena o número).
+    pub fn is_literal(&self) -> bool {
+        self.kind == TokenKind::String || self.kind == TokenKind::Number
+    }
+}
+
+/// Divide una consulta SQL en tokens.
+///
+/// # Argumentos
+/// * `query` - La consulta SQL en formato de cadena.
+///
+/// # Retorno
+/// Devuelve un `Vec<Token>` con los tokens de la consulta, o un `SqlError` de tipo `ErrorType::InvalidSyntax`
+/// (con la posición del problema) si la consulta contiene un carácter inesperado o una cadena sin cerrar.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::lexer::{tokenize, TokenKind};
+///
+/// let tokens = tokenize("SELECT * FROM clientes WHERE ciudad = 'New York'").unwrap();
+/// assert_eq!(tokens.len(), 8);
+/// assert_eq!(tokens[7].kind, TokenKind::String);
+/// assert_eq!(tokens[7].value, "New York");
+/// ```
+pub fn tokenize(query: &str) -> Result<Vec<Token>, SqlError> {
+    let mut lexer = Lexer {
+        chars: query.char_indices().peekable(),
+        tokens: Vec::new(),
+    };
+    while let Some(&(position, c)) = lexer.chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                lexer.chars.next();
+                continue;
+            }
+            '\'' => lexer.read_quoted(position, '\'', TokenKind::String)?,
+            '"' => lexer.read_quoted(position, '"', TokenKind::Identifier)?,
+            c if c.is_ascii_digit() => lexer.read_number(position),
+            '-' if lexer.starts_negative_number() => lexer.read_number(position),
+            c if c.is_alphabetic() || c == '_' => lexer.read_word(position),
+            ',' => lexer.read_punctuation(position, TokenKind::Comma),
+            '(' => lexer.read_punctuation(position, TokenKind::LeftParen),
+            ')' => lexer.read_punctuation(position, TokenKind::RightParen),
+            ';' => lexer.read_punctuation(position, TokenKind::Semicolon),
+            '.' => lexer.read_punctuation(position, TokenKind::Dot),
+            '=' | '<' | '>' | '!' | '|' | '+' | '-' | '*' | '/' | '%' => {
+                lexer.read_operator(position)?
+            }
+            _ => {
+                return Err(
+                    SqlError::new(ErrorType::InvalidSyntax, "Carácter inesperado")
+                        .with_context(&c.to_string())
+                        .with_position(position),
+                );
+            }
+        };
+        lexer.tokens.push(token);
+    }
+    Ok(lexer.tokens)
+}
+
+/// Estado interno del lexer: los caracteres pendientes y los tokens ya reconocidos.
+struct Lexer<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    tokens: Vec<Token>,
+}
+
+impl Lexer<'_> {
+    /// Lee un literal delimitado por `quote`. Dos delimitadores seguidos representan
+    /// el delimitador literal (por ejemplo `'O''Brien'`).
+    fn read_quoted(
+        &mut self,
+        position: usize,
+        quote: char,
+        kind: TokenKind,
+    ) -> Result<Token, SqlError> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, c)) if c == quote => {
+                    if matches!(self.chars.peek(), Some(&(_, next)) if next == quote) {
+                        self.chars.next();
+                        value.push(quote);
+                    } else {
+                        return Ok(Token {
+                            kind,
+                            value,
+                            position,
+                        });
+                    }
+                }
+                Some((_, c)) => value.push(c),
+                None => {
+                    return Err(SqlError::new(ErrorType::InvalidSyntax, "Cadena sin cerrar")
+                        .with_context(&value)
+                        .with_position(position));
+                }
+            }
+        }
+    }
+
+    /// Lee un literal numérico, con signo y parte decimal opcionales.
+    fn read_number(&mut self, position: usize) -> Token {
+        let mut value = String::new();
+        if let Some(&(_, '-')) = self.chars.peek() {
+            value.push('-');
+            self.chars.next();
+        }
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_ascii_digit() || (c == '.' && !value.contains('.')) {
+                value.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        Token {
+            kind: TokenKind::Number,
+            value,
+            position,
+        }
+    }
+
+    /// Lee una palabra y decide si es una palabra reservada o un identificador.
+    fn read_word(&mut self, position: usize) -> Token {
+        let mut value = String::new();
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_alphanumeric() || c == '_' {
+                value.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        let lowercase = value.to_lowercase();
+        if KEYWORDS.contains(&lowercase.as_str()) {
+            Token {
+                kind: TokenKind::Keyword,
+                value: lowercase,
+                position,
+            }
+        } else {
+            Token {
+                kind: TokenKind::Identifier,
+                value,
+                position,
+            }
+        }
+    }
+
+    /// Lee un signo de puntuación de un único carácter.
+    fn read_punctuation(&mut self, position: usize, kind: TokenKind) -> Token {
+        let value = self
+            .chars
+            .next()
+            .map(|(_, c)| c.to_string())
+            .unwrap_or_default();
+        Token {
+            kind,
+            value,
+            position,
+        }
+    }
+
+    /// Lee un operador, que puede ocupar uno o dos caracteres (`<=`, `>=`, `<>`, `!=`, `||`).
+    fn read_operator(&mut self, position: usize) -> Result<Token, SqlError> {
+        let first = self.chars.next().map(|(_, c)| c).unwrap_or_default();
+        let mut value = first.to_string();
+        if let Some(&(_, second)) = self.chars.peek() {
+            let pair = matches!(
+                (first, second),
+                ('<', '=') | ('>', '=') | ('<', '>') | ('!', '=') | ('|', '|')
+            );
+            if pair {
+                value.push(second);
+                self.chars.next();
+            }
+        }
+        if value == "!" || value == "|" {
+            return Err(SqlError::new(ErrorType::InvalidSyntax, "Operador inválido")
+                .with_context(&value)
+                .with_position(position));
+        }
+        Ok(Token {
+            kind: TokenKind::Operator,
+            value,
+            position,
+        })
+    }
+
+    /// Determina si el `-` actual es el signo de un número negativo y no una resta,
+    /// mirando el carácter siguiente y el token anterior.
+    fn starts_negative_number(&self) -> bool {
+        let mut lookahead = self.chars.clone();
+        lookahead.next();
+        let followed_by_digit = matches!(lookahead.peek(), Some(&(_, c)) if c.is_ascii_digit());
+        let after_value = matches!(
+            self.tokens.last().map(|token| &token.kind),
+            Some(TokenKind::Identifier)
+                | Some(TokenKind::Number)
+                | Some(TokenKind::String)
+                | Some(TokenKind::RightParen)
+        );
+        followed_by_digit && !after_value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|t| t.value.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tokenize_string_with_spaces() {
+        let tokens = tokenize("select * from t where ciudad = 'New York'").unwrap();
+        assert_eq!(tokens[7].kind, TokenKind::String);
+        assert_eq!(tokens[7].value, "New York");
+        assert_eq!(tokens[7].position, 31);
+    }
+
+    #[test]
+    fn test_tokenize_escaped_quote() {
+        let tokens = tokenize("'O''Brien'").unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].value, "O'Brien");
+    }
+
+    #[test]
+    fn test_tokenize_punctuation_without_spaces() {
+        let tokens = tokenize("insert into t (a,b) values (1,'x');").unwrap();
+        assert_eq!(
+            values(&tokens),
+            vec![
+                "insert", "into", "t", "(", "a", ",", "b", ")", "values", "(", "1", ",", "x", ")",
+                ";"
+            ]
+        );
+        assert_eq!(tokens[5].kind, TokenKind::Comma);
+        assert_eq!(tokens[14].kind, TokenKind::Semicolon);
+    }
+
+    #[test]
+    fn test_tokenize_keywords_case_insensitive() {
+        let tokens = tokenize("SeLeCt Nombre FROM clientes").unwrap();
+        assert!(tokens[0].is_keyword("select"));
+        assert_eq!(tokens[1].kind, TokenKind::Identifier);
+        assert_eq!(tokens[1].value, "Nombre");
+        assert!(tokens[2].is_keyword("from"));
+    }
+
+    #[test]
+    fn test_tokenize_operators_and_numbers() {
+        let tokens = tokenize("a>=-5 and b<>2.5 or c!=d").unwrap();
+        assert_eq!(
+            values(&tokens),
+            vec!["a", ">=", "-5", "and", "b", "<>", "2.5", "or", "c", "!=", "d"]
+        );
+        assert_eq!(tokens[2].kind, TokenKind::Number);
+        assert!(tokens[5].is_operator("<>"));
+    }
+
+    #[test]
+    fn test_tokenize_subtraction_is_operator() {
+        let tokens = tokenize("cantidad-1").unwrap();
+        assert_eq!(values(&tokens), vec!["cantidad", "-", "1"]);
+    }
+
+    #[test]
+    fn test_tokenize_unterminated_string() {
+        let error = tokenize("select 'abc").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+        assert_eq!(error.position(), Some(7));
+    }
+
+    #[test]
+    fn test_tokenize_unexpected_character() {
+        let error = tokenize("select # from t").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+        assert_eq!(error.context(), Some("#"));
+        assert_eq!(error.position(), Some(7));
+    }
+}
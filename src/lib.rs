@@ -1,5 +1,14 @@
+pub mod aggregate;
+pub mod csv;
+pub mod datetime;
 pub mod error;
+pub mod expression;
 pub mod extras;
+pub mod functions;
+pub mod join;
+pub mod lexer;
 pub mod operations;
 pub mod query;
+pub mod result;
+pub mod schema;
 pub mod utils;
@@ -1,22 +1,21 @@
-use error::{print_error, ErrorType};
+use sql::error::{print_error, ErrorType, SqlError};
 use sql::query::parse_query;
 
 use std::env;
 
-pub mod error;
-pub mod extras;
-
 fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() != 3 {
         let error_description = "Uso: cargo run -- ruta/a/tablas \"<consulta>\"";
-        let error = ErrorType::InvalidSyntax;
-        print_error(error, error_description);
+        print_error(&SqlError::new(ErrorType::InvalidSyntax, error_description));
         return;
     }
     let query = &args[2];
     let path = &args[1];
 
-    if let Err(_error) = parse_query(path, query) {}
+    match parse_query(path, query) {
+        Ok(result) => print!("{}", result),
+        Err(error) => print_error(&error),
+    }
 }
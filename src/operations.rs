@@ -1,6 +1,12 @@
 /// Este módulo define operadores para comparar tipos `Value` y aplica la lógica
 /// de comparación correspondiente basada en el operador proporcionado.
-use crate::extras::{get_int_value, get_str_value, Value};
+use std::cmp::Ordering;
+
+use crate::{
+    datetime::{compare_temporal, date_arithmetic},
+    error::{ErrorType, SqlError},
+    extras::{cast_to_value, get_float_value, integer_value, Value},
+};
 
 /// El trait `Operations` se utiliza para definir una interfaz común para
 /// diferentes operadores de comparación. Cada operador implementará este rasgo
@@ -17,21 +23,88 @@ trait Operations {
     /// * `false` en caso contrario.
     fn apply_operation(&self, value1: Value, value2: Value) -> bool;
 }
+/// Compara dos valores aplicando las reglas de promoción numérica.
+///
+/// # Argumentos
+/// * `value1` - El primer `Value` a comparar.
+/// * `value2` - El segundo `Value` a comparar.
+///
+/// # Retorna
+/// * `Some(Ordering)` si los valores pueden compararse:
+///   - Los números (`Int`, `BigInt` y `Float`) se comparan por su valor numérico: dos enteros se comparan
+///     como enteros de 64 bits, y si alguno es `Float`, ambos se promueven a `f64`.
+///   - Las cadenas se comparan en orden lexicográfico y los booleanos con `false < true`.
+///   - Las fechas, horas, timestamps e intervalos se comparan cronológicamente (ver `datetime::compare_temporal`).
+///   - Una cadena que representa un número, un booleano o una fecha (por ejemplo `'25'`, `'true'` o `'2024-01-15'`)
+///     se compara con un valor de ese tipo como si fuera de ese tipo.
+/// * `None` si los valores son de tipos que no pueden compararse, o si alguno es `NULL`.
+///
+/// # Ejemplo
+/// ```rust
+/// use std::cmp::Ordering;
+/// use sql::extras::Value;
+/// use sql::operations::compare_values;
+///
+/// let a = Value::Str("Laptop".to_string());
+/// let b = Value::Str("Mouse".to_string());
+/// assert_eq!(compare_values(&a, &b), Some(Ordering::Less));
+/// assert_eq!(compare_values(&a, &Value::Int(1)), None);
+/// assert_eq!(compare_values(&Value::Str("25".to_string()), &Value::Int(25)), Some(Ordering::Equal));
+/// assert_eq!(compare_values(&Value::Int(2), &Value::Float(1.5)), Some(Ordering::Greater));
+/// assert_eq!(compare_values(&Value::BigInt(3_000_000_000), &Value::Int(7)), Some(Ordering::Greater));
+/// ```
+pub fn compare_values(value1: &Value, value2: &Value) -> Option<Ordering> {
+    match (value1, value2) {
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Str(s1), Value::Str(s2)) => Some(s1.cmp(s2)),
+        (Value::Bool(b1), Value::Bool(b2)) => Some(b1.cmp(b2)),
+        (Value::Str(s1), _) => match cast_to_value(s1.trim()) {
+            Value::Str(_) => None,
+            value1 => compare_values(&value1, value2),
+        },
+        (_, Value::Str(s2)) => match cast_to_value(s2.trim()) {
+            Value::Str(_) => None,
+            value2 => compare_values(value1, &value2),
+        },
+        _ => compare_temporal(value1, value2).or_else(|| compare_numbers(value1, value2)),
+    }
+}
+
+/// Compara dos valores numéricos, promoviéndolos a `i64` si ambos son enteros o a `f64` en otro caso.
+/// Devuelve `None` si alguno de los valores no es numérico.
+fn compare_numbers(value1: &Value, value2: &Value) -> Option<Ordering> {
+    match (get_integer(value1), get_integer(value2)) {
+        (Some(i1), Some(i2)) => Some(i1.cmp(&i2)),
+        _ => get_float_value(value1)?.partial_cmp(&get_float_value(value2)?),
+    }
+}
+
+/// Obtiene el valor de un entero (`Int` o `BigInt`) como `i64`.
+fn get_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(v) => Some(i64::from(*v)),
+        Value::BigInt(v) => Some(*v),
+        _ => None,
+    }
+}
+
 /// `EqualOperator` es una implementación del trait `Operations` que verifica
 /// si dos valores son iguales.
 struct EqualOperator;
 
 impl Operations for EqualOperator {
     fn apply_operation(&self, value1: Value, value2: Value) -> bool {
-        let int_value1 = get_int_value(&value1);
-        let int_value2 = get_int_value(&value2);
-        let str_value1 = get_str_value(&value1);
-        let str_value2 = get_str_value(&value2);
-        match (int_value1, int_value2, str_value1, str_value2) {
-            (Some(i1), Some(i2), _, _) => i1 == i2,
-            (_, _, Some(s1), Some(s2)) => s1 == s2,
-            _ => false,
-        }
+        compare_values(&value1, &value2) == Some(Ordering::Equal)
+    }
+}
+
+/// `NotEqualOperator` es una implementación del trait `Operations` que verifica
+/// si dos valores son distintos. Se usa tanto para `!=` como para `<>`.
+struct NotEqualOperator;
+
+impl Operations for NotEqualOperator {
+    fn apply_operation(&self, value1: Value, value2: Value) -> bool {
+        !EqualOperator.apply_operation(value1, value2)
     }
 }
 
@@ -41,15 +114,7 @@ struct GreaterThanOperator;
 
 impl Operations for GreaterThanOperator {
     fn apply_operation(&self, value1: Value, value2: Value) -> bool {
-        let int_value1 = get_int_value(&value1);
-        let int_value2 = get_int_value(&value2);
-        let str_value1 = get_str_value(&value1);
-        let str_value2 = get_str_value(&value2);
-        match (int_value1, int_value2, str_value1, str_value2) {
-            (Some(i1), Some(i2), _, _) => i1 > i2,
-            (_, _, Some(_s1), Some(_s2)) => false,
-            _ => false,
-        }
+        compare_values(&value1, &value2) == Some(Ordering::Greater)
     }
 }
 
@@ -59,15 +124,33 @@ struct LessThanOperator;
 
 impl Operations for LessThanOperator {
     fn apply_operation(&self, value1: Value, value2: Value) -> bool {
-        let int_value1 = get_int_value(&value1);
-        let int_value2 = get_int_value(&value2);
-        let str_value1 = get_str_value(&value1);
-        let str_value2 = get_str_value(&value2);
-        match (int_value1, int_value2, str_value1, str_value2) {
-            (Some(i1), Some(i2), _, _) => i1 < i2,
-            (_, _, Some(_s1), Some(_s2)) => false,
-            _ => false,
-        }
+        compare_values(&value1, &value2) == Some(Ordering::Less)
+    }
+}
+
+/// `GreaterOrEqualOperator` es una implementación del trait `Operations` que verifica
+/// si el primer valor es mayor o igual que el segundo.
+struct GreaterOrEqualOperator;
+
+impl Operations for GreaterOrEqualOperator {
+    fn apply_operation(&self, value1: Value, value2: Value) -> bool {
+        matches!(
+            compare_values(&value1, &value2),
+            Some(Ordering::Greater) | Some(Ordering::Equal)
+        )
+    }
+}
+
+/// `LessOrEqualOperator` es una implementación del trait `Operations` que verifica
+/// si el primer valor es menor o igual que el segundo.
+struct LessOrEqualOperator;
+
+impl Operations for LessOrEqualOperator {
+    fn apply_operation(&self, value1: Value, value2: Value) -> bool {
+        matches!(
+            compare_values(&value1, &value2),
+            Some(Ordering::Less) | Some(Ordering::Equal)
+        )
     }
 }
 
@@ -78,12 +161,24 @@ impl Operations for LessThanOperator {
 /// * `value2` - El segundo `Value` a comparar.
 /// * `operator` - El operador de comparación, que puede ser uno de los siguientes:
 ///   - "=": Verifica si `value1` es igual a `value2`.
+///   - "!=" o "<>": Verifica si `value1` es distinto de `value2`.
 ///   - ">": Verifica si `value1` es mayor que `value2`.
 ///   - "<": Verifica si `value1` es menor que `value2`.
+///   - ">=": Verifica si `value1` es mayor o igual que `value2`.
+///   - "<=": Verifica si `value1` es menor o igual que `value2`.
+///   - "like": Verifica si `value1` cumple el patrón `value2` (ver `like`).
+///   - "ilike": Como "like", sin distinguir mayúsculas de minúsculas.
+///
+/// Los números se comparan numéricamente, promoviendo los enteros a `f64` si el otro valor es `Float`,
+/// y las cadenas de forma lexicográfica y exacta (salvo con "like" e "ilike"). Ver `compare_values`.
 ///
 /// # Retorna
-/// * `true` si la comparación basada en el operador tiene éxito.
-/// * `false` si la comparación falla o si se proporciona un operador no soportado.
+/// * `Ok(Some(true))` si la comparación basada en el operador tiene éxito.
+/// * `Ok(Some(false))` si la comparación falla.
+/// * `Ok(None)` si alguno de los valores es `NULL`: con la lógica de tres valores de SQL el resultado es
+///   desconocido, incluso para `NULL = NULL`.
+/// * Un error de tipo `ErrorType::InvalidSyntax` si se proporciona un operador no soportado, o de tipo
+///   `ErrorType::Error` si el patrón de "like" o "ilike" no es válido.
 ///
 /// # Ejemplo
 /// ```rust
@@ -94,15 +189,542 @@ impl Operations for LessThanOperator {
 /// let value2 = Value::Int(20);
 ///
 /// let resultado = filter(value1, value2, "<");
-/// assert_eq!(resultado, true);
+/// assert_eq!(resultado.ok(), Some(Some(true)));
+///
+/// let desconocido = filter(Value::Null, Value::Null, "=");
+/// assert_eq!(desconocido.ok(), Some(None));
 /// ```
-
-pub fn filter(value1: Value, value2: Value, operator: &str) -> bool {
+pub fn filter(value1: Value, value2: Value, operator: &str) -> Result<Option<bool>, SqlError> {
     let operator: Box<dyn Operations> = match operator {
         "=" => Box::new(EqualOperator),
+        "!=" | "<>" => Box::new(NotEqualOperator),
         ">" => Box::new(GreaterThanOperator),
         "<" => Box::new(LessThanOperator),
-        _ => return false,
+        ">=" => Box::new(GreaterOrEqualOperator),
+        "<=" => Box::new(LessOrEqualOperator),
+        "like" | "ilike" => return like(value1, value2, None, operator == "ilike"),
+        _ => {
+            return Err(
+                SqlError::new(ErrorType::InvalidSyntax, "Operador no soportado")
+                    .with_context(operator),
+            )
+        }
+    };
+    if value1.is_null() || value2.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(operator.apply_operation(value1, value2)))
+}
+
+/// Verifica si un valor cumple un patrón de `LIKE`, donde `%` representa cualquier secuencia de caracteres
+/// (incluso vacía) y `_` un único carácter.
+///
+/// # Argumentos
+/// * `value` - El valor a verificar. Los valores que no son cadenas se comparan por su texto.
+/// * `pattern` - El patrón.
+/// * `escape` - El carácter de escape de `ESCAPE`, que hace que el siguiente `%`, `_` o él mismo se tome
+///   literalmente. Sin `ESCAPE`, o con `ESCAPE ''`, no hay carácter de escape.
+/// * `case_insensitive` - Si la comparación no distingue mayúsculas de minúsculas (`ILIKE`).
+///
+/// # Retorna
+/// * `Ok(Some(true))` o `Ok(Some(false))` según si el valor cumple el patrón.
+/// * `Ok(None)` si alguno de los valores es `NULL`.
+/// * Un error de tipo `ErrorType::Error` si el carácter de escape tiene más de un carácter o si el patrón
+///   termina con él.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::extras::Value;
+/// use sql::operations::like;
+///
+/// let text = |s: &str| Value::Str(s.to_string());
+/// assert_eq!(like(text("Laptop Pro"), text("Lap%"), None, false).ok(), Some(Some(true)));
+/// assert_eq!(like(text("Laptop Pro"), text("lap%p_o"), None, true).ok(), Some(Some(true)));
+/// assert_eq!(like(text("50%"), text("50!%"), Some(text("!")), false).ok(), Some(Some(true)));
+/// assert_eq!(like(text("500"), text("50!%"), Some(text("!")), false).ok(), Some(Some(false)));
+/// ```
+pub fn like(
+    value: Value,
+    pattern: Value,
+    escape: Option<Value>,
+    case_insensitive: bool,
+) -> Result<Option<bool>, SqlError> {
+    let escape = match escape.map(|escape| escape.to_field()) {
+        None => None,
+        Some(None) => return Ok(None),
+        Some(Some(escape)) => {
+            let mut chars = escape.chars();
+            match (chars.next(), chars.next()) {
+                (escape_char, None) => escape_char,
+                _ => {
+                    return Err(SqlError::new(
+                        ErrorType::Error,
+                        "El carácter de escape debe ser un único carácter",
+                    )
+                    .with_context(&escape))
+                }
+            }
+        }
+    };
+    let (Some(value), Some(pattern)) = (value.to_field(), pattern.to_field()) else {
+        return Ok(None);
+    };
+    let pattern = like_pattern(&pattern, escape)?;
+    if case_insensitive {
+        let pattern: Vec<LikeToken> = pattern
+            .into_iter()
+            .flat_map(|token| match token {
+                LikeToken::Char(c) => c.to_lowercase().map(LikeToken::Char).collect(),
+                token => vec![token],
+            })
+            .collect();
+        return Ok(Some(like_match(&value.to_lowercase(), &pattern)));
+    }
+    Ok(Some(like_match(&value, &pattern)))
+}
+
+#[derive(Debug, PartialEq)]
+
+/// Un elemento de un patrón de `LIKE`: un carácter literal, `_` o `%`.
+enum LikeToken {
+    Char(char),
+    AnyChar,
+    AnyString,
+}
+
+/// Separa un patrón de `LIKE` en sus elementos, resolviendo el carácter de escape.
+fn like_pattern(pattern: &str, escape: Option<char>) -> Result<Vec<LikeToken>, SqlError> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            c if Some(c) == escape => match chars.next() {
+                Some(escaped) => LikeToken::Char(escaped),
+                None => {
+                    return Err(SqlError::new(
+                        ErrorType::Error,
+                        "El patrón no puede terminar con el carácter de escape",
+                    )
+                    .with_context(pattern))
+                }
+            },
+            '%' => LikeToken::AnyString,
+            '_' => LikeToken::AnyChar,
+            c => LikeToken::Char(c),
+        });
+    }
+    Ok(tokens)
+}
+
+/// Verifica si el texto cumple el patrón. Cuando un carácter no coincide, vuelve al último `%` y le hace
+/// abarcar un carácter más, por lo que no hace falta probar todas las combinaciones.
+fn like_match(value: &str, pattern: &[LikeToken]) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let (mut v, mut p) = (0, 0);
+    // La posición del patrón luego del último `%` y la del texto desde donde ese `%` sigue abarcando.
+    let mut backtrack = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(LikeToken::AnyString) => {
+                p += 1;
+                backtrack = Some((p, v));
+            }
+            Some(LikeToken::AnyChar) => {
+                p += 1;
+                v += 1;
+            }
+            Some(LikeToken::Char(c)) if *c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match backtrack {
+                Some((after_wildcard, start)) => {
+                    p = after_wildcard;
+                    v = start + 1;
+                    backtrack = Some((after_wildcard, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..]
+        .iter()
+        .all(|token| *token == LikeToken::AnyString)
+}
+
+/// Aplica un operador aritmético (`+`, `-`, `*`, `/` o `%`) o de concatenación (`||`) entre dos valores.
+///
+/// # Argumentos
+/// * `value1` - El primer operando.
+/// * `value2` - El segundo operando.
+/// * `operator` - El operador a aplicar.
+///
+/// # Retorna
+/// Devuelve el resultado de la operación, o `Value::Null` si alguno de los operandos es `NULL`.
+/// Las cadenas se convierten antes con `cast_to_value`, por lo que `'2024-05-01' + INTERVAL '1 day'`
+/// opera con fechas. Si la operación no está definida para esos valores, si divide por cero, si un resultado
+/// entero no entra en 64 bits o si una fecha queda fuera del rango `0000-01-01` a `9999-12-31`, devuelve un
+/// error de tipo `ErrorType::Error`.
+///
+/// # Notas
+/// Entre números, las operaciones son enteras si ambos operandos son enteros (la división trunca, como en SQL)
+/// y de punto flotante en otro caso. Entre fechas se soportan fechas, horas y timestamps con intervalos,
+/// fechas con una cantidad entera de días y la diferencia entre dos fechas o timestamps. `||` concatena el
+/// texto de ambos valores, sin convertirlos.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::datetime::{Date, Interval};
+/// use sql::extras::Value;
+/// use sql::operations::arithmetic;
+///
+/// let fecha = Value::Date(Date::parse("2024-01-31").unwrap());
+/// let mes = Value::Interval(Interval::parse("1 month").unwrap());
+/// let resultado = arithmetic(fecha, mes, "+").unwrap();
+/// assert_eq!(resultado.to_string(), "2024-02-29");
+/// assert_eq!(arithmetic(Value::Int(7), Value::Int(2), "/").unwrap(), Value::Int(3));
+/// ```
+pub fn arithmetic(value1: Value, value2: Value, operator: &str) -> Result<Value, SqlError> {
+    if value1.is_null() || value2.is_null() {
+        return Ok(Value::Null);
+    }
+    if operator == "||" {
+        return Ok(Value::Str(format!("{}{}", value1, value2)));
+    }
+    let cast = |value: Value| match value {
+        Value::Str(value) => cast_to_value(value.trim()),
+        value => value,
+    };
+    let (value1, value2) = (cast(value1), cast(value2));
+    if let Some(result) = date_arithmetic(&value1, &value2, operator) {
+        return result;
+    }
+    numeric_arithmetic(&value1, &value2, operator).unwrap_or_else(|| {
+        Err(
+            SqlError::new(ErrorType::Error, "Operación no soportada entre los valores")
+                .with_context(&format!("{} {} {}", value1, operator, value2)),
+        )
+    })
+}
+
+/// Aplica un operador aritmético entre dos números, con aritmética entera si ambos son enteros. Devuelve
+/// `None` si algún valor no es numérico, o un error si divide por cero o el resultado entero no entra en 64 bits.
+fn numeric_arithmetic(
+    value1: &Value,
+    value2: &Value,
+    operator: &str,
+) -> Option<Result<Value, SqlError>> {
+    let division_by_zero = || SqlError::new(ErrorType::Error, "División por cero");
+    if let (Some(i1), Some(i2)) = (get_integer(value1), get_integer(value2)) {
+        let result = match operator {
+            "/" | "%" if i2 == 0 => return Some(Err(division_by_zero())),
+            "+" => i1.checked_add(i2),
+            "-" => i1.checked_sub(i2),
+            "*" => i1.checked_mul(i2),
+            "/" => i1.checked_div(i2),
+            "%" => i1.checked_rem(i2),
+            _ => return None,
+        };
+        return Some(result.map(integer_value).ok_or_else(|| {
+            SqlError::new(ErrorType::Error, "El resultado está fuera de rango")
+                .with_context(&format!("{} {} {}", i1, operator, i2))
+        }));
+    }
+    let (f1, f2) = (get_float_value(value1)?, get_float_value(value2)?);
+    let result = match operator {
+        "/" | "%" if f2 == 0.0 => return Some(Err(division_by_zero())),
+        "+" => f1 + f2,
+        "-" => f1 - f2,
+        "*" => f1 * f2,
+        "/" => f1 / f2,
+        "%" => f1 % f2,
+        _ => return None,
     };
-    operator.apply_operation(value1, value2)
+    Some(Ok(Value::Float(result)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::Interval;
+
+    fn text(s: &str) -> Value {
+        Value::Str(s.to_string())
+    }
+
+    #[test]
+    fn test_filter_equal_is_case_sensitive() {
+        assert_eq!(
+            filter(text("Laptop"), text("laptop"), "=").ok(),
+            Some(Some(false))
+        );
+    }
+
+    #[test]
+    fn test_filter_ilike_ignores_case() {
+        assert_eq!(
+            filter(text("Laptop"), text("LAPTOP"), "ilike").ok(),
+            Some(Some(true))
+        );
+    }
+
+    #[test]
+    fn test_filter_ilike_with_numbers() {
+        assert_eq!(
+            filter(Value::Int(7), Value::Int(7), "ilike").ok(),
+            Some(Some(true))
+        );
+    }
+
+    #[test]
+    fn test_filter_greater_and_less_or_equal() {
+        assert_eq!(
+            filter(Value::Int(5), Value::Int(5), ">=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::Int(4), Value::Int(5), ">=").ok(),
+            Some(Some(false))
+        );
+        assert_eq!(
+            filter(Value::Int(5), Value::Int(5), "<=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::Int(6), Value::Int(5), "<=").ok(),
+            Some(Some(false))
+        );
+    }
+
+    #[test]
+    fn test_filter_not_equal() {
+        assert_eq!(
+            filter(Value::Int(1), Value::Int(2), "!=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(text("Mouse"), text("Mouse"), "<>").ok(),
+            Some(Some(false))
+        );
+        assert_eq!(
+            filter(Value::Int(1), text("uno"), "<>").ok(),
+            Some(Some(true))
+        );
+    }
+
+    #[test]
+    fn test_filter_string_ordering() {
+        assert_eq!(
+            filter(text("Mouse"), text("Laptop"), ">").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(text("Laptop"), text("Mouse"), "<").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(text("Laptop"), text("Laptop"), ">=").ok(),
+            Some(Some(true))
+        );
+    }
+
+    #[test]
+    fn test_filter_mixed_types_do_not_compare() {
+        assert_eq!(
+            filter(Value::Int(1), text("1a"), "<").ok(),
+            Some(Some(false))
+        );
+        assert_eq!(
+            filter(Value::Int(1), text("1a"), ">").ok(),
+            Some(Some(false))
+        );
+    }
+
+    #[test]
+    fn test_filter_numeric_promotion() {
+        assert_eq!(
+            filter(Value::Int(20), Value::Float(19.99), ">").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::Float(2.0), Value::Int(2), "=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::BigInt(4_000_000_000), Value::Int(i32::MAX), ">").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::BigInt(i64::MAX), Value::BigInt(i64::MAX - 1), ">").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(text("19.99"), Value::Float(19.99), "=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::Float(10.5), Value::Float(9.75), "<=").ok(),
+            Some(Some(false))
+        );
+    }
+
+    #[test]
+    fn test_filter_booleans() {
+        assert_eq!(
+            filter(Value::Bool(true), Value::Bool(true), "=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::Bool(false), Value::Bool(true), "<").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(text("TRUE"), Value::Bool(true), "=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(Value::Bool(true), Value::Int(1), "=").ok(),
+            Some(Some(false))
+        );
+    }
+
+    #[test]
+    fn test_filter_dates() {
+        let date = cast_to_value("2024-03-05");
+        assert_eq!(
+            filter(date.clone(), text("2024-10-01"), "<").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(date.clone(), cast_to_value("2024-03-05T00:00:00"), "=").ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            filter(cast_to_value("09:30"), text("10:00:00"), ">=").ok(),
+            Some(Some(false))
+        );
+        assert_eq!(filter(date, Value::Int(2024), "=").ok(), Some(Some(false)));
+    }
+
+    #[test]
+    fn test_filter_null_is_unknown() {
+        assert_eq!(filter(Value::Null, Value::Null, "=").ok(), Some(None));
+        assert_eq!(filter(Value::Int(1), Value::Null, "!=").ok(), Some(None));
+        assert_eq!(filter(Value::Null, text("a"), "ilike").ok(), Some(None));
+        assert!(filter(Value::Null, Value::Null, "=~").is_err());
+    }
+
+    #[test]
+    fn test_filter_unknown_operator() {
+        let error = filter(Value::Int(1), Value::Int(2), "=~").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+        assert_eq!(error.context(), Some("=~"));
+    }
+
+    #[test]
+    fn test_arithmetic_with_dates() {
+        let date = Value::Str("2024-05-31".to_string());
+        let interval = Value::Interval(Interval::parse("1 day").unwrap());
+        assert_eq!(
+            arithmetic(date.clone(), interval.clone(), "+")
+                .unwrap()
+                .to_string(),
+            "2024-06-01"
+        );
+        assert_eq!(arithmetic(Value::Null, interval, "+").unwrap(), Value::Null);
+        let error = arithmetic(date, Value::Str("abc".to_string()), "+").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+    }
+
+    #[test]
+    fn test_arithmetic_with_numbers_and_strings() {
+        assert_eq!(
+            arithmetic(Value::Int(7), Value::Int(2), "/").unwrap(),
+            Value::Int(3)
+        );
+        assert_eq!(
+            arithmetic(Value::Int(7), Value::Int(2), "%").unwrap(),
+            Value::Int(1)
+        );
+        assert_eq!(
+            arithmetic(text("1.5"), Value::Int(2), "*").unwrap(),
+            Value::Float(3.0)
+        );
+        assert_eq!(
+            arithmetic(Value::Int(2), Value::Null, "-").unwrap(),
+            Value::Null
+        );
+        assert_eq!(
+            arithmetic(text("Ana"), Value::Int(1), "||").unwrap(),
+            text("Ana1")
+        );
+        assert_eq!(
+            arithmetic(text("Ana"), Value::Null, "||").unwrap(),
+            Value::Null
+        );
+
+        let error = arithmetic(Value::Int(1), Value::Int(0), "/").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        let error = arithmetic(Value::Float(1.0), Value::Int(0), "%").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        let error = arithmetic(Value::BigInt(i64::MAX), Value::Int(1), "+").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        let error = arithmetic(text("Ana"), Value::Int(1), "+").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+    }
+
+    #[test]
+    fn test_like_wildcards() {
+        let matches =
+            |value: &str, pattern: &str| like(text(value), text(pattern), None, false).unwrap();
+        assert_eq!(matches("Laptop", "Lap%"), Some(true));
+        assert_eq!(matches("Laptop", "%top"), Some(true));
+        assert_eq!(matches("Laptop", "L_pt_p"), Some(true));
+        assert_eq!(matches("Laptop", "laptop"), Some(false));
+        assert_eq!(matches("Laptop", "L_p"), Some(false));
+        assert_eq!(matches("aaab", "%a%ab"), Some(true));
+        assert_eq!(matches("aaac", "%a%ab"), Some(false));
+        assert_eq!(matches("", "%"), Some(true));
+        assert_eq!(matches("Ñandú", "_and_"), Some(true));
+        assert_eq!(matches("a\\b", "a\\b"), Some(true));
+        assert_eq!(matches("50\\0", "50\\%"), Some(true));
+        assert_eq!(matches("50%", "50\\%"), Some(false));
+        assert_eq!(
+            like(Value::Int(2024), text("20%"), None, false).ok(),
+            Some(Some(true))
+        );
+        assert_eq!(like(Value::Null, text("%"), None, false).ok(), Some(None));
+        assert_eq!(
+            filter(text("LAPTOP pro"), text("laptop%"), "ilike").ok(),
+            Some(Some(true))
+        );
+    }
+
+    #[test]
+    fn test_like_escape() {
+        let escaped = |value: &str, pattern: &str, escape: &str| {
+            like(text(value), text(pattern), Some(text(escape)), false)
+        };
+        assert_eq!(escaped("a_b", "a!_b", "!").ok(), Some(Some(true)));
+        assert_eq!(escaped("axb", "a!_b", "!").ok(), Some(Some(false)));
+        assert_eq!(escaped("a!b", "a!!b", "!").ok(), Some(Some(true)));
+        assert_eq!(escaped("a\\b", "a\\b", "").ok(), Some(Some(true)));
+        assert_eq!(
+            like(text("A%B"), text("aX%b"), Some(text("X")), true).ok(),
+            Some(Some(true))
+        );
+        assert_eq!(
+            like(text("A%B"), text("ax%b"), Some(text("X")), true).ok(),
+            Some(Some(false))
+        );
+        assert_eq!(
+            like(text("a"), text("a"), Some(Value::Null), false).ok(),
+            Some(None)
+        );
+
+        let error = escaped("a", "a", "ab").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        let error = escaped("a", "a!", "!").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+    }
 }
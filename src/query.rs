@@ -1,11 +1,19 @@
+use utils::alter_query::{AlterParser, AlterTableQuery};
+use utils::create_query::{CreateParser, CreateTableQuery};
 use utils::delete_query::{DeleteParser, DeleteQuery};
+use utils::drop_query::{DropParser, DropTableQuery};
 use utils::insert_query::{InsertParser, InsertQuery};
 use utils::select_query::{SelectParser, SelectQuery};
 use utils::update_query::{UpdateParser, UpdateQuery};
 
-use crate::error::{self, ErrorType};
+use crate::error::{ErrorType, SqlError};
+use crate::lexer::{tokenize, Token, TokenKind};
+use crate::result::ResultSet;
 use crate::utils;
+use crate::utils::alter_query::alter_table;
+use crate::utils::create_query::create_table;
 use crate::utils::delete_query::delete;
+use crate::utils::drop_query::drop_table;
 use crate::utils::insert_query::insert;
 use crate::utils::select_query::select;
 use crate::utils::update_query::update;
@@ -19,6 +27,9 @@ use crate::utils::update_query::update;
 /// * `Insert` - Consulta de inserción.
 /// * `Delete` - Consulta de eliminación.
 /// * `Update` - Consulta de actualización.
+/// * `CreateTable` - Consulta de creación de tabla.
+/// * `DropTable` - Consulta de eliminación de tabla.
+/// * `AlterTable` - Consulta de modificación de tabla.
 ///
 ///
 pub enum Query {
@@ -26,6 +37,9 @@ pub enum Query {
     Insert(InsertQuery),
     Delete(DeleteQuery),
     Update(UpdateQuery),
+    CreateTable(CreateTableQuery),
+    DropTable(DropTableQuery),
+    AlterTable(AlterTableQuery),
 }
 
 #[derive(Debug)]
@@ -38,6 +52,9 @@ pub enum CommandType {
     Insert,
     Delete,
     Update,
+    CreateTable,
+    DropTable,
+    AlterTable,
 }
 
 /// Trait para el análisis de comandos SQL.
@@ -49,140 +66,188 @@ pub trait CommandParser {
     /// Valida la sintaxis del comando SQL.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Un vector de `String` que representa el comando SQL descompuesto en tokens.
+    /// * `tokens` - Los tokens del comando SQL, tal como los produce el lexer.
     ///
     /// # Retorno
-    /// Devuelve `Ok(())` si la sintaxis es válida, o un `ErrorType::InvalidSyntax` si la sintaxis es incorrecta.
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un error de tipo `ErrorType::InvalidSyntax` si la sintaxis es incorrecta.
     ///
-    fn validate_syntax(&self, parsed_query: &[String]) -> Result<(), ErrorType>;
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError>;
 
     /// Parsea el comando SQL en una estructura `Query`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Un vector de `String` que representa el comando SQL descompuesto en tokens.
+    /// * `tokens` - Los tokens del comando SQL, tal como los produce el lexer.
     ///
     /// # Retorno
-    /// Devuelve un `Ok(Query)` si el parseo es exitoso, o un `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
+    /// Devuelve un `Ok(Query)` si el parseo es exitoso, o un error de tipo `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
     ///
-    fn parse(&self, parsed_query: Vec<String>) -> Result<Query, ErrorType>;
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError>;
 }
 
 /// Parsea y ejecuta una consulta SQL.
 ///
 /// # Argumentos
-/// * `path` - La ruta del archivo sobre el que se debe ejecutar la consulta.
+/// * `path` - La ruta del directorio que contiene las tablas, donde cada tabla es un archivo `<tabla>.csv`.
 /// * `query` - La consulta SQL en formato de cadena.
 ///
 /// # Retorno
-/// Devuelve `Ok(())` si la ejecución es exitosa, o un `ErrorType` si ocurre un error durante el parseo o ejecución de la consulta.
+/// Devuelve el `ResultSet` de la consulta si la ejecución es exitosa, o un `SqlError` que describe el error ocurrido durante el parseo o ejecución de la consulta.
 ///
 /// # Notas
 /// Esta función es la principal para ejecutar consultas SQL.
-/// Es la encargada de parsear la consulta y determinar qué tipo de consulta se está realizando, en base a eso, se ejecuta la consulta correspondiente.
-
-pub fn parse_query(path: &str, query: &str) -> Result<(), ErrorType> {
-    let parsed_query: Vec<String> = query
-        .split_whitespace()
-        .map(|s| s.to_string().to_lowercase())
-        .collect();
-
-    if parsed_query.len() < 4 {
-        let error = ErrorType::InvalidSyntax;
-        error::print_error(error, "Sintaxis inválida");
-        return Err(ErrorType::InvalidSyntax);
+/// Es la encargada de parsear la consulta (ver `parse`) y, en base al tipo de consulta, ejecutar la función correspondiente.
+pub fn parse_query(path: &str, query: &str) -> Result<ResultSet, SqlError> {
+    let query = parse(query)?;
+    execute(path, query)
+}
+
+/// Parsea una consulta SQL sin ejecutarla.
+///
+/// # Argumentos
+/// * `query` - La consulta SQL en formato de cadena.
+///
+/// # Retorno
+/// Devuelve la `Query` parseada, o un `SqlError` de tipo `ErrorType::InvalidSyntax` si la consulta no es válida.
+///
+/// # Notas
+/// Tokeniza la consulta y determina qué tipo de consulta se está realizando para asignar el parser correspondiente.
+/// El punto y coma final, si lo hay, se descarta.
+/// Solo las palabras reservadas se reconocen sin distinguir mayúsculas; los identificadores y los literales conservan su capitalización.
+pub fn parse(query: &str) -> Result<Query, SqlError> {
+    let mut tokens = tokenize(query)?;
+    if tokens.last().map(|token| &token.kind) == Some(&TokenKind::Semicolon) {
+        tokens.pop();
+    }
+
+    if tokens.len() < 3 {
+        return Err(SqlError::new(
+            ErrorType::InvalidSyntax,
+            "Sintaxis inválida: la consulta está incompleta",
+        ));
     }
-    let command: Box<dyn CommandParser> = match parsed_query[0].as_str() {
+    let command: Box<dyn CommandParser> = match tokens[0].value.as_str() {
         "select" => Box::new(SelectParser),
         "insert" => Box::new(InsertParser),
         "delete" => Box::new(DeleteParser),
         "update" => Box::new(UpdateParser),
+        "create" => Box::new(CreateParser),
+        "drop" => Box::new(DropParser),
+        "alter" => Box::new(AlterParser),
         _ => {
-            let error = ErrorType::InvalidSyntax;
-            error::print_error(error, "Comando no válido");
-            return Err(ErrorType::InvalidSyntax);
+            return Err(SqlError::new(ErrorType::InvalidSyntax, "Comando no válido")
+                .with_context(&tokens[0].value)
+                .with_position(tokens[0].position))
         }
     };
-    command.validate_syntax(&parsed_query)?;
-    match command.parse(parsed_query) {
-        Ok(query) => execute(path, query),
-        Err(error) => return Err(error),
-    };
-    Ok(())
+    command.validate_syntax(&tokens)?;
+    command.parse(tokens)
 }
 
-/// Ejecuta una consulta SQL en el archivo especificado.
+/// Ejecuta una consulta SQL sobre la tabla que indica la consulta.
 ///
 /// # Argumentos
-/// * `path` - La ruta del archivo sobre el que se debe ejecutar la consulta.
+/// * `path` - La ruta del directorio que contiene las tablas, donde cada tabla es un archivo `<tabla>.csv`.
 /// * `query` - La consulta SQL a ejecutar, encapsulada en una variante de `Query`.
 ///
+/// # Retorno
+/// Devuelve un `ResultSet` con las filas seleccionadas (para `SELECT`) o con la cantidad de filas afectadas
+/// (para `INSERT`, `UPDATE` y `DELETE`; cero para `CREATE TABLE`, `DROP TABLE` y `ALTER TABLE`), o un `SqlError` si la ejecución falla.
+///
 /// # Notas
 /// De acuerdo a la consulta SQL, se ejecuta la función correspondiente.
-pub fn execute(path: &str, query: Query) {
+pub fn execute(path: &str, query: Query) -> Result<ResultSet, SqlError> {
     match query {
-        Query::Select(select_query) => {
-            let _ = select(path, select_query);
-        }
-        Query::Insert(insert_query) => {
-            let _ = insert(path, insert_query);
-        }
-        Query::Delete(delete_query) => {
-            let _ = delete(path, delete_query);
-        }
-        Query::Update(update_query) => {
-            let _ = update(path, update_query);
+        Query::Select(select_query) => select(path, select_query),
+        Query::Insert(insert_query) => insert(path, insert_query).map(ResultSet::affected),
+        Query::Delete(delete_query) => delete(path, delete_query).map(ResultSet::affected),
+        Query::Update(update_query) => update(path, update_query).map(ResultSet::affected),
+        Query::CreateTable(create_query) => {
+            create_table(path, create_query).map(ResultSet::affected)
         }
+        Query::DropTable(drop_query) => drop_table(path, drop_query).map(ResultSet::affected),
+        Query::AlterTable(alter_query) => alter_table(path, alter_query).map(ResultSet::affected),
     }
 }
 #[cfg(test)]
 mod tests_query {
     use super::*;
+    use crate::extras::Value;
 
     #[test]
     fn test_parse_select_query() {
         let query = "select * from table_name";
-        let result = parse_query("fake_path.csv", query);
+        let result = parse(query);
         assert!(result.is_ok(), "No se pudo parsear la consulta SELECT");
     }
 
     #[test]
     fn test_parse_insert_query() {
         let query = "INSERT INTO ordenes (id, id_cliente, producto, cantidad) VALUES (111, 6, 'Laptop', 3);";
-        let result = parse_query("fake_path.csv", query);
+        let result = parse(query);
         assert!(result.is_ok(), "No se pudo parsear la consulta INSERT");
     }
 
     #[test]
     fn test_parse_update_query() {
         let query = "update table_name set column1 = 'value1' where column2 = 'value2'";
-        let result = parse_query("fake_path.csv", query);
+        let result = parse(query);
         assert!(result.is_ok(), "No se pudo parsear la consulta UPDATE");
     }
 
     #[test]
     fn test_parse_delete_query() {
         let query = "delete from table_name where column1 = 'value1'";
-        let result = parse_query("fake_path.csv", query);
+        let result = parse(query);
         assert!(result.is_ok(), "No se pudo parsear la consulta DELETE");
     }
 
+    #[test]
+    fn test_parse_create_and_drop_table() {
+        let result = parse("CREATE TABLE IF NOT EXISTS productos (codigo TEXT, stock INT);");
+        assert!(matches!(result, Ok(Query::CreateTable(_))));
+
+        let result = parse("DROP TABLE IF EXISTS productos");
+        assert!(matches!(result, Ok(Query::DropTable(_))));
+    }
+
     #[test]
     fn test_parse_invalid_command() {
         let query = "not_a_command table table_name";
         let result = parse_query("fake_path.csv", query);
         assert!(result.is_err(), "Comando no válido");
         if let Err(error) = result {
-            assert_eq!(error, ErrorType::InvalidSyntax);
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax);
         }
     }
 
+    #[test]
+    fn test_parse_query_returns_rows() {
+        let query = "SELECT id, producto FROM ordenes WHERE cantidad > 1";
+        let result = parse_query("tests", query).unwrap();
+        assert_eq!(result.columns, vec!["id", "producto"]);
+        assert_eq!(result.rows.len(), 3);
+        assert_eq!(
+            result.rows[0],
+            vec![Value::Int(102), Value::Str("Teléfono".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_missing_table() {
+        let query = "select * from table_name";
+        let result = parse_query("tests", query);
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidTable);
+        assert_eq!(error.context(), Some("table_name"));
+    }
+
     #[test]
     fn test_parse_short_query() {
         let query = "insert into";
         let result = parse_query("fake_path.csv", query);
         assert!(result.is_err(), "Sintaxis inválida");
         if let Err(error) = result {
-            assert_eq!(error, ErrorType::InvalidSyntax);
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax);
         }
     }
 }
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::{csv::format_record, extras::Value};
+
+#[derive(Debug, PartialEq)]
+
+/// Representa el resultado de ejecutar una consulta, con los parámetros:
+///
+/// * `columns` - Los nombres de las columnas del resultado (vacío para `INSERT`, `UPDATE` y `DELETE`).
+/// * `rows` - Las filas del resultado, con un `Value` por columna.
+/// * `affected_rows` - La cantidad de filas insertadas, actualizadas o eliminadas.
+///
+pub struct ResultSet {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub affected_rows: usize,
+}
+
+impl ResultSet {
+    /// Crea el resultado de una consulta `SELECT` a partir de sus columnas y filas.
+    pub fn new(columns: Vec<String>, rows: Vec<Vec<Value>>) -> Self {
+        ResultSet {
+            columns,
+            rows,
+            affected_rows: 0,
+        }
+    }
+
+    /// Crea el resultado de una consulta que modifica la tabla, indicando cuántas filas afectó.
+    pub fn affected(affected_rows: usize) -> Self {
+        ResultSet {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            affected_rows,
+        }
+    }
+}
+
+/// Muestra el resultado en formato CSV: una línea con los nombres de las columnas y un registro por fila,
+/// escapando los valores con comas, comillas o saltos de línea. Los valores `NULL` se muestran como campos vacíos.
+/// Si el resultado no tiene columnas (por ejemplo, el de un `DELETE`), no muestra nada.
+impl fmt::Display for ResultSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.columns.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "{}", format_record(&self.columns))?;
+        for row in &self.rows {
+            let values: Vec<Option<String>> = row.iter().map(Value::to_field).collect();
+            writeln!(f, "{}", format_record(&values))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_result_set() {
+        let result = ResultSet::new(
+            vec!["id".to_string(), "producto".to_string()],
+            vec![
+                vec![Value::Int(101), Value::Str("Laptop".to_string())],
+                vec![Value::Int(105), Value::Str("Mouse".to_string())],
+            ],
+        );
+        assert_eq!(result.to_string(), "id,producto\n101,Laptop\n105,Mouse\n");
+    }
+
+    #[test]
+    fn test_display_null_and_empty_values() {
+        let result = ResultSet::new(
+            vec!["id".to_string(), "email".to_string()],
+            vec![
+                vec![Value::Int(1), Value::Null],
+                vec![Value::Int(2), Value::Str(String::new())],
+            ],
+        );
+        assert_eq!(result.to_string(), "id,email\n1,\n2,\"\"\n");
+    }
+
+    #[test]
+    fn test_display_affected_rows_is_empty() {
+        let result = ResultSet::affected(3);
+        assert_eq!(result.affected_rows, 3);
+        assert_eq!(result.to_string(), "");
+    }
+}
@@ -0,0 +1,460 @@
+use std::{fmt, fs, io, path::Path};
+
+use crate::{
+    datetime::{Date, Time, Timestamp},
+    error::{ErrorType, SqlError},
+    extras::{cast_to_value, parse_float, Value},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+
+/// Representa el tipo declarado de una columna en el esquema de una tabla.
+///
+/// # Variantes
+/// * `Int` - Números enteros de 32 bits (`INT` o `INTEGER`).
+/// * `BigInt` - Números enteros de 64 bits (`BIGINT`).
+/// * `Float` - Números de punto flotante (`FLOAT`, `REAL` o `DOUBLE`).
+/// * `Bool` - Valores booleanos `true` o `false` (`BOOL` o `BOOLEAN`).
+/// * `Date` - Fechas ISO-8601 `AAAA-MM-DD` (`DATE`).
+/// * `Time` - Horas ISO-8601 `HH:MM:SS` (`TIME`).
+/// * `Timestamp` - Fechas con hora ISO-8601 (`TIMESTAMP` o `DATETIME`). Una fecha sola se toma como su medianoche.
+/// * `Text` - Cadenas de texto (`TEXT`, `VARCHAR` o `STRING`).
+pub enum ColumnType {
+    Int,
+    BigInt,
+    Float,
+    Bool,
+    Date,
+    Time,
+    Timestamp,
+    Text,
+}
+
+impl ColumnType {
+    /// Obtiene el tipo a partir de su nombre en el esquema, sin distinguir mayúsculas de minúsculas.
+    ///
+    /// # Argumentos
+    /// * `name` - El nombre del tipo, por ejemplo `INT` o `text`.
+    ///
+    /// # Retorno
+    /// Devuelve el `ColumnType` correspondiente, o `None` si el tipo no es soportado.
+    ///
+    pub fn from_name(name: &str) -> Option<ColumnType> {
+        match name.to_lowercase().as_str() {
+            "int" | "integer" => Some(ColumnType::Int),
+            "bigint" => Some(ColumnType::BigInt),
+            "float" | "real" | "double" => Some(ColumnType::Float),
+            "bool" | "boolean" => Some(ColumnType::Bool),
+            "date" => Some(ColumnType::Date),
+            "time" => Some(ColumnType::Time),
+            "timestamp" | "datetime" => Some(ColumnType::Timestamp),
+            "text" | "varchar" | "string" => Some(ColumnType::Text),
+            _ => None,
+        }
+    }
+
+    /// Convierte el texto de una celda en un valor de este tipo.
+    ///
+    /// # Argumentos
+    /// * `raw` - El texto de la celda tal como está en el archivo CSV.
+    ///
+    /// # Retorno
+    /// Devuelve el `Value` de la celda, o `None` si el texto no es válido para el tipo.
+    ///
+    /// # Notas
+    /// Las celdas `NULL` no llegan a esta función (ver `parse_row`); una cadena vacía solo es válida en columnas de texto.
+    ///
+    pub fn parse_value(&self, raw: &str) -> Option<Value> {
+        match self {
+            ColumnType::Int => raw.trim().parse::<i32>().ok().map(Value::Int),
+            ColumnType::BigInt => raw.trim().parse::<i64>().ok().map(Value::BigInt),
+            ColumnType::Float => parse_float(raw.trim()).map(Value::Float),
+            ColumnType::Bool => match cast_to_value(raw.trim()) {
+                Value::Bool(value) => Some(Value::Bool(value)),
+                _ => None,
+            },
+            ColumnType::Date => Date::parse(raw.trim()).map(Value::Date),
+            ColumnType::Time => Time::parse(raw.trim()).map(Value::Time),
+            ColumnType::Timestamp => Timestamp::parse(raw.trim())
+                .or_else(|| Date::parse(raw.trim()).map(Timestamp::from))
+                .map(Value::Timestamp),
+            ColumnType::Text => Some(Value::Str(raw.to_string())),
+        }
+    }
+}
+
+/// Muestra el tipo con el nombre que se usa en el archivo de esquema.
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColumnType::Int => write!(f, "INT"),
+            ColumnType::BigInt => write!(f, "BIGINT"),
+            ColumnType::Float => write!(f, "FLOAT"),
+            ColumnType::Bool => write!(f, "BOOL"),
+            ColumnType::Date => write!(f, "DATE"),
+            ColumnType::Time => write!(f, "TIME"),
+            ColumnType::Timestamp => write!(f, "TIMESTAMP"),
+            ColumnType::Text => write!(f, "TEXT"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa una columna del esquema, con los parámetros:
+///
+/// * `name` - El nombre de la columna, igual al del encabezado del CSV.
+/// * `column_type` - El tipo declarado de la columna.
+///
+pub struct ColumnDef {
+    pub name: String,
+    pub column_type: ColumnType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa el esquema de una tabla, leído del archivo `<tabla>.schema` que acompaña a `<tabla>.csv`.
+///
+/// El archivo tiene una línea por columna, en el mismo orden que el encabezado del CSV, con el nombre
+/// de la columna y su tipo separados por espacios. Las líneas vacías se ignoran.
+///
+/// # Ejemplo
+/// ```rust
+/// use sql::extras::Value;
+/// use sql::schema::{ColumnType, TableSchema};
+///
+/// let schema = TableSchema::parse("id INT\ncodigo TEXT\n").unwrap();
+/// assert_eq!(schema.column_type("codigo"), Some(ColumnType::Text));
+/// assert_eq!(schema.parse_value("codigo", "007").unwrap(), Value::Str("007".to_string()));
+/// assert!(schema.parse_value("id", "abc").is_err());
+/// ```
+pub struct TableSchema {
+    pub columns: Vec<ColumnDef>,
+}
+
+impl TableSchema {
+    /// Parsea el contenido de un archivo de esquema.
+    ///
+    /// # Argumentos
+    /// * `contents` - El contenido del archivo, con una línea `columna TIPO` por columna.
+    ///
+    /// # Retorno
+    /// Devuelve el `TableSchema`, o un error de tipo `ErrorType::InvalidTable` si alguna línea
+    /// está mal formada o declara un tipo no soportado.
+    ///
+    pub fn parse(contents: &str) -> Result<TableSchema, SqlError> {
+        let mut columns = Vec::new();
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let column_type = match parts.as_slice() {
+                [_, type_name] => ColumnType::from_name(type_name),
+                _ => None,
+            };
+            match column_type {
+                Some(column_type) => columns.push(ColumnDef {
+                    name: parts[0].to_string(),
+                    column_type,
+                }),
+                None => {
+                    return Err(SqlError::new(
+                        ErrorType::InvalidTable,
+                        "Línea inválida en el esquema de la tabla",
+                    )
+                    .with_context(line))
+                }
+            }
+        }
+        Ok(TableSchema { columns })
+    }
+
+    /// Carga el esquema de una tabla, si existe, y verifica que coincida con su encabezado.
+    ///
+    /// # Argumentos
+    /// * `table_path` - La ruta del archivo CSV de la tabla.
+    /// * `headers` - El encabezado del archivo CSV.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(Some(schema))` si la tabla tiene esquema, `Ok(None)` si no lo tiene, o un error
+    /// de tipo `ErrorType::InvalidTable` si el esquema no se puede leer o no coincide con el encabezado.
+    ///
+    pub fn load(table_path: &str, headers: &[String]) -> Result<Option<TableSchema>, SqlError> {
+        let path = schema_path(table_path);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(
+                    SqlError::new(ErrorType::InvalidTable, "No se pudo leer el esquema")
+                        .with_context(&path)
+                        .with_source(e),
+                )
+            }
+        };
+        let schema = TableSchema::parse(&contents)?;
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        if names != headers {
+            return Err(SqlError::new(
+                ErrorType::InvalidTable,
+                "El esquema no coincide con el encabezado de la tabla",
+            )
+            .with_context(&path));
+        }
+        Ok(Some(schema))
+    }
+
+    /// Devuelve el tipo declarado de una columna, o `None` si la columna no está en el esquema.
+    pub fn column_type(&self, column: &str) -> Option<ColumnType> {
+        self.columns
+            .iter()
+            .find(|c| c.name == column)
+            .map(|c| c.column_type)
+    }
+
+    /// Convierte y valida el valor de una columna según su tipo declarado.
+    ///
+    /// # Argumentos
+    /// * `column` - El nombre de la columna.
+    /// * `raw` - El texto del valor.
+    ///
+    /// # Retorno
+    /// Devuelve el `Value` tipado, un error de tipo `ErrorType::InvalidColumn` si la columna no existe,
+    /// o un error de tipo `ErrorType::Error` si el valor no es válido para el tipo de la columna.
+    ///
+    pub fn parse_value(&self, column: &str, raw: &str) -> Result<Value, SqlError> {
+        let column_type = self.column_type(column).ok_or_else(|| {
+            SqlError::new(ErrorType::InvalidColumn, "La columna no existe").with_context(column)
+        })?;
+        column_type
+            .parse_value(raw)
+            .ok_or_else(|| invalid_value(column, column_type, raw))
+    }
+}
+
+/// Muestra el esquema con el formato del archivo `<tabla>.schema`.
+impl fmt::Display for TableSchema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for column in &self.columns {
+            writeln!(f, "{} {}", column.name, column.column_type)?;
+        }
+        Ok(())
+    }
+}
+
+/// Obtiene la ruta del archivo de esquema de una tabla, reemplazando la extensión `.csv` por `.schema`.
+///
+/// # Argumentos
+/// * `table_path` - La ruta del archivo CSV de la tabla.
+///
+pub fn schema_path(table_path: &str) -> String {
+    Path::new(table_path)
+        .with_extension("schema")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Convierte los campos de un registro del CSV en valores tipados.
+///
+/// # Argumentos
+/// * `schema` - El esquema de la tabla, o `None` si la tabla no tiene esquema.
+/// * `record` - Los campos del registro, en el orden del encabezado.
+///
+/// # Retorno
+/// Devuelve un `Value` por campo, o un error de tipo `ErrorType::Error` si algún campo no es válido
+/// para el tipo de su columna.
+///
+/// # Notas
+/// Los campos `NULL` se convierten en `Value::Null` en cualquier columna. Sin esquema, el tipo de cada
+/// campo se deduce con `cast_to_value`.
+///
+pub fn parse_row(
+    schema: Option<&TableSchema>,
+    record: &[Option<String>],
+) -> Result<Vec<Value>, SqlError> {
+    match schema {
+        Some(schema) => record
+            .iter()
+            .zip(&schema.columns)
+            .map(|(field, column)| match field {
+                Some(raw) => column
+                    .column_type
+                    .parse_value(raw)
+                    .ok_or_else(|| invalid_value(&column.name, column.column_type, raw)),
+                None => Ok(Value::Null),
+            })
+            .collect(),
+        None => Ok(record
+            .iter()
+            .map(|field| field.as_deref().map_or(Value::Null, cast_to_value))
+            .collect()),
+    }
+}
+
+/// Verifica que las columnas existan en la tabla y que los valores correspondan a sus tipos.
+///
+/// # Argumentos
+/// * `schema` - El esquema de la tabla, o `None` si la tabla no tiene esquema.
+/// * `headers` - El encabezado del archivo CSV.
+/// * `columns` - Las columnas que se van a escribir (en un `INSERT` o en el `SET` de un `UPDATE`).
+/// * `values` - Los valores para cada una de esas columnas (`None` para `NULL`).
+///
+/// # Retorno
+/// Devuelve `Ok(())` si los valores son válidos, un error de tipo `ErrorType::InvalidColumn` si alguna columna
+/// no existe, o un error de tipo `ErrorType::Error` si algún valor no es válido para el tipo de su columna.
+/// `NULL` es válido en cualquier columna.
+///
+pub fn validate_values(
+    schema: Option<&TableSchema>,
+    headers: &[String],
+    columns: &[String],
+    values: &[Option<String>],
+) -> Result<(), SqlError> {
+    for (column, value) in columns.iter().zip(values) {
+        if !headers.contains(column) {
+            return Err(
+                SqlError::new(ErrorType::InvalidColumn, "La columna no existe")
+                    .with_context(column),
+            );
+        }
+        if let (Some(schema), Some(raw)) = (schema, value) {
+            schema.parse_value(column, raw)?;
+        }
+    }
+    Ok(())
+}
+
+/// Crea el error para un valor que no corresponde al tipo declarado de su columna.
+fn invalid_value(column: &str, column_type: ColumnType, raw: &str) -> SqlError {
+    SqlError::new(
+        ErrorType::Error,
+        &format!(
+            "Valor inválido para la columna '{}' de tipo {}",
+            column, column_type
+        ),
+    )
+    .with_context(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schema() {
+        let schema = TableSchema::parse("id INT\n\nnombre varchar\n").unwrap();
+        assert_eq!(
+            schema.columns,
+            vec![
+                ColumnDef {
+                    name: "id".to_string(),
+                    column_type: ColumnType::Int,
+                },
+                ColumnDef {
+                    name: "nombre".to_string(),
+                    column_type: ColumnType::Text,
+                },
+            ]
+        );
+        assert_eq!(schema.to_string(), "id INT\nnombre TEXT\n");
+    }
+
+    #[test]
+    fn test_parse_schema_unknown_type() {
+        let error = TableSchema::parse("id DECIMAL").unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidTable);
+        assert_eq!(error.context(), Some("id DECIMAL"));
+    }
+
+    #[test]
+    fn test_parse_row_keeps_text_columns() {
+        let schema = TableSchema::parse("id INT\ncodigo TEXT").unwrap();
+        let record = vec![Some("1".to_string()), Some("007".to_string())];
+        assert_eq!(
+            parse_row(Some(&schema), &record).unwrap(),
+            vec![Value::Int(1), Value::Str("007".to_string())]
+        );
+        assert_eq!(
+            parse_row(None, &record).unwrap(),
+            vec![Value::Int(1), Value::Int(7)]
+        );
+    }
+
+    #[test]
+    fn test_parse_row_numeric_and_bool_columns() {
+        let schema = TableSchema::parse("id BIGINT\nprecio FLOAT\nactivo BOOLEAN").unwrap();
+        assert_eq!(schema.to_string(), "id BIGINT\nprecio FLOAT\nactivo BOOL\n");
+        let record = vec![
+            Some("5000000000".to_string()),
+            Some("20".to_string()),
+            Some("False".to_string()),
+        ];
+        assert_eq!(
+            parse_row(Some(&schema), &record).unwrap(),
+            vec![
+                Value::BigInt(5_000_000_000),
+                Value::Float(20.0),
+                Value::Bool(false)
+            ]
+        );
+
+        assert!(schema.parse_value("precio", "barato").is_err());
+        assert!(schema.parse_value("activo", "1").is_err());
+        assert!(schema.parse_value("id", "1.5").is_err());
+    }
+
+    #[test]
+    fn test_parse_row_date_columns() {
+        let schema = TableSchema::parse("fecha DATE\nhora TIME\ncreado DATETIME").unwrap();
+        assert_eq!(
+            schema.to_string(),
+            "fecha DATE\nhora TIME\ncreado TIMESTAMP\n"
+        );
+        let record = vec![
+            Some("2024-02-29".to_string()),
+            Some("18:05".to_string()),
+            Some("2024-02-29".to_string()),
+        ];
+        let row = parse_row(Some(&schema), &record).unwrap();
+        let fields: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+        assert_eq!(
+            fields,
+            vec!["2024-02-29", "18:05:00", "2024-02-29 00:00:00"]
+        );
+
+        assert!(schema.parse_value("fecha", "29/02/2024").is_err());
+        assert!(schema.parse_value("hora", "25:00").is_err());
+    }
+
+    #[test]
+    fn test_parse_row_invalid_int() {
+        let schema = TableSchema::parse("id INT").unwrap();
+        let error = parse_row(Some(&schema), &[Some("uno".to_string())]).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        assert_eq!(error.context(), Some("uno"));
+
+        let error = parse_row(Some(&schema), &[Some(String::new())]).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+    }
+
+    #[test]
+    fn test_parse_row_null_fields() {
+        let schema = TableSchema::parse("id INT\ncodigo TEXT").unwrap();
+        let record = vec![None, Some(String::new())];
+        assert_eq!(
+            parse_row(Some(&schema), &record).unwrap(),
+            vec![Value::Null, Value::Str(String::new())]
+        );
+        assert_eq!(
+            parse_row(None, &record).unwrap(),
+            vec![Value::Null, Value::Str(String::new())]
+        );
+    }
+
+    #[test]
+    fn test_schema_path() {
+        assert_eq!(schema_path("tests/ordenes.csv"), "tests/ordenes.schema");
+    }
+}
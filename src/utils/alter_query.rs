@@ -0,0 +1,567 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use crate::query::{CommandParser, Query};
+use crate::{
+    csv::write_record,
+    error::{ErrorType, SqlError},
+    extras::{
+        get_column_index, get_table_path, open_table, read_error, replace_table, syntax_error_at,
+        table_file_path, write_error,
+    },
+    lexer::{Token, TokenKind},
+    schema::{schema_path, ColumnDef, ColumnType, TableSchema},
+};
+
+#[derive(Debug, PartialEq)]
+
+/// Representa el cambio que aplica una consulta `ALTER TABLE`.
+///
+/// # Variantes
+/// * `AddColumn` - Agrega una columna al final, con su tipo (si la tabla tiene esquema) y el valor por
+///   defecto para las filas existentes (`None` si las filas existentes quedan en `NULL`).
+/// * `DropColumn` - Elimina una columna.
+/// * `RenameColumn` - Cambia el nombre de una columna.
+/// * `RenameTable` - Cambia el nombre de la tabla.
+pub enum AlterAction {
+    AddColumn {
+        name: String,
+        column_type: Option<ColumnType>,
+        default: Option<String>,
+    },
+    DropColumn(String),
+    RenameColumn {
+        from: String,
+        to: String,
+    },
+    RenameTable(String),
+}
+
+#[derive(Debug)]
+
+/// Representa una consulta `ALTER TABLE`, con los parámetros:
+///
+/// * `table_name` - El nombre de la tabla a modificar.
+/// * `action` - El cambio a aplicar.
+///
+pub struct AlterTableQuery {
+    pub table_name: String,
+    pub action: AlterAction,
+}
+
+pub struct AlterParser;
+
+impl CommandParser for AlterParser {
+    /// Valida la sintaxis de la consulta `ALTER TABLE`.
+    ///
+    /// # Argumentos
+    /// * `tokens` - Una referencia a los tokens de la consulta.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
+    ///
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 5 || !tokens[0].is_keyword("alter") || !tokens[1].is_keyword("table") {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "Sintaxis inválida: falta 'ALTER TABLE'",
+            ));
+        }
+        if tokens[2].kind != TokenKind::Identifier {
+            return Err(syntax_error_at(
+                tokens,
+                2,
+                "Sintaxis inválida: falta el nombre de la tabla",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parsea la consulta `ALTER TABLE` y la convierte en una estructura `Query`.
+    ///
+    /// # Argumentos
+    /// * `tokens` - Los tokens de la consulta.
+    ///
+    /// # Retorno
+    /// Devuelve un `Ok(Query)` con una consulta `ALTER TABLE` si el parseo es exitoso,
+    /// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
+    ///
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let mut parser = ActionParser {
+            tokens: &tokens,
+            index: 4,
+        };
+        let action = match tokens[3].value.as_str() {
+            "add" if tokens[3].kind == TokenKind::Keyword => parser.parse_add()?,
+            "drop" if tokens[3].kind == TokenKind::Keyword => {
+                parser.skip_keyword("column");
+                AlterAction::DropColumn(parser.expect_identifier()?)
+            }
+            "rename" if tokens[3].kind == TokenKind::Keyword => parser.parse_rename()?,
+            _ => {
+                return Err(syntax_error_at(
+                    &tokens,
+                    3,
+                    "Sintaxis inválida: se esperaba 'ADD', 'DROP' o 'RENAME'",
+                ))
+            }
+        };
+        if parser.index != tokens.len() {
+            return Err(syntax_error_at(
+                &tokens,
+                parser.index,
+                "Sintaxis inválida: tokens inesperados al final de 'ALTER TABLE'",
+            ));
+        }
+        Ok(Query::AlterTable(AlterTableQuery {
+            table_name: tokens[2].value.to_string(),
+            action,
+        }))
+    }
+}
+
+/// Recorre los tokens que siguen a `ALTER TABLE <tabla>` para armar la `AlterAction`.
+struct ActionParser<'a> {
+    tokens: &'a [Token],
+    index: usize,
+}
+
+impl ActionParser<'_> {
+    fn skip_keyword(&mut self, keyword: &str) -> bool {
+        let found = self
+            .tokens
+            .get(self.index)
+            .is_some_and(|token| token.is_keyword(keyword));
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, SqlError> {
+        match self.tokens.get(self.index) {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                self.index += 1;
+                Ok(token.value.to_string())
+            }
+            _ => Err(syntax_error_at(
+                self.tokens,
+                self.index,
+                "Se esperaba el nombre de una columna o tabla",
+            )),
+        }
+    }
+
+    /// `ADD [COLUMN] columna [TIPO] [DEFAULT valor]`
+    fn parse_add(&mut self) -> Result<AlterAction, SqlError> {
+        self.skip_keyword("column");
+        let name = self.expect_identifier()?;
+        let column_type = match self.tokens.get(self.index) {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                let column_type = ColumnType::from_name(&token.value).ok_or_else(|| {
+                    syntax_error_at(self.tokens, self.index, "Tipo de columna no soportado")
+                })?;
+                self.index += 1;
+                Some(column_type)
+            }
+            _ => None,
+        };
+        let default = if self.skip_keyword("default") {
+            match self.tokens.get(self.index) {
+                Some(token) if token.is_keyword("null") => {
+                    self.index += 1;
+                    None
+                }
+                Some(token)
+                    if token.is_literal()
+                        || token.is_keyword("true")
+                        || token.is_keyword("false") =>
+                {
+                    self.index += 1;
+                    Some(token.value.to_string())
+                }
+                _ => {
+                    return Err(syntax_error_at(
+                        self.tokens,
+                        self.index,
+                        "Se esperaba un valor luego de 'DEFAULT'",
+                    ))
+                }
+            }
+        } else {
+            None
+        };
+        Ok(AlterAction::AddColumn {
+            name,
+            column_type,
+            default,
+        })
+    }
+
+    /// `RENAME TO tabla` o `RENAME [COLUMN] columna TO columna`
+    fn parse_rename(&mut self) -> Result<AlterAction, SqlError> {
+        if self.skip_keyword("to") {
+            return Ok(AlterAction::RenameTable(self.expect_identifier()?));
+        }
+        self.skip_keyword("column");
+        let from = self.expect_identifier()?;
+        if !self.skip_keyword("to") {
+            return Err(syntax_error_at(self.tokens, self.index, "Se esperaba 'TO'"));
+        }
+        let to = self.expect_identifier()?;
+        Ok(AlterAction::RenameColumn { from, to })
+    }
+}
+
+/// Aplica una consulta `ALTER TABLE` sobre la tabla.
+///
+/// # Argumentos
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
+/// * `query` - La consulta `ALTER TABLE`.
+///
+/// # Retorno
+/// Devuelve `Ok(0)` si la tabla se modificó, o un `SqlError` si la tabla o la columna no existen,
+/// si el nuevo nombre ya está en uso, si el valor por defecto no corresponde al tipo de la columna
+/// o si falla la escritura de los archivos.
+///
+/// # Notas
+/// Los cambios de columnas reescriben el CSV en una sola pasada (ver `replace_table`). Si la tabla
+/// tiene esquema, el nuevo esquema se escribe en `<tabla>.schema.tmp` y se renombra sobre el original
+/// justo antes de reemplazar el CSV. Si después falla el reemplazo del CSV, se vuelve a escribir el
+/// esquema anterior; solo si también falla esa escritura el encabezado y el esquema quedan desparejos.
+/// Una columna agregada sin tipo a una tabla con esquema es `TEXT`, y sin `DEFAULT` las filas
+/// existentes quedan en `NULL`.
+///
+pub fn alter_table(path: &str, query: AlterTableQuery) -> Result<usize, SqlError> {
+    let table_path = get_table_path(path, &query.table_name)?;
+    if let AlterAction::RenameTable(new_name) = &query.action {
+        return rename_table(path, &table_path, new_name);
+    }
+
+    let (header, reader) = open_table(&table_path)?;
+    let schema = TableSchema::load(&table_path, &header)?;
+    let mut new_header = header.clone();
+    let mut new_schema = schema.clone();
+
+    let mut appends_column = false;
+    let mut appended_value: Option<String> = None;
+    let mut removed_index: Option<usize> = None;
+    match query.action {
+        AlterAction::AddColumn {
+            name,
+            column_type,
+            default,
+        } => {
+            if get_column_index(&header, &name) >= 0 {
+                return Err(column_already_exists(&name));
+            }
+            match (&mut new_schema, column_type) {
+                (Some(schema), column_type) => {
+                    let column_type = column_type.unwrap_or(ColumnType::Text);
+                    schema.columns.push(ColumnDef {
+                        name: name.to_string(),
+                        column_type,
+                    });
+                    if let Some(default) = &default {
+                        schema.parse_value(&name, default)?;
+                    }
+                }
+                (None, Some(_)) => {
+                    return Err(SqlError::new(
+                        ErrorType::InvalidSyntax,
+                        "La tabla no tiene esquema: la columna no puede declarar tipo",
+                    )
+                    .with_context(&name))
+                }
+                (None, None) => {}
+            }
+            new_header.push(name);
+            appends_column = true;
+            appended_value = default;
+        }
+        AlterAction::DropColumn(name) => {
+            let index = existing_column_index(&header, &name)?;
+            if header.len() == 1 {
+                return Err(SqlError::new(
+                    ErrorType::InvalidColumn,
+                    "No se puede eliminar la única columna de la tabla",
+                )
+                .with_context(&name));
+            }
+            new_header.remove(index);
+            if let Some(schema) = &mut new_schema {
+                schema.columns.remove(index);
+            }
+            removed_index = Some(index);
+        }
+        AlterAction::RenameColumn { from, to } => {
+            let index = existing_column_index(&header, &from)?;
+            if get_column_index(&header, &to) >= 0 {
+                return Err(column_already_exists(&to));
+            }
+            new_header[index] = to.to_string();
+            if let Some(schema) = &mut new_schema {
+                schema.columns[index].name = to;
+            }
+        }
+        AlterAction::RenameTable(_) => {}
+    }
+
+    let schema_path = schema_path(&table_path);
+    let schema_temp_path = format!("{}.tmp", schema_path);
+    let mut schema_replaced = false;
+    let result = replace_table(&table_path, |writer| {
+        write_record(writer, &new_header).map_err(|e| write_error(&table_path, e))?;
+        for record in reader {
+            let mut record = record.map_err(|e| read_error(&table_path, e))?;
+            if let Some(index) = removed_index.filter(|&index| index < record.len()) {
+                record.remove(index);
+            }
+            if appends_column {
+                record.push(appended_value.clone());
+            }
+            write_record(writer, &record).map_err(|e| write_error(&table_path, e))?;
+        }
+        if let Some(schema) = &new_schema {
+            write_schema(&schema_path, schema)?;
+            schema_replaced = true;
+        }
+        Ok(0)
+    });
+    if result.is_err() {
+        if let (true, Some(schema)) = (schema_replaced, &schema) {
+            let _ = write_schema(&schema_path, schema);
+        }
+        let _ = fs::remove_file(&schema_temp_path);
+    }
+    result
+}
+
+/// Reemplaza el esquema de una tabla escribiéndolo en `<tabla>.schema.tmp` y renombrándolo sobre el
+/// original, por lo que el archivo nunca queda escrito a medias.
+fn write_schema(schema_path: &str, schema: &TableSchema) -> Result<(), SqlError> {
+    let temp_path = format!("{}.tmp", schema_path);
+    fs::write(&temp_path, schema.to_string()).map_err(|e| write_error(&temp_path, e))?;
+    fs::rename(&temp_path, schema_path).map_err(|e| write_error(schema_path, e))
+}
+
+/// Renombra los archivos de la tabla (el CSV y, si existe, el esquema).
+///
+/// # Argumentos
+/// * `path` - La ruta del directorio de la base de datos.
+/// * `table_path` - La ruta del archivo CSV actual de la tabla.
+/// * `new_name` - El nuevo nombre de la tabla.
+///
+fn rename_table(path: &str, table_path: &str, new_name: &str) -> Result<usize, SqlError> {
+    let new_path = table_file_path(path, new_name)?;
+    if Path::new(&new_path).exists() {
+        return Err(
+            SqlError::new(ErrorType::InvalidTable, "La tabla ya existe").with_context(new_name)
+        );
+    }
+    fs::rename(table_path, &new_path).map_err(|e| write_error(table_path, e))?;
+
+    let old_schema_path = schema_path(table_path);
+    match fs::rename(&old_schema_path, schema_path(&new_path)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(write_error(&old_schema_path, e)),
+        _ => Ok(0),
+    }
+}
+
+/// Busca el índice de una columna que debe existir en la tabla.
+fn existing_column_index(header: &[String], column: &str) -> Result<usize, SqlError> {
+    match get_column_index(header, column) {
+        index if index >= 0 => Ok(index as usize),
+        _ => Err(
+            SqlError::new(ErrorType::InvalidColumn, "La columna no existe").with_context(column),
+        ),
+    }
+}
+
+/// Crea el error para una columna nueva cuyo nombre ya está en uso.
+fn column_already_exists(column: &str) -> SqlError {
+    SqlError::new(ErrorType::InvalidColumn, "La columna ya existe").with_context(column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    fn parse(query: &str) -> Result<AlterTableQuery, ErrorType> {
+        let tokens = tokenize(query).unwrap();
+        AlterParser
+            .validate_syntax(&tokens)
+            .and_then(|_| AlterParser.parse(tokens))
+            .map_err(|error| error.kind())
+            .map(|query| match query {
+                Query::AlterTable(alter_query) => alter_query,
+                _ => panic!("Se esperaba una consulta ALTER TABLE"),
+            })
+    }
+
+    #[test]
+    fn test_alter_parser_actions() {
+        assert_eq!(
+            parse("ALTER TABLE t ADD COLUMN stock INT DEFAULT 0")
+                .unwrap()
+                .action,
+            AlterAction::AddColumn {
+                name: "stock".to_string(),
+                column_type: Some(ColumnType::Int),
+                default: Some("0".to_string()),
+            }
+        );
+        assert_eq!(
+            parse("ALTER TABLE t ADD activo BOOL DEFAULT TRUE")
+                .unwrap()
+                .action,
+            AlterAction::AddColumn {
+                name: "activo".to_string(),
+                column_type: Some(ColumnType::Bool),
+                default: Some("true".to_string()),
+            }
+        );
+        assert_eq!(
+            parse("alter table t drop email").unwrap().action,
+            AlterAction::DropColumn("email".to_string())
+        );
+        assert_eq!(
+            parse("ALTER TABLE t RENAME COLUMN nombre TO name")
+                .unwrap()
+                .action,
+            AlterAction::RenameColumn {
+                from: "nombre".to_string(),
+                to: "name".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("ALTER TABLE t RENAME TO t2").unwrap().action,
+            AlterAction::RenameTable("t2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_alter_parser_invalid() {
+        assert_eq!(
+            parse("ALTER TABLE t MODIFY x").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("ALTER TABLE t RENAME a b").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("ALTER TABLE t ADD x DEFAULT").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_alter_table_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let table = "test_alter_columns";
+        fs::write(
+            "test_alter_columns.csv",
+            "id,nombre\n1,Ana\n2,\"Luis, Jr\"\n",
+        )?;
+
+        alter_table(
+            ".",
+            parse(&format!(
+                "ALTER TABLE {} ADD ciudad DEFAULT 'Rosario'",
+                table
+            ))
+            .unwrap(),
+        )?;
+        assert_eq!(
+            fs::read_to_string("test_alter_columns.csv")?,
+            "id,nombre,ciudad\n1,Ana,Rosario\n2,\"Luis, Jr\",Rosario\n"
+        );
+
+        alter_table(
+            ".",
+            parse(&format!("ALTER TABLE {} DROP COLUMN nombre", table)).unwrap(),
+        )?;
+        alter_table(
+            ".",
+            parse(&format!("ALTER TABLE {} RENAME ciudad TO city", table)).unwrap(),
+        )?;
+        assert_eq!(
+            fs::read_to_string("test_alter_columns.csv")?,
+            "id,city\n1,Rosario\n2,Rosario\n"
+        );
+
+        let error = alter_table(
+            ".",
+            parse(&format!("ALTER TABLE {} DROP nombre", table)).unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidColumn);
+
+        fs::remove_file("test_alter_columns.csv")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_alter_table_with_schema() -> Result<(), Box<dyn std::error::Error>> {
+        fs::write("test_alter_schema.csv", "id\n1\n")?;
+        fs::write("test_alter_schema.schema", "id INT\n")?;
+
+        let query = parse("ALTER TABLE test_alter_schema ADD stock INT DEFAULT 'x'").unwrap();
+        assert_eq!(
+            alter_table(".", query).map_err(|error| error.kind()),
+            Err(ErrorType::Error)
+        );
+        assert_eq!(fs::read_to_string("test_alter_schema.csv")?, "id\n1\n");
+
+        let query = parse("ALTER TABLE test_alter_schema ADD stock INT DEFAULT 5").unwrap();
+        alter_table(".", query)?;
+        let query = parse("ALTER TABLE test_alter_schema RENAME TO test_alter_schema2").unwrap();
+        alter_table(".", query)?;
+
+        assert_eq!(
+            fs::read_to_string("test_alter_schema2.csv")?,
+            "id,stock\n1,5\n"
+        );
+        assert_eq!(
+            fs::read_to_string("test_alter_schema2.schema")?,
+            "id INT\nstock INT\n"
+        );
+        assert!(fs::metadata("test_alter_schema.csv").is_err());
+
+        let query = parse("ALTER TABLE test_alter_schema2 ADD activo BOOL DEFAULT FALSE").unwrap();
+        alter_table(".", query)?;
+        assert_eq!(
+            fs::read_to_string("test_alter_schema2.csv")?,
+            "id,stock,activo\n1,5,false\n"
+        );
+        assert_eq!(
+            fs::read_to_string("test_alter_schema2.schema")?,
+            "id INT\nstock INT\nactivo BOOL\n"
+        );
+
+        fs::remove_file("test_alter_schema2.csv")?;
+        fs::remove_file("test_alter_schema2.schema")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_alter_table_schema_write_fails() -> Result<(), Box<dyn std::error::Error>> {
+        fs::write("test_alter_atomic.csv", "id\n1\n")?;
+        fs::write("test_alter_atomic.schema", "id INT\n")?;
+        fs::create_dir("test_alter_atomic.schema.tmp")?;
+
+        let query = parse("ALTER TABLE test_alter_atomic ADD stock INT DEFAULT 5").unwrap();
+        assert_eq!(
+            alter_table(".", query).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidTable)
+        );
+        assert_eq!(fs::read_to_string("test_alter_atomic.csv")?, "id\n1\n");
+        assert_eq!(fs::read_to_string("test_alter_atomic.schema")?, "id INT\n");
+        assert!(fs::metadata("test_alter_atomic.csv.tmp").is_err());
+
+        fs::remove_dir("test_alter_atomic.schema.tmp")?;
+        fs::remove_file("test_alter_atomic.csv")?;
+        fs::remove_file("test_alter_atomic.schema")?;
+        Ok(())
+    }
+}
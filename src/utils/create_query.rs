@@ -0,0 +1,311 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+};
+
+use crate::query::{CommandParser, Query};
+use crate::{
+    csv::write_record,
+    error::{ErrorType, SqlError},
+    extras::{syntax_error_at, table_file_path, write_error},
+    lexer::{Token, TokenKind},
+    schema::{schema_path, ColumnDef, ColumnType, TableSchema},
+};
+
+#[derive(Debug)]
+
+/// Representa una consulta `CREATE TABLE`, con los parámetros:
+///
+/// * `table_name` - El nombre de la tabla a crear.
+/// * `columns` - Los nombres de las columnas, en el orden del encabezado.
+/// * `schema` - El esquema de la tabla, si las columnas se declararon con tipo.
+/// * `if_not_exists` - Si la consulta tiene `IF NOT EXISTS`, en cuyo caso no es un error que la tabla ya exista.
+///
+pub struct CreateTableQuery {
+    pub table_name: String,
+    pub columns: Vec<String>,
+    pub schema: Option<TableSchema>,
+    pub if_not_exists: bool,
+}
+
+pub struct CreateParser;
+
+impl CommandParser for CreateParser {
+    /// Valida la sintaxis de la consulta `CREATE TABLE`.
+    ///
+    /// # Argumentos
+    /// * `tokens` - Una referencia a los tokens de la consulta.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
+    ///
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 4 || !tokens[0].is_keyword("create") || !tokens[1].is_keyword("table") {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "Sintaxis inválida: falta 'CREATE TABLE'",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parsea la consulta de creación y la convierte en una estructura `Query`.
+    ///
+    /// # Argumentos
+    /// * `tokens` - Los tokens de la consulta.
+    ///
+    /// # Retorno
+    /// Devuelve un `Ok(Query)` con una consulta de creación si el parseo es exitoso,
+    /// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
+    ///
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let if_not_exists = tokens.len() > 4
+            && tokens[2].is_keyword("if")
+            && tokens[3].is_keyword("not")
+            && tokens[4].is_keyword("exists");
+        let name_index = if if_not_exists { 5 } else { 2 };
+        let table_name = match tokens.get(name_index) {
+            Some(token) if token.kind == TokenKind::Identifier => token.value.to_string(),
+            _ => {
+                return Err(syntax_error_at(
+                    &tokens,
+                    name_index,
+                    "Sintaxis inválida: falta el nombre de la tabla",
+                ))
+            }
+        };
+        let (columns, types) = extract_column_definitions(&tokens, name_index + 1)?;
+
+        let schema = if types.iter().all(Option::is_none) {
+            None
+        } else if types.iter().all(Option::is_some) {
+            Some(TableSchema {
+                columns: columns
+                    .iter()
+                    .zip(types.into_iter().flatten())
+                    .map(|(name, column_type)| ColumnDef {
+                        name: name.to_string(),
+                        column_type,
+                    })
+                    .collect(),
+            })
+        } else {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "Sintaxis inválida: si una columna declara su tipo, todas deben declararlo",
+            ));
+        };
+
+        Ok(Query::CreateTable(CreateTableQuery {
+            table_name,
+            columns,
+            schema,
+            if_not_exists,
+        }))
+    }
+}
+
+/// Extrae las definiciones de columnas de la forma `(columna [TIPO], ...)`.
+///
+/// # Argumentos
+/// * `tokens` - Los tokens de la consulta.
+/// * `start` - El índice del paréntesis de apertura.
+///
+/// # Retorno
+/// Devuelve los nombres de las columnas y el tipo declarado de cada una (`None` si no declara tipo),
+/// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si la lista está mal formada, un tipo no es soportado
+/// o hay columnas repetidas.
+///
+fn extract_column_definitions(
+    tokens: &[Token],
+    start: usize,
+) -> Result<(Vec<String>, Vec<Option<ColumnType>>), SqlError> {
+    if tokens.get(start).map(|token| &token.kind) != Some(&TokenKind::LeftParen) {
+        return Err(syntax_error_at(tokens, start, "Se esperaba '('"));
+    }
+    let mut columns: Vec<String> = Vec::new();
+    let mut types = Vec::new();
+    let mut index = start + 1;
+    loop {
+        match tokens.get(index) {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                if columns.contains(&token.value) {
+                    return Err(syntax_error_at(tokens, index, "Columna repetida"));
+                }
+                columns.push(token.value.to_string());
+            }
+            _ => {
+                return Err(syntax_error_at(
+                    tokens,
+                    index,
+                    "Se esperaba el nombre de una columna",
+                ))
+            }
+        }
+        index += 1;
+        match tokens.get(index) {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                let column_type = ColumnType::from_name(&token.value).ok_or_else(|| {
+                    syntax_error_at(tokens, index, "Tipo de columna no soportado")
+                })?;
+                types.push(Some(column_type));
+                index += 1;
+            }
+            _ => types.push(None),
+        }
+        match tokens.get(index).map(|token| &token.kind) {
+            Some(TokenKind::Comma) => index += 1,
+            Some(TokenKind::RightParen) if index + 1 == tokens.len() => {
+                return Ok((columns, types))
+            }
+            Some(TokenKind::RightParen) => {
+                return Err(syntax_error_at(
+                    tokens,
+                    index + 1,
+                    "Sintaxis inválida: tokens inesperados luego de la lista de columnas",
+                ))
+            }
+            _ => return Err(syntax_error_at(tokens, index, "Se esperaba ',' o ')'")),
+        }
+    }
+}
+
+/// Crea una tabla nueva: el archivo CSV con su encabezado y, si las columnas tienen tipo, su archivo de esquema.
+///
+/// # Argumentos
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
+/// * `query` - La consulta de creación.
+///
+/// # Retorno
+/// Devuelve `Ok(0)` si la tabla se creó (o ya existía y la consulta tiene `IF NOT EXISTS`),
+/// o un `SqlError` de tipo `ErrorType::InvalidTable` si la tabla ya existe o no se pudo escribir el archivo.
+///
+/// # Notas
+/// El archivo CSV se crea solo si no existe, por lo que nunca se pisa una tabla existente.
+///
+pub fn create_table(path: &str, query: CreateTableQuery) -> Result<usize, SqlError> {
+    let table_path = table_file_path(path, &query.table_name)?;
+    let mut file = match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&table_path)
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists && query.if_not_exists => return Ok(0),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            return Err(SqlError::new(ErrorType::InvalidTable, "La tabla ya existe")
+                .with_context(&query.table_name))
+        }
+        Err(e) => return Err(write_error(&table_path, e)),
+    };
+
+    let result = write_record(&mut file, &query.columns)
+        .map_err(|e| write_error(&table_path, e))
+        .and_then(|_| match &query.schema {
+            Some(schema) => {
+                let schema_path = schema_path(&table_path);
+                fs::write(&schema_path, schema.to_string())
+                    .map_err(|e| write_error(&schema_path, e))
+            }
+            None => Ok(()),
+        });
+    if let Err(error) = result {
+        let _ = fs::remove_file(&table_path);
+        return Err(error);
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    fn parse(query: &str) -> Result<CreateTableQuery, ErrorType> {
+        let tokens = tokenize(query).unwrap();
+        CreateParser
+            .validate_syntax(&tokens)
+            .and_then(|_| CreateParser.parse(tokens))
+            .map_err(|error| error.kind())
+            .map(|query| match query {
+                Query::CreateTable(create_query) => create_query,
+                _ => panic!("Se esperaba una consulta CREATE TABLE"),
+            })
+    }
+
+    #[test]
+    fn test_create_parser_untyped() {
+        let query = parse("CREATE TABLE clientes (id, nombre)").unwrap();
+        assert_eq!(query.table_name, "clientes");
+        assert_eq!(query.columns, vec!["id", "nombre"]);
+        assert_eq!(query.schema, None);
+        assert!(!query.if_not_exists);
+    }
+
+    #[test]
+    fn test_create_parser_typed_if_not_exists() {
+        let query = parse("create table if not exists productos (codigo TEXT, stock int)").unwrap();
+        assert!(query.if_not_exists);
+        let schema = query.schema.unwrap();
+        assert_eq!(schema.to_string(), "codigo TEXT\nstock INT\n");
+    }
+
+    #[test]
+    fn test_create_parser_invalid() {
+        assert_eq!(
+            parse("CREATE TABLE t (id INT, nombre)").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("CREATE TABLE t (id DECIMAL)").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("CREATE TABLE t (id, id)").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+        assert_eq!(
+            parse("CREATE TABLE t id").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_create_table_writes_header_and_schema() -> Result<(), Box<dyn std::error::Error>> {
+        let query = parse("CREATE TABLE test_create_table (id INT, nombre TEXT)").unwrap();
+        assert_eq!(create_table(".", query).ok(), Some(0));
+        assert_eq!(fs::read_to_string("test_create_table.csv")?, "id,nombre\n");
+        assert_eq!(
+            fs::read_to_string("test_create_table.schema")?,
+            "id INT\nnombre TEXT\n"
+        );
+
+        let query = parse("CREATE TABLE test_create_table (otra)").unwrap();
+        assert_eq!(
+            create_table(".", query).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidTable)
+        );
+        let query = parse("CREATE TABLE IF NOT EXISTS test_create_table (otra)").unwrap();
+        assert_eq!(create_table(".", query).ok(), Some(0));
+        assert_eq!(fs::read_to_string("test_create_table.csv")?, "id,nombre\n");
+
+        fs::remove_file("test_create_table.csv")?;
+        fs::remove_file("test_create_table.schema")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_table_outside_database_directory() {
+        for query in [
+            r#"CREATE TABLE "../test_create_escaped" (a INT, b TEXT)"#,
+            r#"CREATE TABLE IF NOT EXISTS "../test_create_escaped" (a, b)"#,
+        ] {
+            assert_eq!(
+                create_table("tests", parse(query).unwrap()).map_err(|error| error.kind()),
+                Err(ErrorType::InvalidTable)
+            );
+        }
+        assert!(fs::metadata("test_create_escaped.csv").is_err());
+        assert!(fs::metadata("test_create_escaped.schema").is_err());
+    }
+}
@@ -1,13 +1,15 @@
-use std::{
-    fs::{self, File},
-    io::{self, BufRead, BufReader, BufWriter, Write},
-};
-
 use super::select_query::filter_row;
 use crate::{
-    error::{self, print_error, ErrorType},
-    extras::{cleaned_values, get_condition_columns},
+    csv::write_record,
+    error::{ErrorType, SqlError},
+    expression::Expr,
+    extras::{
+        get_condition, get_table_path, open_table, read_error, replace_table, syntax_error_at,
+        write_error,
+    },
+    lexer::{Token, TokenKind},
     query::Query,
+    schema::{parse_row, TableSchema},
 };
 
 use crate::query::CommandParser;
@@ -17,11 +19,11 @@ use crate::query::CommandParser;
 /// Representa una consulta `DELETE`, con los parámetros:
 ///
 /// * `table_name` - El nombre de la tabla de la que se eliminarán filas.
-/// * `condition` - Las condiciones que deben cumplir las filas para ser eliminadas.
+/// * `condition` - La condición que deben cumplir las filas para ser eliminadas, si la consulta tiene `WHERE`.
 ///
 pub struct DeleteQuery {
     pub table_name: String,
-    pub condition: Vec<String>,
+    pub condition: Option<Expr>,
 }
 
 pub struct DeleteParser;
@@ -29,18 +31,17 @@ impl CommandParser for DeleteParser {
     /// Valida la sintaxis de la consulta `DELETES`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una referencia a un `Vec<String>` con los componentes de la consulta ya parseados.
+    /// * `tokens` - Una referencia a los tokens de la consulta.
     ///
     /// # Retorno
-    /// Devuelve `Ok(())` si la sintaxis es válida, o `Err(ErrorType)` si es inválida.
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
     ///
-    fn validate_syntax(&self, parsed_query: &[String]) -> Result<(), ErrorType> {
-        if parsed_query.len() < 3 || parsed_query[0] != "delete" || parsed_query[1] != "from" {
-            error::print_error(
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 3 || !tokens[0].is_keyword("delete") || !tokens[1].is_keyword("from") {
+            return Err(SqlError::new(
                 ErrorType::InvalidSyntax,
                 "Sintaxis inválida: falta 'DELETE FROM'",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
         }
 
         Ok(())
@@ -48,22 +49,33 @@ impl CommandParser for DeleteParser {
     /// Parsea la consulta de eliminación y la convierte en una estructura `Query`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Un vector de `String` que representa la consulta descompuesta en tokens.
+    /// * `tokens` - Los tokens de la consulta.
     ///
     /// # Retorno
     /// Devuelve un `Ok(Query)` con una consulta de eliminación si el parseo es exitoso,
-    /// o un `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
+    /// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
     ///
-    fn parse(&self, parsed_query: Vec<String>) -> Result<Query, ErrorType> {
-        let table_name: String;
-        let table_name_index = parsed_query.iter().position(|x| x == "from");
-        if let Some(index) = table_name_index {
-            table_name = parsed_query[index + 1].to_string();
-        } else {
-            error::print_error(ErrorType::InvalidSyntax, "Sintaxis inválida, falta 'from'");
-            return Err(ErrorType::InvalidSyntax);
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let index = tokens
+            .iter()
+            .position(|token| token.is_keyword("from"))
+            .map_or(tokens.len(), |index| index + 1);
+        let table_name = tokens
+            .get(index)
+            .filter(|token| token.kind == TokenKind::Identifier)
+            .map(|token| token.value.to_string())
+            .ok_or_else(|| syntax_error_at(&tokens, index, "Sintaxis inválida, falta 'from'"))?;
+        if tokens
+            .get(index + 1)
+            .is_some_and(|token| !token.is_keyword("where"))
+        {
+            return Err(syntax_error_at(
+                &tokens,
+                index + 1,
+                "Sintaxis inválida: se esperaba 'WHERE' o el final de la consulta luego del nombre de la tabla",
+            ));
         }
-        let condition = cleaned_values(get_condition_columns(&parsed_query));
+        let condition = get_condition(&tokens, &[])?;
         Ok(Query::Delete(DeleteQuery {
             table_name,
             condition,
@@ -74,86 +86,49 @@ impl CommandParser for DeleteParser {
 /// Elimina las filas de un archivo CSV según la consulta de eliminación.
 ///
 /// # Argumentos
-/// * `path` - La ruta del archivo CSV.
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
 /// * `delete_query` - La consulta de eliminación que especifica las condiciones de eliminación.
 ///
 /// # Retorno
-/// Devuelve `Ok(())` si la eliminación es exitosa, o un `ErrorType::InvalidTable` si ocurre un error al abrir o leer el archivo.
+/// Devuelve la cantidad de filas eliminadas si la eliminación es exitosa, o un `SqlError` de tipo `ErrorType::InvalidTable` si ocurre un error al abrir, leer o escribir el archivo.
+///
+/// # Notas
+/// La tabla se recorre una sola vez: las filas que no cumplen la condición se copian a un archivo temporal,
+/// que reemplaza al original al terminar (ver `replace_table`).
 ///
-pub fn delete(path: &str, delete_query: DeleteQuery) -> Result<(), ErrorType> {
-    let mut index: usize = 0;
-    if let Ok(file) = File::open(path) {
-        let mut reader: io::BufReader<File> = io::BufReader::new(file);
-        let mut header: String = String::new();
-        let _ = reader.read_line(&mut header);
-        let header = header.trim();
-        let headers: Vec<&str> = header.split(',').collect();
-        let lines = reader.lines();
-        for line in lines {
-            index += 1;
-            if let Ok(line) = line {
-                let values: Vec<String> = line.split(",").map(|s| s.to_string()).collect();
-                if filter_row(&values, &delete_query.condition, &headers) {
-                    let _ = delete_line(path, index);
-                    index -= 1;
-                };
+pub fn delete(path: &str, delete_query: DeleteQuery) -> Result<usize, SqlError> {
+    let path = &get_table_path(path, &delete_query.table_name)?;
+    let (header, reader) = open_table(path)?;
+    let schema = TableSchema::load(path, &header)?;
+    let headers: Vec<&str> = header.iter().map(|s| s.as_str()).collect();
+
+    replace_table(path, |writer| {
+        write_record(writer, &header).map_err(|e| write_error(path, e))?;
+        let mut deleted_rows: usize = 0;
+        for record in reader {
+            let record = record.map_err(|e| read_error(path, e))?;
+            let values = parse_row(schema.as_ref(), &record)?;
+            if filter_row(&values, delete_query.condition.as_ref(), &headers)? {
+                deleted_rows += 1;
             } else {
-                print_error(ErrorType::InvalidTable, "No se pudo leer el archivo");
-                return Err(ErrorType::InvalidTable);
+                write_record(writer, &record).map_err(|e| write_error(path, e))?;
             }
         }
-    } else {
-        print_error(ErrorType::InvalidTable, "No se pudo abrir el archivo");
-        return Err(ErrorType::InvalidTable);
-    }
-    Ok(())
+        Ok(deleted_rows)
+    })
 }
 
-/// Elimina una línea específica de un archivo CSV.
-///
-/// # Argumentos
-/// * `file_path` - La ruta del archivo CSV.
-/// * `line_to_delete` - El índice de la línea que se desea eliminar.
-///
-/// # Retorno
-/// Devuelve `Ok(())` si la eliminación es exitosa, o un `io::Result` en caso de error durante la operación de archivo.
-///
-/// # Notas
-/// Se crea un archivo temporal para almacenar las líneas que no se eliminarán, y luego se renombra para pisar el archivo original.
-///
-fn delete_line(file_path: &str, line_to_delete: usize) -> io::Result<()> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
-    let lines = reader.lines();
-    let temp_file_path = format!("{}.csv", file_path);
-    let temp_file = File::create(&temp_file_path)?;
-    let mut writer = BufWriter::new(temp_file);
-    for (index, line) in lines.enumerate() {
-        if index != line_to_delete {
-            writeln!(writer, "{}", line?)?;
-        }
-    }
-    writer.flush()?;
-    drop(writer);
-    fs::rename(temp_file_path, file_path)?;
-    Ok(())
-}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::extras::Value;
+    use crate::lexer::tokenize;
+    use std::{fs, path::Path};
 
     #[test]
     fn test_delete_parser() {
         let parser = DeleteParser;
-        let input = vec![
-            "delete".to_string(),
-            "from".to_string(),
-            "test_table".to_string(),
-            "where".to_string(),
-            "id".to_string(),
-            "=".to_string(),
-            "1".to_string(),
-        ];
+        let input = tokenize("delete from test_table where id = 1").unwrap();
 
         let result = parser.parse(input);
         assert!(result.is_ok());
@@ -162,23 +137,66 @@ mod tests {
             assert_eq!(delete_query.table_name, "test_table");
             assert_eq!(
                 delete_query.condition,
-                vec!["id".to_string(), "=".to_string(), "1".to_string()]
+                Some(Expr::Compare {
+                    left: Box::new(Expr::Column("id".to_string())),
+                    operator: "=".to_string(),
+                    right: Box::new(Expr::Literal(Value::Int(1))),
+                })
             );
         }
     }
     #[test]
     fn test_delete_parser_invalid_missing_from() {
         let parser = DeleteParser;
-        let input = vec![
-            "delete".to_string(),
-            "table".to_string(),
-            "test_table".to_string(),
-        ];
+        let input = tokenize("delete table test_table").unwrap();
 
         let result = parser.parse(input);
         assert!(result.is_err());
         if let Err(error) = result {
-            assert_eq!(error, ErrorType::InvalidSyntax);
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+        }
+    }
+
+    #[test]
+    fn test_delete_parser_invalid_after_table_name() {
+        let parser = DeleteParser;
+        for query in [
+            "DELETE FROM ordenes WEHRE id = 101",
+            "DELETE FROM ordenes id = 101",
+            "DELETE FROM ordenes WHERE id = 101 LIMIT 1",
+            "DELETE FROM ordenes WHERE id = 101 ORDER BY id",
+        ] {
+            assert_eq!(
+                parser
+                    .parse(tokenize(query).unwrap())
+                    .map(|_| ())
+                    .map_err(|error| error.kind()),
+                Err(ErrorType::InvalidSyntax)
+            );
         }
     }
+
+    #[test]
+    fn test_delete_many_rows_in_one_pass() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_delete_many_rows.csv";
+        let rows: String = (1..=10).map(|id| format!("{},item {}\n", id, id)).collect();
+        fs::write(test_file, format!("id,name\n{}", rows))?;
+
+        let query = DeleteQuery {
+            table_name: "test_delete_many_rows".to_string(),
+            condition: Some(Expr::Compare {
+                left: Box::new(Expr::Column("id".to_string())),
+                operator: ">".to_string(),
+                right: Box::new(Expr::Literal(Value::Int(3))),
+            }),
+        };
+
+        assert_eq!(delete(".", query).ok(), Some(7));
+        let contents = fs::read_to_string(test_file)?;
+        assert_eq!(contents, "id,name\n1,item 1\n2,item 2\n3,item 3\n");
+        assert!(!Path::new("test_delete_many_rows.csv.tmp").exists());
+
+        fs::remove_file(test_file)?;
+        Ok(())
+    }
 }
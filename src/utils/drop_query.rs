@@ -0,0 +1,180 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use crate::query::{CommandParser, Query};
+use crate::{
+    error::{ErrorType, SqlError},
+    extras::{get_table_path, syntax_error_at, table_file_path, write_error},
+    lexer::{Token, TokenKind},
+    schema::schema_path,
+};
+
+#[derive(Debug)]
+
+/// Representa una consulta `DROP TABLE`, con los parámetros:
+///
+/// * `table_name` - El nombre de la tabla a eliminar.
+/// * `if_exists` - Si la consulta tiene `IF EXISTS`, en cuyo caso no es un error que la tabla no exista.
+///
+pub struct DropTableQuery {
+    pub table_name: String,
+    pub if_exists: bool,
+}
+
+pub struct DropParser;
+
+impl CommandParser for DropParser {
+    /// Valida la sintaxis de la consulta `DROP TABLE`.
+    ///
+    /// # Argumentos
+    /// * `tokens` - Una referencia a los tokens de la consulta.
+    ///
+    /// # Retorno
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
+    ///
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 3 || !tokens[0].is_keyword("drop") || !tokens[1].is_keyword("table") {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "Sintaxis inválida: falta 'DROP TABLE'",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parsea la consulta de eliminación de tabla y la convierte en una estructura `Query`.
+    ///
+    /// # Argumentos
+    /// * `tokens` - Los tokens de la consulta.
+    ///
+    /// # Retorno
+    /// Devuelve un `Ok(Query)` con una consulta `DROP TABLE` si el parseo es exitoso,
+    /// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
+    ///
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let if_exists =
+            tokens.len() > 3 && tokens[2].is_keyword("if") && tokens[3].is_keyword("exists");
+        let name_index = if if_exists { 4 } else { 2 };
+        let table_name = match tokens.get(name_index) {
+            Some(token) if token.kind == TokenKind::Identifier => token.value.to_string(),
+            _ => {
+                return Err(syntax_error_at(
+                    &tokens,
+                    name_index,
+                    "Sintaxis inválida: falta el nombre de la tabla",
+                ))
+            }
+        };
+        if name_index + 1 != tokens.len() {
+            return Err(syntax_error_at(
+                &tokens,
+                name_index + 1,
+                "Sintaxis inválida: tokens inesperados luego del nombre de la tabla",
+            ));
+        }
+        Ok(Query::DropTable(DropTableQuery {
+            table_name,
+            if_exists,
+        }))
+    }
+}
+
+/// Elimina una tabla: su archivo CSV y, si lo tiene, su archivo de esquema.
+///
+/// # Argumentos
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
+/// * `query` - La consulta `DROP TABLE`.
+///
+/// # Retorno
+/// Devuelve `Ok(0)` si la tabla se eliminó (o no existía y la consulta tiene `IF EXISTS`), o un `SqlError`
+/// de tipo `ErrorType::InvalidTable` si la tabla no existe, su nombre no es válido o no se pudo borrar.
+///
+pub fn drop_table(path: &str, query: DropTableQuery) -> Result<usize, SqlError> {
+    if query.if_exists && !Path::new(&table_file_path(path, &query.table_name)?).is_file() {
+        return Ok(0);
+    }
+    let table_path = get_table_path(path, &query.table_name)?;
+    fs::remove_file(&table_path).map_err(|e| write_error(&table_path, e))?;
+
+    let schema_path = schema_path(&table_path);
+    match fs::remove_file(&schema_path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(write_error(&schema_path, e)),
+        _ => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    fn parse(query: &str) -> Result<DropTableQuery, ErrorType> {
+        let tokens = tokenize(query).unwrap();
+        DropParser
+            .validate_syntax(&tokens)
+            .and_then(|_| DropParser.parse(tokens))
+            .map_err(|error| error.kind())
+            .map(|query| match query {
+                Query::DropTable(drop_query) => drop_query,
+                _ => panic!("Se esperaba una consulta DROP TABLE"),
+            })
+    }
+
+    #[test]
+    fn test_drop_parser() {
+        let query = parse("DROP TABLE clientes").unwrap();
+        assert_eq!(query.table_name, "clientes");
+        assert!(!query.if_exists);
+
+        let query = parse("drop table if exists clientes").unwrap();
+        assert!(query.if_exists);
+
+        assert_eq!(
+            parse("DROP TABLE clientes ordenes").err(),
+            Some(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_drop_table_removes_csv_and_schema() -> Result<(), Box<dyn std::error::Error>> {
+        fs::write("test_drop_table.csv", "id\n1\n")?;
+        fs::write("test_drop_table.schema", "id INT\n")?;
+
+        assert_eq!(
+            drop_table(".", parse("DROP TABLE test_drop_table").unwrap()).ok(),
+            Some(0)
+        );
+        assert!(fs::metadata("test_drop_table.csv").is_err());
+        assert!(fs::metadata("test_drop_table.schema").is_err());
+
+        let missing = drop_table(".", parse("DROP TABLE test_drop_table").unwrap());
+        assert_eq!(
+            missing.map_err(|error| error.kind()),
+            Err(ErrorType::InvalidTable)
+        );
+        let query = parse("DROP TABLE IF EXISTS test_drop_table").unwrap();
+        assert_eq!(drop_table(".", query).ok(), Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_drop_table_outside_database_directory() -> Result<(), Box<dyn std::error::Error>> {
+        fs::write("test_drop_escaped.csv", "id\n1\n")?;
+        fs::write("test_drop_escaped.schema", "id INT\n")?;
+
+        for query in [
+            r#"DROP TABLE "../test_drop_escaped""#,
+            r#"DROP TABLE IF EXISTS "../test_drop_escaped""#,
+        ] {
+            assert_eq!(
+                drop_table("tests", parse(query).unwrap()).map_err(|error| error.kind()),
+                Err(ErrorType::InvalidTable)
+            );
+        }
+        assert_eq!(fs::read_to_string("test_drop_escaped.csv")?, "id\n1\n");
+        assert_eq!(fs::read_to_string("test_drop_escaped.schema")?, "id INT\n");
+
+        fs::remove_file("test_drop_escaped.csv")?;
+        fs::remove_file("test_drop_escaped.schema")?;
+        Ok(())
+    }
+}
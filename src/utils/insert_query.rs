@@ -1,12 +1,18 @@
 use std::{
     fs::{File, OpenOptions},
-    io::{self, BufRead, Write},
+    io::{self, Read, Seek, SeekFrom, Write},
 };
 
 use crate::query::{CommandParser, Query};
 use crate::{
-    error::{self, print_error, ErrorType},
-    extras::{cleaned_values, get_column_index},
+    csv::write_record,
+    error::{ErrorType, SqlError},
+    extras::{
+        get_column_index, get_parenthesized_fields, get_parenthesized_values, get_table_path,
+        open_table, syntax_error_at, write_error,
+    },
+    lexer::{Token, TokenKind},
+    schema::{validate_values, TableSchema},
 };
 
 #[derive(Debug)]
@@ -15,12 +21,12 @@ use crate::{
 ///
 /// * `table_name` - El nombre de la tabla en la que se realizará la inserción.
 /// * `columns` - Las columnas en las que se insertarán los valores.
-/// * `values` - Los valores a insertar en las columnas correspondientes.
+/// * `values` - Los valores a insertar en las columnas correspondientes (`None` para `NULL`).
 ///
 pub struct InsertQuery {
     pub table_name: String,
     pub columns: Vec<String>,
-    pub values: Vec<String>,
+    pub values: Vec<Option<String>>,
 }
 
 pub struct InsertParser;
@@ -29,34 +35,31 @@ impl CommandParser for InsertParser {
     /// Valida la sintaxis de la consulta `INSERT`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una referencia a un `Vec<String>` con los componentes de la consulta ya parseados.
+    /// * `tokens` - Una referencia a los tokens de la consulta.
     ///
     /// # Retorno
-    /// Devuelve `Ok(())` si la sintaxis es válida, o `Err(ErrorType)` si es inválida.
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
     ///
-    fn validate_syntax(&self, parsed_query: &[String]) -> Result<(), ErrorType> {
-        if parsed_query.len() < 4 || parsed_query[0] != "insert" || parsed_query[1] != "into" {
-            error::print_error(
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 4 || !tokens[0].is_keyword("insert") || !tokens[1].is_keyword("into") {
+            return Err(SqlError::new(
                 ErrorType::InvalidSyntax,
                 "Sintaxis inválida: falta 'INSERT INTO'",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
+        }
+        if tokens[2].kind != TokenKind::Identifier {
+            return Err(syntax_error_at(
+                tokens,
+                2,
+                "Sintaxis inválida: falta el nombre de la tabla",
+            ));
         }
-        let table_name_index = parsed_query
-            .iter()
-            .position(|x| x == "into")
-            .map(|index| index + 1);
-        let values_start = parsed_query.iter().position(|x| x == "values");
-        let table_index = table_name_index.ok_or(ErrorType::InvalidSyntax)?;
-        let value_index = values_start.ok_or(ErrorType::InvalidSyntax)?;
-        let number_col = parsed_query[table_index + 1..value_index].len();
-        let number_val = parsed_query[value_index + 1..].len();
-        if number_col != number_val {
-            error::print_error(
+        let (columns, values) = extract_columns_and_values(tokens)?;
+        if columns.len() != values.len() {
+            return Err(SqlError::new(
                 ErrorType::InvalidSyntax,
                 "Cantidad de columnas no coincide con cantidad de valores",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
         }
 
         Ok(())
@@ -64,32 +67,15 @@ impl CommandParser for InsertParser {
     /// Parsea la consulta de inserción y la convierte en una estructura `Query`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una `Vec<String>` que contiene los componentes de la consulta.
+    /// * `tokens` - Los tokens de la consulta.
     ///
     /// # Retorno
     /// Devuelve un `Ok(Query)` con una consulta de inserción si el parseo es exitoso,
     /// o un `ErrorType::InvalidSyntax` si ocurre un error durante el parseo.
     ///
-    fn parse(&self, parsed_query: Vec<String>) -> Result<Query, ErrorType> {
-        let mut table_index = 0;
-        let _table_name_index = parsed_query
-            .iter()
-            .position(|x| x == "insert")
-            .and_then(|index| {
-                if index + 1 < parsed_query.len() && parsed_query[index + 1] == "into" {
-                    table_index = index + 2;
-                    Some(index)
-                } else {
-                    None
-                }
-            });
-        let table_name = parsed_query[table_index].to_string();
-        let mut value_index = 0;
-        if let Some(index) = parsed_query.iter().position(|x| x == "values") {
-            value_index = index;
-        }
-        let values = cleaned_values(parsed_query[value_index + 1..].to_vec());
-        let columns = cleaned_values(parsed_query[table_index + 1..value_index].to_vec());
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let table_name = tokens[2].value.to_string();
+        let (columns, values) = extract_columns_and_values(&tokens)?;
         Ok(Query::Insert(InsertQuery {
             table_name,
             columns,
@@ -98,30 +84,61 @@ impl CommandParser for InsertParser {
     }
 }
 
+/// Extrae la lista de columnas y la lista de valores de la consulta de inserción,
+/// con la forma `INSERT INTO tabla (columnas) VALUES (valores)`.
+///
+/// # Argumentos
+/// * `tokens` - Los tokens de la consulta.
+///
+/// # Retorno
+/// Devuelve una tupla con las columnas y los valores, o un `SqlError` de tipo `ErrorType::InvalidSyntax` si la consulta está mal formada.
+///
+fn extract_columns_and_values(
+    tokens: &[Token],
+) -> Result<(Vec<String>, Vec<Option<String>>), SqlError> {
+    let (columns, values_index) = get_parenthesized_values(tokens, 3)?;
+    if !tokens
+        .get(values_index)
+        .is_some_and(|token| token.is_keyword("values"))
+    {
+        return Err(syntax_error_at(
+            tokens,
+            values_index,
+            "Sintaxis inválida: falta 'VALUES'",
+        ));
+    }
+    let (values, end) = get_parenthesized_fields(tokens, values_index + 1)?;
+    if end != tokens.len() {
+        return Err(syntax_error_at(
+            tokens,
+            end,
+            "Sintaxis inválida: tokens inesperados luego de 'VALUES'",
+        ));
+    }
+    Ok((columns, values))
+}
+
 /// Inserta una fila en el archivo CSV según la consulta.
 ///
 /// # Argumentos
-/// * `path` - La ruta del archivo CSV.
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
 /// * `query` - La consulta de inserción.
 ///
 /// # Retorno
-/// Devuelve `Ok(())` si la inserción es exitosa, o un `ErrorType::InvalidTable` si ocurre un error durante la apertura del archivo.
+/// Devuelve la cantidad de filas insertadas si la inserción es exitosa, o un `SqlError` de tipo `ErrorType::InvalidTable`
+/// si ocurre un error al abrir, leer o escribir el archivo.
+/// Si alguna columna no existe devuelve un error de tipo `ErrorType::InvalidColumn`, y si la tabla tiene esquema y algún
+/// valor no corresponde al tipo de su columna, un error de tipo `ErrorType::Error`.
 ///
-pub fn insert(path: &str, query: InsertQuery) -> Result<(), ErrorType> {
-    if let Ok(file) = File::open(path) {
-        let mut reader: io::BufReader<File> = io::BufReader::new(file);
-        let mut header: String = String::new();
-        let _ = reader.read_line(&mut header);
-        let header = header.trim();
-        let headers: Vec<String> = header.split(',').map(|s| s.to_string()).collect();
-        let row_to_insert = generate_row_to_insert(&headers, &query.columns, &query.values);
-
-        write_csv(path, Some(row_to_insert));
-    } else {
-        print_error(ErrorType::InvalidTable, "No se pudo abrir el archivo");
-        return Err(ErrorType::InvalidTable);
-    }
-    Ok(())
+pub fn insert(path: &str, query: InsertQuery) -> Result<usize, SqlError> {
+    let path = &get_table_path(path, &query.table_name)?;
+    let (headers, _) = open_table(path)?;
+    let schema = TableSchema::load(path, &headers)?;
+    validate_values(schema.as_ref(), &headers, &query.columns, &query.values)?;
+    let row_to_insert = generate_row_to_insert(&headers, &query.columns, &query.values);
+
+    write_csv(path, Some(row_to_insert))?;
+    Ok(1)
 }
 
 /// Genera una fila con los valores a insertar, ajustando el orden de acuerdo con los encabezados.
@@ -132,119 +149,186 @@ pub fn insert(path: &str, query: InsertQuery) -> Result<(), ErrorType> {
 /// * `values` - Los valores a insertar en las columnas correspondientes.
 ///
 /// # Retorno
-/// Devuelve un vector de `String` que representa la fila con los valores a insertar.
+/// Devuelve un vector con los campos de la fila a insertar (`None` para `NULL`).
 ///
 /// # Notas
-/// Si una columna no tiene un valor correspondiente, se inserta una cadena vacía.
-/// Este método tambien se utiliza en el módulo `update_query.rs` para generar la fila cuando no se especifican condiciones.
+/// Si una columna no tiene un valor correspondiente, se inserta `NULL`.
+/// Los valores se guardan tal cual; las comas, comillas o saltos de línea se escapan al escribir la fila con `write_csv`.
 ///
 pub fn generate_row_to_insert(
     headers: &[String],
     columns: &Vec<String>,
-    values: &[String],
-) -> Vec<String> {
-    let mut row_to_insert: Vec<String> = vec![String::new(); headers.len()];
+    values: &[Option<String>],
+) -> Vec<Option<String>> {
+    let mut row_to_insert: Vec<Option<String>> = vec![None; headers.len()];
     for i in headers {
         for j in columns {
             if j == i {
                 let n_column = get_column_index(headers, j) as usize;
                 let n_value = get_column_index(columns, i) as usize;
-                row_to_insert[n_column] = values[n_value].to_string();
-            } else {
-                let index = get_column_index(headers, i);
-                let index = index as usize;
-                row_to_insert[index].push_str("");
+                row_to_insert[n_column] = values[n_value].clone();
             }
         }
     }
     row_to_insert
 }
 
-/// Escribe una línea en el archivo CSV, añadiéndola al final del archivo.
+/// Escribe un registro en el archivo CSV, añadiéndolo al final del archivo.
+/// Los valores con comas, comillas o saltos de línea se escriben entre comillas (ver el módulo `csv`).
+/// Si el archivo no termina en un salto de línea, se agrega uno antes del registro (ver `prepare_end`).
 ///
 /// # Argumentos
 /// * `path` - La ruta del archivo CSV.
-/// * `values` - Los valores a escribir en la nueva línea. Si es `None`, no se escribe nada.
+/// * `values` - Los campos a escribir en la nueva línea (`None` para `NULL`). Si es `None`, no se escribe nada.
 ///
-/// # Notas
-/// Si ocurre un error al abrir el archivo o escribir en él, se imprime un mensaje de error en la consola.
-///     
-pub fn write_csv(path: &str, values: Option<Vec<String>>) {
-    let file = OpenOptions::new()
+/// # Retorno
+/// Devuelve `Ok(())` si la escritura es exitosa, o un `SqlError` de tipo `ErrorType::InvalidTable`
+/// con el error de entrada/salida como causa si no se pudo abrir o escribir el archivo.
+///
+pub fn write_csv(path: &str, values: Option<Vec<Option<String>>>) -> Result<(), SqlError> {
+    let mut file = OpenOptions::new()
+        .read(true)
         .append(true)
         .create(true)
         .open(path)
-        .map_err(|e| e.to_string());
-
-    let mut file = match file {
-        Ok(f) => f,
-        Err(e) => {
-            println!("Failed to open the file: {}", e);
-            return;
-        }
-    };
-    let mut line = String::new();
+        .map_err(|e| write_error(path, e))?;
     if let Some(values) = values {
-        for (i, value) in values.iter().enumerate() {
-            if i > 0 {
-                line.push(',');
+        prepare_end(&mut file, values.len()).map_err(|e| write_error(path, e))?;
+        write_record(&mut file, &values).map_err(|e| write_error(path, e))?;
+    }
+    Ok(())
+}
+
+/// Deja el final del archivo listo para agregar un registro de `fields` campos: si el último registro no termina
+/// en un salto de línea, lo agrega, y si el registro tiene más de un campo, descarta las líneas vacías del final
+/// (que `CsvReader` ignora, pero que antes de un registro nuevo harían inválida la tabla). En una tabla de una
+/// sola columna, una línea vacía es un valor `NULL` y se conserva.
+fn prepare_end(file: &mut File, fields: usize) -> io::Result<()> {
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(());
+    }
+    if byte_at(file, len - 1)? != b'\n' {
+        return file.write_all(b"\n");
+    }
+    if fields > 1 {
+        let mut end = len;
+        loop {
+            if end >= 2 && byte_at(file, end - 2)? == b'\n' {
+                end -= 1;
+            } else if end >= 3
+                && byte_at(file, end - 2)? == b'\r'
+                && byte_at(file, end - 3)? == b'\n'
+            {
+                end -= 2;
+            } else {
+                break;
             }
-            line.push_str(value);
         }
-        line.push('\n');
-        if let Err(_e) = file.write_all(line.as_bytes()) {
-            let error = ErrorType::InvalidTable;
-            print_error(error, "No se pudo escribir en el archivo");
+        if end < len {
+            file.set_len(end)?;
         }
     }
+    Ok(())
+}
+
+/// Lee el byte del archivo que está en la posición `position`.
+fn byte_at(file: &mut File, position: u64) -> io::Result<u8> {
+    let mut byte = [0u8];
+    file.seek(SeekFrom::Start(position))?;
+    file.read_exact(&mut byte)?;
+    Ok(byte[0])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::tokenize;
 
     #[test]
     fn test_insert_parser() {
         let parser = InsertParser;
-        let input = vec![
-            "insert".to_string(),
-            "into".to_string(),
-            "test_table".to_string(),
-            "name".to_string(),
-            "age".to_string(),
-            "values".to_string(),
-            "Alice".to_string(),
-            "30".to_string(),
-        ];
+        let input =
+            tokenize("insert into test_table (name,age) values ('Alice Smith',30)").unwrap();
 
         let result = parser.parse(input);
 
         if let Ok(Query::Insert(insert_query)) = result {
             assert_eq!(insert_query.table_name, "test_table");
             assert_eq!(insert_query.columns, vec!["name", "age"]);
-            assert_eq!(insert_query.values, vec!["Alice", "30"]);
+            assert_eq!(
+                insert_query.values,
+                vec![Some("Alice Smith".to_string()), Some("30".to_string())]
+            );
+        } else {
+            panic!("No se pudo parsear la consulta INSERT");
         }
     }
 
+    #[test]
+    fn test_insert_validate_mismatched_values() {
+        let parser = InsertParser;
+        let input = tokenize("insert into test_table (name, age) values ('Alice')").unwrap();
+
+        assert_eq!(
+            parser.validate_syntax(&input).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidSyntax)
+        );
+    }
+
     #[test]
     fn test_generate_row_to_insert() {
         let headers = vec!["id".to_string(), "name".to_string(), "age".to_string()];
         let columns = vec!["name".to_string(), "age".to_string()];
-        let values = vec!["Alice".to_string(), "30".to_string()];
+        let values = vec![Some("Alice".to_string()), Some("30".to_string())];
 
         let result = generate_row_to_insert(&headers, &columns, &values);
 
-        assert_eq!(result, vec!["", "Alice", "30"]);
+        assert_eq!(
+            result,
+            vec![None, Some("Alice".to_string()), Some("30".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_insert_null_and_missing_columns() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_insert_null.csv";
+        std::fs::write(test_file, "id,nombre,email\n")?;
+        std::fs::write(
+            "test_insert_null.schema",
+            "id INT\nnombre TEXT\nemail TEXT\n",
+        )?;
+
+        let input =
+            tokenize("insert into test_insert_null (id, nombre) values (NULL, '')").unwrap();
+        let query = match InsertParser.parse(input)? {
+            Query::Insert(query) => query,
+            _ => panic!("Se esperaba una consulta INSERT"),
+        };
+        assert_eq!(query.values, vec![None, Some(String::new())]);
+        assert_eq!(insert(".", query).ok(), Some(1));
+        assert_eq!(
+            std::fs::read_to_string(test_file)?,
+            "id,nombre,email\n,\"\",\n"
+        );
+
+        std::fs::remove_file(test_file)?;
+        std::fs::remove_file("test_insert_null.schema")?;
+        Ok(())
     }
 
     #[test]
     fn test_write_csv() -> Result<(), Box<dyn std::error::Error>> {
         let test_file: &str = "test_write_csv.csv";
-        let data = vec!["1".to_string(), "Alice".to_string(), "30".to_string()];
+        let data = vec![
+            Some("1".to_string()),
+            Some("Alice".to_string()),
+            Some("30".to_string()),
+        ];
 
         let _ = std::fs::remove_file(test_file);
 
-        write_csv(test_file, Some(data));
+        write_csv(test_file, Some(data))?;
 
         let contents = std::fs::read_to_string(test_file)?;
         assert!(contents.contains("1,Alice,30"));
@@ -253,4 +337,57 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_write_csv_after_missing_newline_and_blank_lines(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_write_csv_newline.csv";
+        let row = |id: &str, name: &str| Some(vec![Some(id.to_string()), Some(name.to_string())]);
+
+        std::fs::write(test_file, "id,name\n1,a")?;
+        write_csv(test_file, row("2", "b"))?;
+        assert_eq!(std::fs::read_to_string(test_file)?, "id,name\n1,a\n2,b\n");
+
+        std::fs::write(test_file, "id,name\r\n1,a\r\n\r\n\n")?;
+        write_csv(test_file, row("2", "b"))?;
+        assert_eq!(
+            std::fs::read_to_string(test_file)?,
+            "id,name\r\n1,a\r\n2,b\n"
+        );
+
+        std::fs::write(test_file, "id\n1\n\n")?;
+        write_csv(test_file, Some(vec![Some("2".to_string())]))?;
+        assert_eq!(std::fs::read_to_string(test_file)?, "id\n1\n\n2\n");
+
+        std::fs::remove_file(test_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_validates_schema_types() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_insert_schema.csv";
+        std::fs::write(test_file, "id,nombre\n")?;
+        std::fs::write("test_insert_schema.schema", "id INT\nnombre TEXT\n")?;
+
+        let query = InsertQuery {
+            table_name: "test_insert_schema".to_string(),
+            columns: vec!["id".to_string(), "nombre".to_string()],
+            values: vec![Some("uno".to_string()), Some("Ana".to_string())],
+        };
+        let error = insert(".", query).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::Error);
+        assert_eq!(std::fs::read_to_string(test_file)?, "id,nombre\n");
+
+        let query = InsertQuery {
+            table_name: "test_insert_schema".to_string(),
+            columns: vec!["id".to_string(), "nombre".to_string()],
+            values: vec![Some("1".to_string()), Some("Ana".to_string())],
+        };
+        assert_eq!(insert(".", query).ok(), Some(1));
+        assert_eq!(std::fs::read_to_string(test_file)?, "id,nombre\n1,Ana\n");
+
+        std::fs::remove_file(test_file)?;
+        std::fs::remove_file("test_insert_schema.schema")?;
+        Ok(())
+    }
 }
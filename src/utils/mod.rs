@@ -1,4 +1,7 @@
+pub mod alter_query;
+pub mod create_query;
 pub mod delete_query;
+pub mod drop_query;
 pub mod insert_query;
 pub mod select_query;
 pub mod update_query;
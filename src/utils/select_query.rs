@@ -1,67 +1,176 @@
 use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, BufRead},
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt, iter,
 };
 
 use crate::query::{CommandParser, Query};
 use crate::{
-    error::{self, print_error, ErrorType},
+    aggregate::{Aggregate, GroupTable},
+    error::{ErrorType, SqlError},
+    expression::{parse_expression, Expr},
     extras::{
-        cast_to_value, cleaned_values, get_column_index, get_columns, get_condition_columns,
-        get_int_value, get_str_value,
+        find_column, get_condition, get_parenthesized_values, get_table_path, open_table,
+        read_error, syntax_error_at, Value,
     },
+    join::{qualify, HashJoin, Join, JoinCondition, JoinKind},
+    lexer::{Token, TokenKind},
+    operations::compare_values,
+    result::ResultSet,
+    schema::{parse_row, TableSchema},
 };
 
-use crate::operations::filter;
-
 #[derive(Debug)]
 
 /// Representa una consulta `SELECT`, con los parámetros:
 ///
 /// * `table_name` - Nombre de la tabla de la cual se seleccionarán los datos.
-/// * `columns` - Columnas que se van a seleccionar.
-/// * `condition` - Condiciones que deben cumplirse para seleccionar filas.
-/// * `order_by` - Criterios de ordenamiento para los resultados.
+/// * `alias` - Nombre con el que se referencia a la tabla en la consulta, si tiene alias (`FROM ordenes o`).
+/// * `joins` - Tablas que se combinan con la primera mediante `JOIN ... ON`, en orden.
+/// * `distinct` - Si se descartan las filas repetidas del resultado (`SELECT DISTINCT`).
+/// * `columns` - Columnas, funciones de agregación o expresiones que se van a seleccionar.
+/// * `condition` - Condición que deben cumplir las filas para ser seleccionadas, si la consulta tiene `WHERE`.
+/// * `group_by` - Columnas por las que se agrupan las filas, si la consulta tiene `GROUP BY`.
+/// * `having` - Condición que deben cumplir los grupos, si la consulta tiene `HAVING`.
+/// * `order_by` - Criterios de ordenamiento para los resultados, en orden de precedencia (ver `OrderBy`).
+/// * `limit` - Cantidad máxima de filas a devolver, si la consulta tiene `LIMIT`.
+/// * `offset` - Cantidad de filas a saltear al principio del resultado (`OFFSET`, o 0 si no lo tiene).
 ///
 pub struct SelectQuery {
     pub table_name: String,
-    pub columns: Vec<String>,
-    pub condition: Vec<String>,
-    pub order_by: Vec<String>,
+    pub alias: Option<String>,
+    pub joins: Vec<Join>,
+    pub distinct: bool,
+    pub columns: Vec<SelectItem>,
+    pub condition: Option<Expr>,
+    pub group_by: Vec<String>,
+    pub having: Option<Expr>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl SelectQuery {
+    /// Devuelve el nombre con el que se califican las columnas de la primera tabla: su alias o, si no tiene,
+    /// su nombre.
+    pub fn name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.table_name)
+    }
+
+    /// Indica si la consulta agrupa las filas: si tiene `GROUP BY`, `HAVING` o alguna función de agregación.
+    pub fn is_grouped(&self) -> bool {
+        !self.group_by.is_empty()
+            || self.having.is_some()
+            || self
+                .columns
+                .iter()
+                .any(|column| !column.aggregates().is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa un elemento de la lista de columnas de un `SELECT`.
+///
+/// # Variantes
+/// * `All` - Todas las columnas de la tabla (`*`).
+/// * `Column` - Una columna de la tabla, calificada o no con el nombre o el alias de su tabla (`c.nombre`).
+/// * `Aggregate` - Una función de agregación, como `COUNT(*)` o `SUM(cantidad)`.
+/// * `Expression` - Una expresión calculada para cada fila (`cantidad * 2`, `nombre || ' ' || apellido`, un
+///   literal), o cualquier elemento con alias (`cantidad AS unidades`). En una consulta agrupada se calcula
+///   para cada grupo, por lo que puede combinar agregaciones (`SUM(cantidad) * 2`).
+pub enum SelectItem {
+    All,
+    Column(String),
+    Aggregate(Aggregate),
+    Expression { expr: Expr, alias: Option<String> },
+}
+
+impl SelectItem {
+    /// Devuelve las funciones de agregación del elemento: la suya si es una agregación, o las que aparecen
+    /// en su expresión.
+    pub fn aggregates(&self) -> Vec<&Aggregate> {
+        match self {
+            SelectItem::Aggregate(aggregate) => vec![aggregate],
+            SelectItem::Expression { expr, .. } => expr.aggregates(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Devuelve la expresión del elemento, si es una expresión calculada.
+    fn expression(&self) -> Option<&Expr> {
+        match self {
+            SelectItem::Expression { expr, .. } => Some(expr),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+
+/// Representa un criterio de la cláusula `ORDER BY`, con los parámetros:
+///
+/// * `expr` - Por qué se ordena: una columna, un alias, una función de agregación o una expresión que aparece
+///   en la lista de columnas.
+/// * `descending` - Si el orden es descendente (`DESC`); si no, es ascendente.
+///
+pub struct OrderBy {
+    pub expr: Expr,
+    pub descending: bool,
+}
+
+impl OrderBy {
+    /// Devuelve el nombre de la columna del resultado por la que se ordena: el de la columna o el alias, o la
+    /// expresión como se escribe en SQL, igual que el encabezado de una expresión sin alias.
+    pub fn label(&self) -> String {
+        match &self.expr {
+            Expr::Column(name) => name.to_string(),
+            expr => expr.to_string(),
+        }
+    }
+}
+
+/// Muestra el elemento como aparece en el encabezado del resultado: una expresión se muestra con su alias o,
+/// si no tiene, como se escribe en SQL.
+impl fmt::Display for SelectItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectItem::All => write!(f, "*"),
+            SelectItem::Column(column) => write!(f, "{}", column),
+            SelectItem::Aggregate(aggregate) => write!(f, "{}", aggregate),
+            SelectItem::Expression {
+                alias: Some(alias), ..
+            } => write!(f, "{}", alias),
+            SelectItem::Expression { expr, alias: None } => write!(f, "{}", expr),
+        }
+    }
 }
+
 pub struct SelectParser;
 impl CommandParser for SelectParser {
     /// Valida la sintaxis de la consulta `SELECT`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una referencia a un `Vec<String>` con los componentes de la consulta ya parseados.
+    /// * `tokens` - Una referencia a los tokens de la consulta.
     ///
     /// # Retorno
-    /// Devuelve `Ok(())` si la sintaxis es válida, o `Err(ErrorType)` si es inválida.
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
     ///
-    fn validate_syntax(&self, parsed_query: &[String]) -> Result<(), ErrorType> {
-        if parsed_query.len() < 4
-            || parsed_query[0] != "select"
-            || !parsed_query.contains(&"from".to_string())
-        {
-            error::print_error(
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 4 || !tokens[0].is_keyword("select") || from_index(tokens).is_none() {
+            return Err(SqlError::new(
                 ErrorType::InvalidSyntax,
                 "Sintaxis inválida: falta 'SELECT' o 'FROM'",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
         }
 
-        let from_index = parsed_query
-            .iter()
-            .position(|x| x == "from")
-            .ok_or(ErrorType::InvalidSyntax)?;
+        let from_index = from_index(tokens).unwrap_or(0);
         if from_index <= 1 {
-            error::print_error(
-                ErrorType::InvalidSyntax,
+            return Err(syntax_error_at(
+                tokens,
+                from_index,
                 "Sintaxis inválida: falta la lista de columnas",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
         }
 
         Ok(())
@@ -70,446 +179,1544 @@ impl CommandParser for SelectParser {
     /// Parsea la consulta `SELECT` en un objeto `Query`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una `Vec<String>` que contiene los componentes de la consulta.
+    /// * `tokens` - Los tokens de la consulta.
     ///
     /// # Retorno
-    /// Devuelve un `Query::Select` que contiene los detalles de la consulta, o un `Err(ErrorType)` en caso de error.
+    /// Devuelve un `Query::Select` que contiene los detalles de la consulta, o un `SqlError` en caso de error.
     ///
-    fn parse(&self, parsed_query: Vec<String>) -> Result<Query, ErrorType> {
-        let table_name = extract_table_name(&parsed_query)?;
-        let columns = cleaned_values(get_columns(&parsed_query));
-        let mut condition = cleaned_values(get_condition_columns(&parsed_query));
-        let order_by = extract_order_by(&mut condition);
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let (table_name, alias, joins) = extract_from(&tokens)?;
+        let distinct = tokens
+            .get(1)
+            .is_some_and(|token| token.is_keyword("distinct"));
+        let columns = extract_columns(&tokens, if distinct { 2 } else { 1 })?;
+        let condition = get_condition(&tokens, &["group", "having", "order", "limit", "offset"])?;
+        let group_by = extract_group_by(&tokens)?;
+        let having = extract_having(&tokens)?;
+        let order_by = extract_order_by(&tokens, &columns)?;
+        let limit = extract_count(&tokens, "limit")?;
+        let offset = extract_count(&tokens, "offset")?.unwrap_or(0);
 
-        Ok(Query::Select(SelectQuery {
+        let query = SelectQuery {
             table_name,
+            alias,
+            joins,
+            distinct,
             columns,
             condition,
+            group_by,
+            having,
             order_by,
-        }))
+            limit,
+            offset,
+        };
+        validate_tables(&query)?;
+        validate_distinct(&query)?;
+        Ok(Query::Select(query))
     }
 }
 
-/// Extrae el nombre de la tabla de la consulta `SELECT`.
+/// Extrae la cláusula `FROM` de la consulta `SELECT`: la primera tabla y los `JOIN` que la siguen, de la forma
+/// `tabla [[AS] alias] tipo JOIN tabla [[AS] alias] {ON condicion | USING (columnas)} ...`, donde el tipo es
+/// `[INNER]`, `LEFT [OUTER]`, `RIGHT [OUTER]` o `FULL [OUTER]`. `CROSS JOIN` y `NATURAL ... JOIN` no llevan condición.
 ///
 /// # Argumentos
-/// * `parsed_query` - Una referencia a un `Vec<String>` con la consulta ya parseada.
+/// * `tokens` - Una referencia a los tokens de la consulta.
 ///
 /// # Retorno
-/// Devuelve el nombre de la tabla o un `Err(ErrorType)` si no se encuentra.
+/// Devuelve el nombre de la primera tabla, su alias y los `JOIN`, o un `SqlError` de tipo `ErrorType::InvalidSyntax`
+/// si falta el nombre de alguna tabla, falta `ON` o `USING`, o alguna condición está mal formada.
 ///
-fn extract_table_name(parsed_query: &[String]) -> Result<String, ErrorType> {
-    parsed_query
+fn extract_from(tokens: &[Token]) -> Result<(String, Option<String>, Vec<Join>), SqlError> {
+    let index = from_index(tokens).map_or(tokens.len(), |index| index + 1);
+    let (table_name, alias, mut index) = table_reference(
+        tokens,
+        index,
+        "Sintaxis inválida, falta el nombre de la tabla luego de 'from'",
+    )?;
+    let end = clause_end(tokens, index);
+    let mut joins = Vec::new();
+    while index < end {
+        let (kind, natural, length) = join_kind_at(tokens, index).ok_or_else(|| {
+            syntax_error_at(
+                tokens,
+                index,
+                "Sintaxis inválida: se esperaba 'JOIN' o 'WHERE' luego de la tabla",
+            )
+        })?;
+        let (join_table, join_alias, next) = table_reference(
+            tokens,
+            index + length,
+            "Sintaxis inválida, falta el nombre de la tabla luego de 'join'",
+        )?;
+        let (condition, condition_end) = if natural {
+            (Some(JoinCondition::Natural), next)
+        } else if kind == JoinKind::Cross {
+            (None, next)
+        } else {
+            let (condition, condition_end) = join_condition(tokens, next, end)?;
+            (Some(condition), condition_end)
+        };
+        joins.push(Join {
+            kind,
+            table_name: join_table,
+            alias: join_alias,
+            condition,
+        });
+        index = condition_end;
+    }
+    Ok((table_name, alias, joins))
+}
+
+/// Parsea la condición de un `JOIN` que empieza en `index`: `ON condicion`, que termina en el próximo `JOIN` o
+/// en `end`, o `USING (columnas)`. Devuelve la condición y el índice del token siguiente.
+fn join_condition(
+    tokens: &[Token],
+    index: usize,
+    end: usize,
+) -> Result<(JoinCondition, usize), SqlError> {
+    match tokens.get(index) {
+        Some(token) if token.is_keyword("on") => {
+            let condition_end = (index + 1..end)
+                .find(|&i| join_kind_at(tokens, i).is_some())
+                .unwrap_or(end);
+            let condition = parse_expression(&tokens[index + 1..condition_end])?;
+            Ok((JoinCondition::On(condition), condition_end))
+        }
+        Some(token) if token.is_keyword("using") => {
+            let (columns, next) = get_parenthesized_values(tokens, index + 1)?;
+            Ok((JoinCondition::Using(columns), next))
+        }
+        _ => Err(syntax_error_at(
+            tokens,
+            index,
+            "Sintaxis inválida: se esperaba 'ON' o 'USING' luego de la tabla",
+        )),
+    }
+}
+
+/// Parsea una tabla de la cláusula `FROM` con su alias opcional (`tabla`, `tabla alias` o `tabla AS alias`)
+/// que empieza en `index`. Devuelve el nombre, el alias y el índice del token siguiente, o un `SqlError` con
+/// el mensaje `message` si falta el nombre de la tabla.
+fn table_reference(
+    tokens: &[Token],
+    index: usize,
+    message: &str,
+) -> Result<(String, Option<String>, usize), SqlError> {
+    let table_name = tokens
+        .get(index)
+        .filter(|token| token.kind == TokenKind::Identifier)
+        .map(|token| token.value.to_string())
+        .ok_or_else(|| syntax_error_at(tokens, index, message))?;
+    let has_as = tokens
+        .get(index + 1)
+        .is_some_and(|token| token.is_keyword("as"));
+    let alias_index = if has_as { index + 2 } else { index + 1 };
+    match tokens.get(alias_index) {
+        Some(token) if token.kind == TokenKind::Identifier => {
+            Ok((table_name, Some(token.value.to_string()), alias_index + 1))
+        }
+        _ if has_as => Err(syntax_error_at(
+            tokens,
+            alias_index,
+            "Sintaxis inválida, falta el alias luego de 'as'",
+        )),
+        _ => Ok((table_name, None, index + 1)),
+    }
+}
+
+/// Reconoce el tipo de `JOIN` que empieza en `index` (por ejemplo `JOIN`, `LEFT OUTER JOIN`, `CROSS JOIN` o
+/// `NATURAL FULL JOIN`). Devuelve el tipo, si es `NATURAL` y la cantidad de tokens que ocupa.
+fn join_kind_at(tokens: &[Token], index: usize) -> Option<(JoinKind, bool, usize)> {
+    let natural = tokens
+        .get(index)
+        .is_some_and(|token| token.is_keyword("natural"));
+    let start = index + usize::from(natural);
+    let keywords: Vec<&str> = tokens
+        .get(start..)?
         .iter()
-        .position(|x| x == "from")
-        .map(|index| parsed_query[index + 1].to_string())
-        .ok_or_else(|| {
-            error::print_error(ErrorType::InvalidSyntax, "Sintaxis inválida, falta 'from'");
-            ErrorType::InvalidSyntax
-        })
+        .take(3)
+        .take_while(|token| token.kind == TokenKind::Keyword)
+        .map(|token| token.value.as_str())
+        .collect();
+    let (kind, length) = match keywords.as_slice() {
+        ["join", ..] => (JoinKind::Inner, 1),
+        ["inner", "join", ..] => (JoinKind::Inner, 2),
+        ["cross", "join", ..] if !natural => (JoinKind::Cross, 2),
+        [side, "join", ..] => (outer_join_kind(side)?, 2),
+        [side, "outer", "join"] => (outer_join_kind(side)?, 3),
+        _ => return None,
+    };
+    Some((kind, natural, start - index + length))
 }
 
-/// Extrae las columnas para la cláusula `ORDER BY` y las remueve de las condiciones.
+/// Devuelve el tipo de `JOIN` externo de la palabra clave `LEFT`, `RIGHT` o `FULL`.
+fn outer_join_kind(keyword: &str) -> Option<JoinKind> {
+    match keyword {
+        "left" => Some(JoinKind::Left),
+        "right" => Some(JoinKind::Right),
+        "full" => Some(JoinKind::Full),
+        _ => None,
+    }
+}
+
+/// Extrae la lista de columnas de la consulta `SELECT`, que va desde `SELECT` (o `SELECT DISTINCT`) hasta `FROM`.
 ///
 /// # Argumentos
-/// * `condition` - Una referencia mutable a un `Vec<String>` que contiene las condiciones de la consulta.
+/// * `tokens` - Una referencia a los tokens de la consulta.
+/// * `start` - El índice del primer token de la lista.
 ///
 /// # Retorno
-/// Devuelve un `Vec<String>` con las columnas de ordenamiento.
+/// Devuelve un `Vec<SelectItem>` con los elementos separados por comas (sin contar las comas entre los
+/// argumentos de una función), o un `SqlError` de tipo `ErrorType::InvalidSyntax` si algún elemento no es
+/// `*`, una columna, una función de agregación o una expresión válida.
 ///
-fn extract_order_by(condition: &mut Vec<String>) -> Vec<String> {
-    if let Some(index) = condition.iter().position(|x| x == "order") {
-        if index + 1 < condition.len() && condition[index + 1] == "by" {
-            let order_by = cleaned_values(condition[index + 2..].to_vec());
-            *condition = condition[..index].to_vec(); // Modify condition to exclude order clause
-            return order_by;
+fn extract_columns(tokens: &[Token], start: usize) -> Result<Vec<SelectItem>, SqlError> {
+    let end = from_index(tokens).unwrap_or(tokens.len());
+    if start >= end {
+        return Err(syntax_error_at(
+            tokens,
+            end,
+            "Sintaxis inválida: falta la lista de columnas",
+        ));
+    }
+    let mut columns = Vec::new();
+    let mut start = start;
+    while start < end {
+        let mut depth = 0;
+        let item_end = tokens[start..end]
+            .iter()
+            .position(|token| {
+                match token.kind {
+                    TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightParen => depth -= 1,
+                    _ => {}
+                }
+                depth == 0 && token.kind == TokenKind::Comma
+            })
+            .map_or(end, |offset| start + offset);
+        columns.push(parse_select_item(tokens, start, item_end)?);
+        start = item_end + 1;
+        if start == end {
+            return Err(syntax_error_at(
+                tokens,
+                end,
+                "Sintaxis inválida: falta una columna luego de la coma",
+            ));
         }
     }
-    Vec::new()
+
+    Ok(columns)
 }
 
-/// Filtra una fila de acuerdo a las condiciones dadas.
+/// Parsea un elemento de la lista de columnas: `*`, `columna`, una función de agregación o una expresión,
+/// con un alias opcional (`expresion [AS] alias`).
 ///
 /// # Argumentos
-/// * `row` - Una referencia a un `Vec<String>` que representa los valores de la fila.
-/// * `condition` - Una referencia a un `Vec<String>` que contiene las condiciones a evaluar.
-/// * `headers` - Una referencia a un `Vec<&str>` que representa los encabezados de las columnas.
+/// * `tokens` - Una referencia a los tokens de la consulta.
+/// * `start` - El índice del primer token del elemento.
+/// * `end` - El índice siguiente al último token del elemento.
 ///
 /// # Retorno
-/// Devuelve `true` si la fila cumple las condiciones, o `false` en caso contrario.
+/// Devuelve el `SelectItem` correspondiente, o un `SqlError` de tipo `ErrorType::InvalidSyntax` si el
+/// elemento no es válido. Las columnas y agregaciones con alias se devuelven como `SelectItem::Expression`.
 ///
-/// # Notas
-/// Las condiciones pueden ser simples o compuestas, y pueden incluir operadores lógicos como `AND`, `OR` y `NOT`.
-/// Esta funcion tambien es utilizada en update y delete dado que tambien se necesita filtrar las filas.
-///
-pub fn filter_row(row: &[String], condition: &[String], headers: &[&str]) -> bool {
-    if condition.is_empty() {
-        return true;
-    }
-    let headers_vec: Vec<String> = headers.iter().map(|&s| s.to_string()).collect();
-    let column_condition_index = get_column_index(&headers_vec, condition[0].as_str());
-    let column_condition_value = cast_to_value(condition[2].as_str());
-    let operator = condition[1].as_str();
-    let value = cast_to_value(&row[column_condition_index as usize]);
-    if condition.len() > 3 {
-        let (results, ops) = extract_bools_and_operators(condition, row, headers);
-        evaluate_logical_conditions(results, ops)
-    } else {
-        filter(value, column_condition_value, operator)
+fn parse_select_item(tokens: &[Token], start: usize, end: usize) -> Result<SelectItem, SqlError> {
+    let (end, alias) = select_alias(tokens, start, end)?;
+    let item = match &tokens[start..end] {
+        [token] if token.is_operator("*") => {
+            if alias.is_some() {
+                return Err(syntax_error_at(
+                    tokens,
+                    start,
+                    "Sintaxis inválida: '*' no puede tener alias",
+                ));
+            }
+            return Ok(SelectItem::All);
+        }
+        [token] if token.kind == TokenKind::Identifier => {
+            SelectItem::Column(token.value.to_string())
+        }
+        [_, dot, _] if dot.kind == TokenKind::Dot => match qualified_name_at(tokens, start) {
+            Some((name, _)) => SelectItem::Column(name),
+            None => {
+                return Err(syntax_error_at(
+                    tokens,
+                    start,
+                    "Sintaxis inválida en la lista de columnas",
+                ))
+            }
+        },
+        _ => match aggregate_at(tokens, start).filter(|(_, length)| start + length == end) {
+            Some((aggregate, _)) => SelectItem::Aggregate(aggregate),
+            None => SelectItem::Expression {
+                expr: parse_expression(&tokens[start..end])?,
+                alias: None,
+            },
+        },
+    };
+    Ok(match (item, alias) {
+        (item, None) => item,
+        (SelectItem::Column(column), alias) => SelectItem::Expression {
+            expr: Expr::Column(column),
+            alias,
+        },
+        (SelectItem::Aggregate(aggregate), alias) => SelectItem::Expression {
+            expr: Expr::Aggregate(aggregate),
+            alias,
+        },
+        (SelectItem::Expression { expr, .. }, alias) => SelectItem::Expression { expr, alias },
+        (SelectItem::All, _) => SelectItem::All,
+    })
+}
+
+/// Separa el alias de un elemento de la lista de columnas, que puede escribirse como `AS alias` o solo `alias`
+/// luego de un operando (`cantidad * 2 doble`). Devuelve el índice donde termina el elemento sin el alias y el
+/// alias, o un `SqlError` si falta el alias luego de `AS`.
+fn select_alias(
+    tokens: &[Token],
+    start: usize,
+    end: usize,
+) -> Result<(usize, Option<String>), SqlError> {
+    let item = &tokens[start..end];
+    match item {
+        [.., last] if last.is_keyword("as") => Err(syntax_error_at(
+            tokens,
+            end,
+            "Sintaxis inválida, falta el alias luego de 'as'",
+        )),
+        [_, .., as_keyword, alias] if as_keyword.is_keyword("as") => {
+            if alias.kind == TokenKind::Identifier {
+                Ok((end - 2, Some(alias.value.to_string())))
+            } else {
+                Err(syntax_error_at(
+                    tokens,
+                    end - 1,
+                    "Sintaxis inválida, el alias debe ser un nombre",
+                ))
+            }
+        }
+        [.., previous, alias]
+            if alias.kind == TokenKind::Identifier
+                && matches!(
+                    previous.kind,
+                    TokenKind::Identifier
+                        | TokenKind::Number
+                        | TokenKind::String
+                        | TokenKind::RightParen
+                ) =>
+        {
+            Ok((end - 1, Some(alias.value.to_string())))
+        }
+        _ => Ok((end, None)),
     }
 }
 
-/// Ejecuta la consulta `SELECT` sobre el archivo CSV especificado.
+/// Reconoce el nombre de una columna, calificado (`tabla.columna`) o no, que empieza en `index`. Devuelve el
+/// nombre y la cantidad de tokens que ocupa.
+fn qualified_name_at(tokens: &[Token], index: usize) -> Option<(String, usize)> {
+    match tokens.get(index..index + 3) {
+        Some([table, dot, column])
+            if table.kind == TokenKind::Identifier
+                && dot.kind == TokenKind::Dot
+                && column.kind == TokenKind::Identifier =>
+        {
+            Some((format!("{}.{}", table.value, column.value), 3))
+        }
+        _ => tokens
+            .get(index)
+            .filter(|token| token.kind == TokenKind::Identifier)
+            .map(|token| (token.value.to_string(), 1)),
+    }
+}
+
+/// Reconoce una función de agregación de la forma `FUNCION([DISTINCT] columna)` o `COUNT(*)` que empieza en
+/// `index`, usando el parser de expresiones. Devuelve la agregación y la cantidad de tokens que ocupa.
+fn aggregate_at(tokens: &[Token], index: usize) -> Option<(Aggregate, usize)> {
+    if tokens.get(index + 1)?.kind != TokenKind::LeftParen {
+        return None;
+    }
+    let close = index
+        + tokens[index..]
+            .iter()
+            .position(|token| token.kind == TokenKind::RightParen)?;
+    match parse_expression(&tokens[index..=close]) {
+        Ok(Expr::Aggregate(aggregate)) => Some((aggregate, close + 1 - index)),
+        _ => None,
+    }
+}
+
+/// Extrae las columnas de la cláusula `GROUP BY`, que termina en `HAVING`, `ORDER BY` o al final de la consulta.
 ///
 /// # Argumentos
-/// * `path` - Ruta del archivo CSV.
-/// * `query` - Un objeto `SelectQuery` con los detalles de la consulta.
+/// * `tokens` - Una referencia a los tokens de la consulta.
 ///
 /// # Retorno
-/// Devuelve `Ok(())` si la operación fue exitosa, o `Err(ErrorType)` si hubo algún error.
+/// Devuelve un `Vec<String>` con las columnas de agrupamiento (vacío si la consulta no tiene `GROUP BY`),
+/// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si falta `BY` o la lista de columnas está mal formada.
 ///
-/// # Notas
-/// Esta función lee el archivo línea por línea, y filtra linea a linea (usando filter_row) quedandose con las que cumplen la condición.
-///
-pub fn select(path: &str, query: SelectQuery) -> Result<(), ErrorType> {
-    if let Ok(file) = File::open(path) {
-        let mut reader: io::BufReader<File> = io::BufReader::new(file);
-        let mut header: String = String::new();
-
-        let _ = reader.read_line(&mut header);
-        let header = header.trim();
-        let headers: Vec<&str> = header.split(',').collect();
-        let lines = reader.lines();
-
-        let mut result_table: Vec<String> = Vec::new();
-        for line in lines {
-            if let Ok(line) = line {
-                let values: Vec<String> = line.split(",").map(|s| s.to_string()).collect();
-                if filter_row(&values, &query.condition, &headers) {
-                    result_table.push(line);
-                };
-            } else {
-                print_error(ErrorType::InvalidTable, "No se pudo leer el archivo");
-                return Err(ErrorType::InvalidTable);
+fn extract_group_by(tokens: &[Token]) -> Result<Vec<String>, SqlError> {
+    let index = match tokens.iter().position(|token| token.is_keyword("group")) {
+        Some(index) => index,
+        None => return Ok(Vec::new()),
+    };
+    if !tokens
+        .get(index + 1)
+        .is_some_and(|token| token.is_keyword("by"))
+    {
+        return Err(syntax_error_at(
+            tokens,
+            index + 1,
+            "Sintaxis inválida: se esperaba 'BY' luego de 'GROUP'",
+        ));
+    }
+    let start = index + 2;
+    let end = clause_end(tokens, start);
+    let mut columns = Vec::new();
+    let mut i = start;
+    while i < end {
+        let expects_column = columns.is_empty() || tokens[i - 1].kind == TokenKind::Comma;
+        match qualified_name_at(&tokens[..end], i) {
+            Some((column, length)) if expects_column => {
+                columns.push(column);
+                i += length;
+            }
+            _ if !expects_column && tokens[i].kind == TokenKind::Comma => i += 1,
+            _ => {
+                return Err(syntax_error_at(
+                    tokens,
+                    i,
+                    "Sintaxis inválida en la cláusula GROUP BY",
+                ))
             }
         }
-        let (order_map, insertion_order) = parse_order_by(&query.order_by, &headers);
-
-        order_rows(&mut result_table, order_map, insertion_order);
-
-        print_selected_rows(result_table, &query, &headers)
-    } else {
-        print_error(ErrorType::InvalidTable, "No se pudo abrir el archivo");
-        return Err(ErrorType::InvalidTable);
     }
-    Ok(())
+    if columns.is_empty() || tokens[end - 1].kind == TokenKind::Comma {
+        return Err(syntax_error_at(
+            tokens,
+            end,
+            "Sintaxis inválida: falta una columna en la cláusula GROUP BY",
+        ));
+    }
+    Ok(columns)
 }
 
-/// Imprime las filas seleccionadas por la consulta.
+/// Extrae y parsea la condición de la cláusula `HAVING`, que termina en `ORDER BY` o al final de la consulta.
 ///
 /// # Argumentos
-/// * `result_table` - Un `Vec<String>` con las filas seleccionadas.
-/// * `query` - Una referencia a la consulta `SelectQuery`.
-/// * `headers` - Una referencia a un `Vec<&str>` con los nombres de las columnas.
+/// * `tokens` - Una referencia a los tokens de la consulta.
+///
+/// # Retorno
+/// Devuelve `Ok(Some(Expr))` con la condición, `Ok(None)` si la consulta no tiene `HAVING`, o un error de tipo
+/// `ErrorType::InvalidSyntax` si la condición está mal formada.
 ///
-pub fn print_selected_rows(mut result_table: Vec<String>, query: &SelectQuery, headers: &[&str]) {
-    result_table.insert(0, headers.join(","));
-    if query.columns[0] == "*" {
-        for row in result_table {
-            println!("{}", row);
+fn extract_having(tokens: &[Token]) -> Result<Option<Expr>, SqlError> {
+    match tokens.iter().position(|token| token.is_keyword("having")) {
+        Some(index) => {
+            let end = clause_end(tokens, index + 1);
+            parse_expression(&tokens[index + 1..end]).map(Some)
         }
-    } else {
-        let mut selected_indices = Vec::new();
-        for column in &query.columns {
-            let column_index = get_column_index(
-                &headers
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>(),
-                column,
-            );
-            selected_indices.push(column_index);
+        None => Ok(None),
+    }
+}
+
+/// Devuelve el índice del `FROM` que termina la lista de columnas: el primero fuera de paréntesis, ya que
+/// `EXTRACT(YEAR FROM fecha)` también lleva `FROM`.
+fn from_index(tokens: &[Token]) -> Option<usize> {
+    let mut depth = 0usize;
+    tokens.iter().position(|token| {
+        match token.kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen => depth = depth.saturating_sub(1),
+            _ => {}
         }
-        for row in result_table {
-            let selected_row: Vec<&str> = selected_indices
+        depth == 0 && token.is_keyword("from")
+    })
+}
+
+/// Devuelve el índice donde termina la cláusula que empieza en `start`: el de la próxima palabra clave
+/// `WHERE`, `GROUP`, `HAVING`, `ORDER`, `LIMIT` u `OFFSET`, o el final de la consulta.
+fn clause_end(tokens: &[Token], start: usize) -> usize {
+    tokens[start..]
+        .iter()
+        .position(|token| {
+            ["where", "group", "having", "order", "limit", "offset"]
                 .iter()
-                .map(|&i| row.split(',').collect::<Vec<&str>>()[i as usize])
-                .collect();
-            println!("{}", selected_row.join(","));
+                .any(|k| token.is_keyword(k))
+        })
+        .map_or(tokens.len(), |offset| start + offset)
+}
+
+/// Extrae la cantidad de filas de la cláusula `LIMIT` u `OFFSET`.
+///
+/// # Argumentos
+/// * `tokens` - Una referencia a los tokens de la consulta.
+/// * `keyword` - La palabra clave de la cláusula (`limit` u `offset`).
+///
+/// # Retorno
+/// Devuelve `Ok(Some(n))` con la cantidad, `Ok(None)` si la consulta no tiene esa cláusula, o un `SqlError`
+/// de tipo `ErrorType::InvalidSyntax` si no la sigue un entero no negativo.
+///
+fn extract_count(tokens: &[Token], keyword: &str) -> Result<Option<usize>, SqlError> {
+    let index = match tokens.iter().position(|token| token.is_keyword(keyword)) {
+        Some(index) => index + 1,
+        None => return Ok(None),
+    };
+    let count = tokens
+        .get(index)
+        .filter(|token| token.kind == TokenKind::Number)
+        .and_then(|token| token.value.parse::<usize>().ok())
+        .ok_or_else(|| {
+            syntax_error_at(
+                tokens,
+                index,
+                "Sintaxis inválida: se esperaba un entero no negativo",
+            )
+        })?;
+    if clause_end(tokens, index) != index + 1 {
+        return Err(syntax_error_at(
+            tokens,
+            index + 1,
+            "Sintaxis inválida: token inesperado",
+        ));
+    }
+    Ok(Some(count))
+}
+
+/// Verifica que cada tabla de la cláusula `FROM` se referencie con un nombre distinto, para que las columnas
+/// calificadas no sean ambiguas.
+///
+/// # Argumentos
+/// * `query` - La consulta ya parseada.
+///
+/// # Retorno
+/// Devuelve `Ok(())` si los nombres son únicos, o un `SqlError` de tipo `ErrorType::InvalidSyntax` con el
+/// nombre repetido (por ejemplo, al combinar una tabla consigo misma sin alias).
+///
+fn validate_tables(query: &SelectQuery) -> Result<(), SqlError> {
+    let mut names = vec![query.name()];
+    for join in &query.joins {
+        if names.contains(&join.name()) {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "La tabla aparece más de una vez en FROM, debe usarse un alias distinto",
+            )
+            .with_context(join.name()));
         }
+        names.push(join.name());
     }
+    Ok(())
 }
 
-/// Parsea las columnas y direcciones especificadas en la cláusula `ORDER BY`.
+/// Verifica que una consulta agrupada solo seleccione columnas de `GROUP BY` y funciones de agregación.
 ///
 /// # Argumentos
-/// * `order_by` - Una referencia a un `Vec<String>` con las columnas para ordenar.
-/// * `headers` - Una referencia a un `Vec<&str>` con los nombres de las columnas.
+/// * `query` - La consulta ya parseada.
+/// * `headers` - Los nombres de las columnas de las filas que se agrupan.
 ///
 /// # Retorno
-/// Devuelve un `HashMap` que relaciona el índice de la columna con el ordenamiento (`asc` o `desc`)
-/// y un `Vec<usize>` que representa el orden de inserción dentro de ese hashmap.
+/// Devuelve `Ok(())` si la lista de columnas es válida, o un `SqlError` de tipo `ErrorType::InvalidSyntax`
+/// si la consulta agrupada usa `*` o una columna que no está en `GROUP BY` (también dentro de una expresión).
+/// Si alguna columna no existe o es ambigua, devuelve el error de `find_column`.
 ///
 /// # Notas
-/// Entiendo que si no se proporciona un ordenamiento, se asume que es ascendente.
+/// Las columnas se comparan por la columna de la tabla a la que se refieren y no por cómo se escriben, por lo
+/// que con `GROUP BY c.nombre` puede seleccionarse `nombre`, y viceversa.
 ///
-pub fn parse_order_by(
-    order_by: &[String],
-    headers: &[&str],
-) -> (HashMap<usize, String>, Vec<usize>) {
-    let mut order_map = HashMap::new();
-    let mut insertion_order = Vec::new();
-
-    let headers_vec: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
-
-    let mut i = 0;
-    while i < order_by.len() {
-        let column = &order_by[i];
-        let direction =
-            if i + 1 < order_by.len() && (order_by[i + 1] == "asc" || order_by[i + 1] == "desc") {
-                i += 2;
-                &order_by[i - 1]
-            } else {
-                i += 1;
-                "asc"
-            };
-
-        let column_index = get_column_index(&headers_vec, column) as usize;
-        insertion_order.push(column_index);
-        order_map.insert(column_index, direction.to_string());
+fn validate_grouping(query: &SelectQuery, headers: &[&str]) -> Result<(), SqlError> {
+    if !query.is_grouped() {
+        return Ok(());
     }
-
-    (order_map, insertion_order)
+    if query.columns.contains(&SelectItem::All) {
+        return Err(SqlError::new(
+            ErrorType::InvalidSyntax,
+            "No se puede seleccionar '*' en una consulta agrupada",
+        ));
+    }
+    let group_indices = query
+        .group_by
+        .iter()
+        .map(|column| find_column(headers, column))
+        .collect::<Result<Vec<usize>, SqlError>>()?;
+    let columns = query.columns.iter().flat_map(|item| match item {
+        SelectItem::Column(name) => vec![name.as_str()],
+        SelectItem::Expression { expr, .. } => expr.columns(),
+        _ => Vec::new(),
+    });
+    for name in columns {
+        if !group_indices.contains(&find_column(headers, name)?) {
+            return Err(SqlError::new(
+                ErrorType::InvalidSyntax,
+                "La columna debe aparecer en GROUP BY o usarse en una función de agregación",
+            )
+            .with_context(name));
+        }
+    }
+    Ok(())
 }
 
-/// Ordena las filas de acuerdo a las columnas especificadas en `ORDER BY`.
+/// Verifica que, con `SELECT DISTINCT`, las columnas de `ORDER BY` estén en la lista de columnas, ya que
+/// cada fila del resultado puede representar a varias filas de la tabla.
 ///
 /// # Argumentos
-/// * `result_table` - Una referencia mutable a un `Vec<String>` con las filas a ordenar, previamente seleccionadas del csv.
-/// * `order_map` - Un `HashMap<usize, String>` que indica el índice de la columna y su dirección de orden.
-/// * `insertion_order` - Un `Vec<usize>` que representa el orden de precedencia de las columnas para aplicar el ordenamiento.
+/// * `query` - La consulta ya parseada.
 ///
-fn order_rows(
-    result_table: &mut [String],
-    order_map: HashMap<usize, String>,
-    insertion_order: Vec<usize>,
-) {
-    result_table.sort_by(|a, b| {
-        let columns_a: Vec<&str> = a.split(',').collect();
-        let columns_b: Vec<&str> = b.split(',').collect();
-
-        for &index in &insertion_order {
-            if let Some(order) = order_map.get(&index) {
-                let cmp = compare_columns(columns_a[index], columns_b[index]);
-
-                if cmp != std::cmp::Ordering::Equal {
-                    return if order == "asc" { cmp } else { cmp.reverse() };
-                }
-            }
-        }
-        std::cmp::Ordering::Equal
-    });
+/// # Retorno
+/// Devuelve `Ok(())` si el ordenamiento es válido, o un `SqlError` de tipo `ErrorType::InvalidSyntax` con la
+/// columna que no se selecciona.
+///
+fn validate_distinct(query: &SelectQuery) -> Result<(), SqlError> {
+    if !query.distinct || query.columns.contains(&SelectItem::All) {
+        return Ok(());
+    }
+    let selected: Vec<String> = query.columns.iter().map(SelectItem::to_string).collect();
+    match query
+        .order_by
+        .iter()
+        .map(OrderBy::label)
+        .find(|label| !selected.contains(label))
+    {
+        Some(label) => Err(SqlError::new(
+            ErrorType::InvalidSyntax,
+            "Con SELECT DISTINCT, las columnas de ORDER BY deben estar en la lista de columnas",
+        )
+        .with_context(&label)),
+        None => Ok(()),
+    }
 }
 
-/// Compara dos valores de columna.
+/// Extrae los criterios de la cláusula `ORDER BY`, de la forma `elemento [ASC | DESC], ...`.
 ///
 /// # Argumentos
-/// * `val_a` - Valor de la primera columna como `&str`.
-/// * `val_b` - Valor de la segunda columna como `&str`.
+/// * `tokens` - Una referencia a los tokens de la consulta.
+/// * `columns` - La lista de columnas de la consulta.
 ///
 /// # Retorno
-/// Devuelve un `Ordering` que indica si el valor es menor, igual o mayor.
+/// Devuelve los criterios de ordenamiento (vacío si la consulta no tiene `ORDER BY`), o un `SqlError` de tipo
+/// `ErrorType::InvalidSyntax` si falta `BY`, algún elemento está mal formado o no es una columna, un alias,
+/// una función de agregación o una expresión de la lista de columnas (`ORDER BY cantidad * 2` solo es válido
+/// si se selecciona `cantidad * 2`).
 ///
-fn compare_columns(val_a: &str, val_b: &str) -> std::cmp::Ordering {
-    let val_a = cast_to_value(val_a);
-    let val_b = cast_to_value(val_b);
-
-    match (
-        get_int_value(&val_a),
-        get_int_value(&val_b),
-        get_str_value(&val_a),
-        get_str_value(&val_b),
-    ) {
-        (Some(i1), Some(i2), _, _) => i1.cmp(&i2),
-        (_, _, Some(s1), Some(s2)) => s1.cmp(&s2),
-        _ => std::cmp::Ordering::Equal,
+fn extract_order_by(tokens: &[Token], columns: &[SelectItem]) -> Result<Vec<OrderBy>, SqlError> {
+    let index = match tokens.iter().position(|token| token.is_keyword("order")) {
+        Some(index) => index,
+        None => return Ok(Vec::new()),
+    };
+    if !tokens
+        .get(index + 1)
+        .is_some_and(|token| token.is_keyword("by"))
+    {
+        return Err(syntax_error_at(
+            tokens,
+            index + 1,
+            "Sintaxis inválida: falta 'BY' luego de 'ORDER'",
+        ));
+    }
+    let computed: Vec<String> = columns
+        .iter()
+        .filter(|item| item.expression().is_some())
+        .map(SelectItem::to_string)
+        .collect();
+    let end = clause_end(tokens, index + 2);
+    let mut order_by = Vec::new();
+    let mut start = index + 2;
+    loop {
+        let mut depth = 0;
+        let item_end = tokens[start..end]
+            .iter()
+            .position(|token| {
+                match token.kind {
+                    TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightParen => depth -= 1,
+                    _ => {}
+                }
+                depth == 0 && token.kind == TokenKind::Comma
+            })
+            .map_or(end, |offset| start + offset);
+        let (expr_end, descending) = match tokens[start..item_end].last() {
+            Some(token) if token.is_keyword("desc") => (item_end - 1, true),
+            Some(token) if token.is_keyword("asc") => (item_end - 1, false),
+            _ => (item_end, false),
+        };
+        if start == expr_end {
+            return Err(syntax_error_at(
+                tokens,
+                expr_end,
+                "Sintaxis inválida: falta una columna en ORDER BY",
+            ));
+        }
+        let expr = parse_expression(&tokens[start..expr_end])?;
+        if !matches!(expr, Expr::Column(_) | Expr::Aggregate(_))
+            && !computed.contains(&expr.to_string())
+        {
+            return Err(syntax_error_at(
+                tokens,
+                start,
+                "Sintaxis inválida: ORDER BY solo admite columnas, alias, funciones de agregación o \
+                 expresiones de la lista de columnas",
+            ));
+        }
+        order_by.push(OrderBy { expr, descending });
+        if item_end == end {
+            return Ok(order_by);
+        }
+        start = item_end + 1;
     }
 }
 
-/// Extrae las evaluaciones booleanas y los operadores de las condiciones lógicas.
+/// Filtra una fila de acuerdo a la condición dada.
 ///
 /// # Argumentos
-/// * `condition` - Una referencia a un `Vec<String>` con las condiciones.
-/// * `row` - Una referencia a un `Vec<String>` con los valores de la fila.
-/// * `headers` - Una referencia a un `Vec<&str>` con los encabezados de las columnas.
+/// * `row` - Los valores tipados de la fila (ver `schema::parse_row`).
+/// * `condition` - La condición a evaluar, o `None` si la consulta no tiene `WHERE`.
+/// * `headers` - Una referencia a un `Vec<&str>` que representa los encabezados de las columnas.
 ///
 /// # Retorno
-/// Devuelve un `Vec<bool>` con los resultados de las evaluaciones y un `Vec<String>` con los operadores lógicos.
+/// Devuelve `Ok(true)` si la fila cumple la condición, `Ok(false)` en caso contrario o si el resultado es
+/// desconocido (por ejemplo, `columna = 1` cuando la columna es `NULL`), o un `SqlError` si la condición
+/// no puede evaluarse (por ejemplo, una columna inexistente).
 ///
 /// # Notas
-/// Esta función es utilizada para evaluar condiciones compuestas con operadores lógicos.
-/// Cada bool corresponde a una condicion simple.
+/// La condición es un árbol `Expr` que puede combinar comparaciones con `AND`, `OR`, `NOT`, `IS [NOT] NULL` y paréntesis.
+/// Esta funcion tambien es utilizada en update y delete dado que tambien se necesita filtrar las filas.
 ///
-fn extract_bools_and_operators(
-    condition: &[String],
-    row: &[String],
+pub fn filter_row(
+    row: &[Value],
+    condition: Option<&Expr>,
     headers: &[&str],
-) -> (Vec<bool>, Vec<String>) {
-    let mut bools = Vec::new();
-    let mut ops = Vec::new();
-    let mut i = 0;
-
-    while i < condition.len() {
-        if condition[i] == "and" || condition[i] == "or" || condition[i] == "not" {
-            ops.push(condition[i].to_string());
-            i += 1;
-        } else if i + 2 < condition.len() {
-            let column = &condition[i];
-            let operator = &condition[i + 1];
-            let value = &condition[i + 2];
-            let column_index = get_column_index(
-                &headers
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>(),
-                column,
-            ) as usize;
-            let column_value = cast_to_value(&row[column_index]);
-            let condition_value = cast_to_value(value);
-            let result = filter(column_value, condition_value, operator);
-            bools.push(result);
-
-            i += 3;
-        }
+) -> Result<bool, SqlError> {
+    match condition {
+        Some(condition) => condition.is_true(row, headers),
+        None => Ok(true),
     }
-    (bools, ops)
 }
 
-/// Evalúa las condiciones lógicas utilizando los operadores especificados.
+/// Ejecuta la consulta `SELECT` sobre el archivo CSV especificado.
 ///
 /// # Argumentos
-/// * `bools` - Un `Vec<bool>` que contiene los resultados de las evaluaciones.
-/// * `ops` - Un `Vec<String>` con los operadores lógicos.
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
+/// * `query` - Un objeto `SelectQuery` con los detalles de la consulta.
 ///
 /// # Retorno
-/// Devuelve un `bool` que indica el resultado final de la evaluación.
-///
-fn evaluate_logical_conditions(bools: Vec<bool>, ops: Vec<String>) -> bool {
-    let mut result = bools[0];
-    for (i, op) in ops.iter().enumerate() {
-        match op.as_str() {
-            "and" => result = result && bools[i + 1],
-            "or" => result = result || bools[i + 1],
-            "not" => result = !result,
-            _ => {}
-        }
-    }
-    result
-}
+/// Devuelve un `ResultSet` con las columnas y filas seleccionadas, o un `SqlError` si hubo algún error.
+///
+/// # Notas
+/// Esta función lee el archivo registro por registro (usando el módulo `csv`), y filtra linea a linea (usando filter_row) quedandose con las que cumplen la condición.
+/// Si la consulta está agrupada (ver `SelectQuery::is_grouped`), las filas no se guardan: cada una actualiza los
+/// acumuladores de su grupo apenas se lee, y el resultado tiene una fila por grupo (ver `build_grouped_result_set`).
+/// Con `LIMIT` y sin `ORDER BY`, la lectura termina apenas se juntan las filas pedidas; con `ORDER BY`, solo se
+/// guardan las `LIMIT + OFFSET` primeras filas en orden (ver `order_rows`). Con `DISTINCT`, las filas repetidas
+/// se descartan mientras se leen, antes de ordenar y de aplicar `LIMIT`.
+/// Con `JOIN`, las tablas de la derecha se leen completas al empezar (ver `join::HashJoin`) y cada fila de la
+/// primera tabla se combina con ellas a medida que se lee. Las columnas de la fila combinada se llaman
+/// `tabla.columna` (con el alias de la tabla si tiene), y pueden usarse sin calificar mientras no sean ambiguas.
+///
+pub fn select(path: &str, query: SelectQuery) -> Result<ResultSet, SqlError> {
+    let table_path = &get_table_path(path, &query.table_name)?;
+    let (header, reader) = open_table(table_path)?;
+    let schema = TableSchema::load(table_path, &header)?;
 
-#[cfg(test)]
-mod tests_select_query {
+    let mut qualified_header = qualify(query.name(), &header);
+    let mut visible: Vec<usize> = (0..qualified_header.len()).collect();
+    let mut hash_joins = Vec::new();
+    for join in &query.joins {
+        let (join_header, join_rows) = read_table(path, &join.table_name)?;
+        hash_joins.push(HashJoin::new(
+            join,
+            &mut qualified_header,
+            &mut visible,
+            &join_header,
+            join_rows,
+        )?);
+    }
+    let headers: Vec<&str> = qualified_header.iter().map(|s| s.as_str()).collect();
+    validate_grouping(&query, &headers)?;
 
-    use super::*;
+    let mut joined_rows: Box<dyn Iterator<Item = Result<Vec<Value>, SqlError>>> =
+        Box::new(reader.map(|record| {
+            let record = record.map_err(|e| read_error(table_path, e))?;
+            parse_row(schema.as_ref(), &record)
+        }));
+    for hash_join in &hash_joins {
+        let headers = &headers;
+        let matched = joined_rows.flat_map(move |row| {
+            row.and_then(|row| hash_join.probe(&row, headers))
+                .map_or_else(|e| vec![Err(e)], |rows| rows.into_iter().map(Ok).collect())
+        });
+        // Las filas de la derecha sin combinación se conocen recién al terminar de leer la izquierda.
+        let unmatched =
+            iter::once(hash_join).flat_map(|hash_join| hash_join.unmatched().into_iter().map(Ok));
+        joined_rows = Box::new(matched.chain(unmatched));
+    }
 
-    #[test]
-    fn test_select_parser() {
-        let parser = SelectParser;
-        let input = vec![
-            "select".to_string(),
-            "*".to_string(),
-            "from".to_string(),
-            "test_table".to_string(),
-            "where".to_string(),
-            "age".to_string(),
-            ">".to_string(),
-            "25".to_string(),
-            "order".to_string(),
-            "by".to_string(),
-            "name".to_string(),
-            "asc".to_string(),
-        ];
+    // Las expresiones de la lista de columnas se calculan para cada fila y se agregan al final, con su nombre
+    // en el resultado, para poder ordenar por ellas o por su alias. En una consulta agrupada se calculan por grupo.
+    let expressions: Vec<&Expr> = if query.is_grouped() {
+        Vec::new()
+    } else {
+        query
+            .columns
+            .iter()
+            .filter_map(SelectItem::expression)
+            .collect()
+    };
+    let labels = computed_labels(&query);
+    let mut output_headers = headers.clone();
+    if !query.is_grouped() {
+        output_headers.extend(labels.iter().map(|s| s.as_str()));
+    }
 
-        let result = parser.parse(input);
+    let distinct_columns = if query.distinct && !query.is_grouped() {
+        Some(selected_columns(&query, &output_headers, &visible)?.1)
+    } else {
+        None
+    };
+    let mut seen = HashSet::new();
+    let mut error = None;
+    let rows = joined_rows
+        .map_while(|row| {
+            let row = row.and_then(|values| {
+                if !filter_row(&values, query.condition.as_ref(), &headers)? {
+                    return Ok(None);
+                }
+                let values = with_computed_columns(values, &expressions, &headers)?;
+                let selected = distinct_columns
+                    .as_ref()
+                    .is_none_or(|indices| is_first_occurrence(&mut seen, &values, indices));
+                Ok(selected.then_some(values))
+            });
+            row.map_err(|e| error = Some(e)).ok()
+        })
+        .flatten();
 
-        if let Ok(Query::Select(select_query)) = result {
-            assert_eq!(select_query.table_name, "test_table");
-            assert_eq!(select_query.columns, vec!["*"]);
-            assert_eq!(select_query.condition, vec!["age", ">", "25"]);
-            assert_eq!(select_query.order_by, vec!["name", "asc"]);
+    if query.is_grouped() {
+        let mut groups = GroupTable::new(&query.group_by, &query_aggregates(&query), &headers)?;
+        for row in rows {
+            groups.add(&row)?;
         }
+        if let Some(error) = error {
+            return Err(error);
+        }
+        return build_grouped_result_set(groups, &query, &headers);
     }
 
-    #[test]
-    fn test_filter_row_match() {
-        let headers = vec!["id", "name", "age"];
-        let condition = vec!["age".to_string(), ">".to_string(), "25".to_string()];
-        let row = vec!["1".to_string(), "Agus".to_string(), "30".to_string()];
-
-        assert!(filter_row(&row, &condition, &headers));
+    let (order_map, insertion_order) = parse_order_by(&query.order_by, &output_headers)?;
+    let result_table: Vec<Vec<Value>> = if query.order_by.is_empty() {
+        rows.skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect()
+    } else {
+        let limit = query.limit.map(|limit| limit.saturating_add(query.offset));
+        order_rows(rows, order_map, insertion_order, limit)
+            .into_iter()
+            .skip(query.offset)
+            .collect()
+    };
+    if let Some(error) = error {
+        return Err(error);
     }
 
-    #[test]
-    fn test_filter_row_no_match() {
-        let headers = vec!["id", "name", "age"];
-        let condition = vec!["age".to_string(), ">".to_string(), "30".to_string()];
-        let row = vec!["1".to_string(), "Agus".to_string(), "25".to_string()];
-
-        assert!(!filter_row(&row, &condition, &headers));
-    }
+    build_result_set(result_table, &query, &output_headers, &visible)
+}
 
-    #[test]
-    fn test_parse_order_by_insertion_order() {
-        let order_by = vec![
-            "age".to_string(),
-            "asc".to_string(),
-            "name".to_string(),
-            "desc".to_string(),
-        ];
-        let headers = vec!["id", "name", "age"];
+/// Devuelve los nombres de las expresiones calculadas de la lista de columnas, en orden (ver `SelectItem::Expression`).
+fn computed_labels(query: &SelectQuery) -> Vec<String> {
+    query
+        .columns
+        .iter()
+        .filter(|item| item.expression().is_some())
+        .map(SelectItem::to_string)
+        .collect()
+}
 
-        let (order_map, insertion_order) = parse_order_by(&order_by, &headers);
+/// Agrega al final de la fila el valor de cada expresión calculada de la lista de columnas.
+///
+/// # Argumentos
+/// * `row` - Los valores de la fila (o del grupo).
+/// * `expressions` - Las expresiones a calcular.
+/// * `headers` - Los nombres de las columnas de la fila.
+///
+/// # Retorno
+/// Devuelve la fila con un valor más por expresión, o un `SqlError` si alguna no puede evaluarse. Las condiciones
+/// (`cantidad > 1`) valen `TRUE`, `FALSE` o `NULL` si el resultado es desconocido.
+///
+fn with_computed_columns(
+    mut row: Vec<Value>,
+    expressions: &[&Expr],
+    headers: &[&str],
+) -> Result<Vec<Value>, SqlError> {
+    for expr in expressions {
+        let value = match expr {
+            Expr::Compare { .. }
+            | Expr::And(_, _)
+            | Expr::Or(_, _)
+            | Expr::Not(_)
+            | Expr::IsNull { .. }
+            | Expr::Like { .. }
+            | Expr::InList { .. }
+            | Expr::Between { .. } => expr
+                .truth_value(&row, headers)?
+                .map_or(Value::Null, Value::Bool),
+            _ => expr.evaluate(&row, headers)?,
+        };
+        row.push(value);
+    }
+    Ok(row)
+}
 
-        let mut expected_order_map = HashMap::new();
-        expected_order_map.insert(2, "asc".to_string());
-        expected_order_map.insert(1, "desc".to_string());
+/// Lee todas las filas de una tabla que se combina con un `JOIN`, ya tipadas según su esquema.
+///
+/// # Argumentos
+/// * `path` - La ruta del directorio de la base de datos.
+/// * `table_name` - El nombre de la tabla.
+///
+/// # Retorno
+/// Devuelve los nombres de las columnas y las filas de la tabla, o un `SqlError` si no puede leerse.
+///
+fn read_table(path: &str, table_name: &str) -> Result<(Vec<String>, Vec<Vec<Value>>), SqlError> {
+    let path = &get_table_path(path, table_name)?;
+    let (header, reader) = open_table(path)?;
+    let schema = TableSchema::load(path, &header)?;
+    let rows = reader
+        .map(|record| {
+            let record = record.map_err(|e| read_error(path, e))?;
+            parse_row(schema.as_ref(), &record)
+        })
+        .collect::<Result<Vec<Vec<Value>>, SqlError>>()?;
+    Ok((header, rows))
+}
 
-        assert_eq!(order_map, expected_order_map);
-        assert_eq!(insertion_order, vec![2, 1]);
+/// Reúne las funciones de agregación que la consulta necesita calcular: las de la lista de columnas y las
+/// de `HAVING`, sin repetir.
+fn query_aggregates(query: &SelectQuery) -> Vec<&Aggregate> {
+    let having = query.having.iter().flat_map(Expr::aggregates);
+    let mut aggregates: Vec<&Aggregate> = Vec::new();
+    for aggregate in query
+        .columns
+        .iter()
+        .flat_map(SelectItem::aggregates)
+        .chain(having)
+    {
+        if !aggregates.contains(&aggregate) {
+            aggregates.push(aggregate);
+        }
     }
+    aggregates
+}
 
-    #[test]
-    fn test_order_rows_with_one_condition() {
-        let mut result_table = vec![
-            "1,Agus,30".to_string(),
-            "2,Bob,25".to_string(),
-            "3,Gon,35".to_string(),
+/// Arma el resultado de una consulta agrupada a partir de sus grupos.
+///
+/// # Argumentos
+/// * `groups` - El `GroupTable` con todas las filas seleccionadas ya agregadas.
+/// * `query` - Una referencia a la consulta `SelectQuery`.
+/// * `row_headers` - Los nombres de las columnas de las filas que se agruparon.
+///
+/// # Retorno
+/// Devuelve un `ResultSet` con una fila por cada grupo que cumple `HAVING`, ordenadas según `ORDER BY` y
+/// recortadas según `LIMIT` y `OFFSET`, o un `SqlError` si `HAVING` u `ORDER BY` referencian una columna que no está en `GROUP BY` ni es
+/// una agregación calculada.
+///
+/// # Notas
+/// Cada grupo es una fila cuyas columnas son las de `GROUP BY` (con el nombre calificado de la tabla, como
+/// `c.nombre`) seguidas por las agregaciones (con nombres como `COUNT(*)`), por lo que `HAVING` y `ORDER BY` se evalúan sobre esa fila igual que `WHERE` sobre la tabla.
+/// Las expresiones de la lista de columnas se calculan sobre esa misma fila luego de `HAVING`, y se agregan al
+/// final para poder ordenar por ellas.
+///
+fn build_grouped_result_set(
+    groups: GroupTable,
+    query: &SelectQuery,
+    row_headers: &[&str],
+) -> Result<ResultSet, SqlError> {
+    let mut labels = query
+        .group_by
+        .iter()
+        .map(|column| find_column(row_headers, column).map(|index| row_headers[index].to_string()))
+        .collect::<Result<Vec<String>, SqlError>>()?;
+    labels.extend(query_aggregates(query).iter().map(|a| a.to_string()));
+    let group_headers: Vec<&str> = labels.iter().map(|s| s.as_str()).collect();
+    let computed = computed_labels(query);
+    let mut headers = group_headers.clone();
+    headers.extend(computed.iter().map(|s| s.as_str()));
+    let expressions: Vec<&Expr> = query
+        .columns
+        .iter()
+        .filter_map(SelectItem::expression)
+        .collect();
+
+    let (columns, indices) = selected_columns(query, &headers, &[])?;
+    let mut seen = HashSet::new();
+    let mut grouped_rows = Vec::new();
+    for row in groups.finish() {
+        if !filter_row(&row, query.having.as_ref(), &group_headers)? {
+            continue;
+        }
+        let row = with_computed_columns(row, &expressions, &group_headers)?;
+        if !query.distinct || is_first_occurrence(&mut seen, &row, &indices) {
+            grouped_rows.push(row);
+        }
+    }
+    let (order_map, insertion_order) = parse_order_by(&query.order_by, &headers)?;
+    let limit = query.limit.map(|limit| limit.saturating_add(query.offset));
+    let grouped_rows: Vec<Vec<Value>> = order_rows(grouped_rows, order_map, insertion_order, limit)
+        .into_iter()
+        .skip(query.offset)
+        .collect();
+
+    let rows = grouped_rows
+        .iter()
+        .map(|row| indices.iter().map(|&i| row[i].clone()).collect())
+        .collect();
+    Ok(ResultSet::new(columns, rows))
+}
+
+/// Arma el resultado de la consulta proyectando las columnas seleccionadas de cada fila.
+///
+/// # Argumentos
+/// * `result_table` - Un `Vec<Vec<Value>>` con las filas seleccionadas, ya tipadas.
+/// * `query` - Una referencia a la consulta `SelectQuery`.
+/// * `headers` - Una referencia a un `Vec<&str>` con los nombres de las columnas.
+/// * `visible` - Los índices de las columnas que reemplazan a `*`.
+///
+/// # Retorno
+/// Devuelve un `ResultSet` con las columnas pedidas y sus valores tipados, o un error de tipo `ErrorType::InvalidColumn`
+/// si alguna de las columnas no existe en la tabla. Cada `*` se reemplaza por las columnas de `visible`.
+/// Las funciones de agregación no pueden proyectarse fila por fila, por lo que devuelven un error de tipo
+/// `ErrorType::InvalidSyntax`.
+///
+pub fn build_result_set(
+    result_table: Vec<Vec<Value>>,
+    query: &SelectQuery,
+    headers: &[&str],
+    visible: &[usize],
+) -> Result<ResultSet, SqlError> {
+    let (columns, selected_indices) = selected_columns(query, headers, visible)?;
+
+    let rows = result_table
+        .iter()
+        .map(|row| selected_indices.iter().map(|&i| row[i].clone()).collect())
+        .collect();
+    Ok(ResultSet::new(columns, rows))
+}
+
+/// Resuelve la lista de columnas de la consulta contra los encabezados de la fila.
+///
+/// # Argumentos
+/// * `query` - Una referencia a la consulta `SelectQuery`.
+/// * `headers` - Una referencia a un `Vec<&str>` con los nombres de las columnas, terminando con las de las
+///   expresiones calculadas en el orden en que aparecen en la consulta (ver `with_computed_columns`).
+/// * `visible` - Los índices de las columnas que reemplazan a `*`.
+///
+/// # Retorno
+/// Devuelve los nombres de las columnas del resultado y sus índices en la fila, con cada `*` reemplazado por las
+/// columnas de `visible`, o un `SqlError` si alguna columna no existe o es una función de agregación que no
+/// está entre las columnas (como en una consulta que no está agrupada).
+/// Como en SQL, el nombre de cada columna del resultado no incluye la tabla (`c.nombre` se muestra como `nombre`).
+///
+fn selected_columns(
+    query: &SelectQuery,
+    headers: &[&str],
+    visible: &[usize],
+) -> Result<(Vec<String>, Vec<usize>), SqlError> {
+    let mut columns = Vec::new();
+    let mut selected_indices = Vec::new();
+    let mut computed = headers.len() - computed_labels(query).len();
+    for item in &query.columns {
+        match item {
+            SelectItem::All => {
+                columns.extend(visible.iter().map(|&index| unqualified(headers[index])));
+                selected_indices.extend(visible);
+            }
+            SelectItem::Column(column) => {
+                selected_indices.push(find_column(headers, column)?);
+                columns.push(unqualified(column));
+            }
+            SelectItem::Aggregate(aggregate) => {
+                let label = aggregate.to_string();
+                let index = headers
+                    .iter()
+                    .position(|header| *header == label)
+                    .ok_or_else(|| {
+                        SqlError::new(
+                            ErrorType::InvalidSyntax,
+                            "La función de agregación no puede calcularse por fila",
+                        )
+                        .with_context(&label)
+                    })?;
+                selected_indices.push(index);
+                columns.push(label);
+            }
+            SelectItem::Expression { .. } => {
+                selected_indices.push(computed);
+                columns.push(item.to_string());
+                computed += 1;
+            }
+        }
+    }
+    Ok((columns, selected_indices))
+}
+
+/// Devuelve el nombre de una columna sin la tabla que la califica.
+fn unqualified(column: &str) -> String {
+    column
+        .rsplit_once('.')
+        .map_or(column, |(_, name)| name)
+        .to_string()
+}
+
+/// Registra los valores de las columnas seleccionadas de la fila y devuelve `true` si es la primera vez que
+/// aparecen. Los valores se comparan por su texto (ver `Value::to_field`), por lo que dos `NULL` se consideran iguales.
+fn is_first_occurrence(
+    seen: &mut HashSet<Vec<Option<String>>>,
+    row: &[Value],
+    indices: &[usize],
+) -> bool {
+    seen.insert(indices.iter().map(|&i| row[i].to_field()).collect())
+}
+
+/// Resuelve las columnas y direcciones especificadas en la cláusula `ORDER BY`.
+///
+/// # Argumentos
+/// * `order_by` - Los criterios de ordenamiento de la consulta (ver `OrderBy`).
+/// * `headers` - Una referencia a un `Vec<&str>` con los nombres de las columnas.
+///
+/// # Retorno
+/// Devuelve un `HashMap` que relaciona el índice de la columna con el ordenamiento (`asc` o `desc`)
+/// y un `Vec<usize>` que representa el orden de inserción dentro de ese hashmap, o un error de tipo
+/// `ErrorType::InvalidColumn` si alguna de las columnas no existe.
+///
+/// # Notas
+/// Entiendo que si no se proporciona un ordenamiento, se asume que es ascendente.
+///
+pub fn parse_order_by(
+    order_by: &[OrderBy],
+    headers: &[&str],
+) -> Result<(HashMap<usize, String>, Vec<usize>), SqlError> {
+    let mut order_map = HashMap::new();
+    let mut insertion_order = Vec::new();
+
+    for item in order_by {
+        let direction = if item.descending { "desc" } else { "asc" };
+        let column_index = find_column(headers, &item.label())?;
+        insertion_order.push(column_index);
+        order_map.insert(column_index, direction.to_string());
+    }
+
+    Ok((order_map, insertion_order))
+}
+
+/// Ordena las filas de acuerdo a las columnas especificadas en `ORDER BY`.
+///
+/// # Argumentos
+/// * `rows` - Las filas a ordenar, previamente seleccionadas del csv. Pueden leerse a medida que se ordenan.
+/// * `order_map` - Un `HashMap<usize, String>` que indica el índice de la columna y su dirección de orden.
+/// * `insertion_order` - Un `Vec<usize>` que representa el orden de precedencia de las columnas para aplicar el ordenamiento.
+/// * `limit` - La cantidad máxima de filas a devolver, o `None` para devolverlas todas.
+///
+/// # Retorno
+/// Devuelve las primeras `limit` filas en orden. Las filas iguales conservan el orden en que llegaron.
+///
+/// # Notas
+/// Con `limit`, las filas se guardan en un heap acotado que descarta la mayor cada vez que se pasa del límite,
+/// por lo que nunca hay más de `limit` filas en memoria.
+///
+fn order_rows(
+    rows: impl IntoIterator<Item = Vec<Value>>,
+    order_map: HashMap<usize, String>,
+    insertion_order: Vec<usize>,
+    limit: Option<usize>,
+) -> Vec<Vec<Value>> {
+    let order = RowOrder {
+        order_map,
+        insertion_order,
+    };
+    let limit = match limit {
+        Some(limit) => limit,
+        None => {
+            let mut rows: Vec<Vec<Value>> = rows.into_iter().collect();
+            rows.sort_by(|columns_a, columns_b| order.compare(columns_a, columns_b));
+            return rows;
+        }
+    };
+
+    let mut heap = BinaryHeap::new();
+    for (position, row) in rows.into_iter().enumerate() {
+        heap.push(HeapRow {
+            row,
+            position,
+            order: &order,
+        });
+        if heap.len() > limit {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|entry| entry.row)
+        .collect()
+}
+
+/// Criterio de ordenamiento de `ORDER BY`: las columnas en orden de precedencia y la dirección de cada una.
+struct RowOrder {
+    order_map: HashMap<usize, String>,
+    insertion_order: Vec<usize>,
+}
+
+impl RowOrder {
+    /// Compara dos filas columna por columna, hasta encontrar una en la que difieran.
+    fn compare(&self, columns_a: &[Value], columns_b: &[Value]) -> Ordering {
+        for &index in &self.insertion_order {
+            if let Some(order) = self.order_map.get(&index) {
+                let cmp = compare_columns(&columns_a[index], &columns_b[index]);
+
+                if cmp != Ordering::Equal {
+                    return if order == "asc" { cmp } else { cmp.reverse() };
+                }
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+/// Fila guardada en el heap de `order_rows`, junto con su posición de llegada para desempatar.
+struct HeapRow<'a> {
+    row: Vec<Value>,
+    position: usize,
+    order: &'a RowOrder,
+}
+
+impl Ord for HeapRow<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order
+            .compare(&self.row, &other.row)
+            .then(self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for HeapRow<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapRow<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapRow<'_> {}
+
+/// Compara dos valores de columna.
+///
+/// # Argumentos
+/// * `val_a` - Valor de la primera columna.
+/// * `val_b` - Valor de la segunda columna.
+///
+/// # Retorno
+/// Devuelve un `Ordering` que indica si el valor es menor, igual o mayor.
+/// Usa las mismas reglas de promoción numérica que los filtros (ver `compare_values`), por lo que `9.5`
+/// queda antes que `10`. Los valores que no pueden compararse (por ejemplo, un entero y un texto) se consideran iguales.
+/// `NULL` se considera mayor que cualquier otro valor, por lo que queda al final en orden ascendente.
+///
+fn compare_columns(val_a: &Value, val_b: &Value) -> Ordering {
+    match (val_a.is_null(), val_b.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => compare_values(val_a, val_b).unwrap_or(Ordering::Equal),
+    }
+}
+
+#[cfg(test)]
+mod tests_select_query {
+
+    use super::*;
+    use crate::csv::parse_record;
+    use crate::lexer::tokenize;
+
+    fn rows(lines: &[&str]) -> Vec<Vec<Value>> {
+        lines
+            .iter()
+            .map(|line| parse_row(None, &parse_record(line)).unwrap())
+            .collect()
+    }
+
+    fn compare(column: &str, operator: &str, value: Value) -> Expr {
+        Expr::Compare {
+            left: Box::new(Expr::Column(column.to_string())),
+            operator: operator.to_string(),
+            right: Box::new(Expr::Literal(value)),
+        }
+    }
+
+    fn order_labels(query: &SelectQuery) -> Vec<(String, bool)> {
+        query
+            .order_by
+            .iter()
+            .map(|item| (item.label(), item.descending))
+            .collect()
+    }
+
+    #[test]
+    fn test_select_parser() {
+        let parser = SelectParser;
+        let input = tokenize("select * from test_table where age > 25 order by name asc").unwrap();
+
+        let result = parser.parse(input);
+
+        if let Ok(Query::Select(select_query)) = result {
+            assert_eq!(select_query.table_name, "test_table");
+            assert_eq!(select_query.columns, vec![SelectItem::All]);
+            assert_eq!(
+                select_query.condition,
+                Some(compare("age", ">", Value::Int(25)))
+            );
+            assert_eq!(
+                order_labels(&select_query),
+                vec![("name".to_string(), false)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_parser_quoted_string_and_commas() {
+        let parser = SelectParser;
+        let input = tokenize(
+            "select id,nombre from clientes where ciudad = 'New York' order by id, nombre desc",
+        )
+        .unwrap();
+
+        let result = parser.parse(input);
+
+        if let Ok(Query::Select(select_query)) = result {
+            assert_eq!(
+                select_query.columns,
+                vec![
+                    SelectItem::Column("id".to_string()),
+                    SelectItem::Column("nombre".to_string())
+                ]
+            );
+            assert_eq!(
+                select_query.condition,
+                Some(compare("ciudad", "=", Value::Str("New York".to_string())))
+            );
+            assert_eq!(
+                order_labels(&select_query),
+                vec![("id".to_string(), false), ("nombre".to_string(), true)]
+            );
+        } else {
+            panic!("No se pudo parsear la consulta SELECT");
+        }
+    }
+
+    #[test]
+    fn test_select_parser_preserves_case() {
+        let parser = SelectParser;
+        let input =
+            tokenize("SELECT Nombre FROM Clientes WHERE producto = 'Laptop' ORDER BY Nombre DESC")
+                .unwrap();
+
+        let result = parser.parse(input);
+
+        if let Ok(Query::Select(select_query)) = result {
+            assert_eq!(select_query.table_name, "Clientes");
+            assert_eq!(
+                select_query.columns,
+                vec![SelectItem::Column("Nombre".to_string())]
+            );
+            assert_eq!(
+                select_query.condition,
+                Some(compare("producto", "=", Value::Str("Laptop".to_string())))
+            );
+            assert_eq!(
+                order_labels(&select_query),
+                vec![("Nombre".to_string(), true)]
+            );
+        } else {
+            panic!("No se pudo parsear la consulta SELECT");
+        }
+    }
+
+    #[test]
+    fn test_validate_grouping() {
+        let headers = vec!["c.id", "c.nombre", "o.id", "o.id_cliente", "o.cantidad"];
+        let grouping = |query: &str| {
+            let Ok(Query::Select(query)) = SelectParser.parse(tokenize(query).unwrap()) else {
+                panic!("se esperaba una consulta SELECT: {}", query);
+            };
+            validate_grouping(&query, &headers)
+        };
+
+        let valid = [
+            "select nombre, count(*) from clientes c join ordenes o on c.id = o.id_cliente group by c.nombre",
+            "select c.nombre, count(*) from clientes c join ordenes o on c.id = o.id_cliente group by nombre",
+            "select upper(nombre) from clientes c join ordenes o on c.id = o.id_cliente group by c.nombre",
+            "select nombre from clientes c join ordenes o on c.id = o.id_cliente",
         ];
+        for query in valid {
+            assert!(grouping(query).is_ok(), "{}", query);
+        }
+
+        let invalid = [
+            (
+                "select cantidad, count(*) from ordenes o group by nombre",
+                ErrorType::InvalidSyntax,
+            ),
+            (
+                "select o.id, count(*) from ordenes o group by c.id",
+                ErrorType::InvalidSyntax,
+            ),
+            (
+                "select cantidad * 2 from ordenes o group by id_cliente",
+                ErrorType::InvalidSyntax,
+            ),
+            (
+                "select * from ordenes o group by id_cliente",
+                ErrorType::InvalidSyntax,
+            ),
+            (
+                "select id, count(*) from ordenes o group by nombre",
+                ErrorType::InvalidColumn,
+            ),
+        ];
+        for (query, kind) in invalid {
+            assert_eq!(grouping(query).unwrap_err().kind(), kind, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_order_by_expressions() {
+        let tokens = tokenize(
+            "select producto, cantidad * 2 from ordenes \
+             order by cantidad * 2 desc, producto",
+        );
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens.unwrap()) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        assert_eq!(
+            query.order_by[0],
+            OrderBy {
+                expr: Expr::Arithmetic {
+                    left: Box::new(Expr::Column("cantidad".to_string())),
+                    operator: "*".to_string(),
+                    right: Box::new(Expr::Literal(Value::Int(2))),
+                },
+                descending: true,
+            }
+        );
+        assert_eq!(query.order_by[0].label(), "cantidad * 2");
+        assert_eq!(order_labels(&query)[1], ("producto".to_string(), false));
+
+        let invalid = [
+            "select producto from ordenes order by cantidad * 2",
+            "select producto from ordenes order by upper(producto)",
+            "select producto from ordenes order producto",
+            "select producto from ordenes order by",
+            "select producto from ordenes order by producto,",
+            "select producto from ordenes order by desc",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_filter_row_string_is_case_sensitive() {
+        let headers = vec!["id", "producto"];
+        let row = vec![Value::Int(1), Value::Str("Laptop".to_string())];
+        let exact = compare("producto", "=", Value::Str("Laptop".to_string()));
+        let lowercase = compare("producto", "=", Value::Str("laptop".to_string()));
+        let ilike = compare("producto", "ilike", Value::Str("laptop".to_string()));
+
+        assert_eq!(filter_row(&row, Some(&exact), &headers).ok(), Some(true));
+        assert_eq!(
+            filter_row(&row, Some(&lowercase), &headers).ok(),
+            Some(false)
+        );
+        assert_eq!(filter_row(&row, Some(&ilike), &headers).ok(), Some(true));
+    }
+
+    #[test]
+    fn test_filter_row_match() {
+        let headers = vec!["id", "name", "age"];
+        let condition = compare("age", ">", Value::Int(25));
+        let row = vec![
+            Value::Int(1),
+            Value::Str("Agus".to_string()),
+            Value::Int(30),
+        ];
+
+        assert_eq!(
+            filter_row(&row, Some(&condition), &headers).ok(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_filter_row_no_match() {
+        let headers = vec!["id", "name", "age"];
+        let condition = compare("age", ">", Value::Int(30));
+        let row = vec![
+            Value::Int(1),
+            Value::Str("Agus".to_string()),
+            Value::Int(25),
+        ];
+
+        assert_eq!(
+            filter_row(&row, Some(&condition), &headers).ok(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_filter_row_without_condition() {
+        let headers = vec!["id"];
+        let row = vec![Value::Int(1)];
+
+        assert_eq!(filter_row(&row, None, &headers).ok(), Some(true));
+    }
+
+    #[test]
+    fn test_parse_order_by_insertion_order() {
+        let order_by = vec![
+            OrderBy {
+                expr: Expr::Column("age".to_string()),
+                descending: false,
+            },
+            OrderBy {
+                expr: Expr::Column("name".to_string()),
+                descending: true,
+            },
+        ];
+        let headers = vec!["id", "name", "age"];
+
+        let (order_map, insertion_order) = parse_order_by(&order_by, &headers).unwrap();
+
+        let mut expected_order_map = HashMap::new();
+        expected_order_map.insert(2, "asc".to_string());
+        expected_order_map.insert(1, "desc".to_string());
+
+        assert_eq!(order_map, expected_order_map);
+        assert_eq!(insertion_order, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_order_rows_with_one_condition() {
+        let result_table = rows(&["1,Agus,30", "2,Bob,25", "3,Gon,35"]);
 
         let mut order_map = HashMap::new();
         order_map.insert(2, "asc".to_string());
 
         let insertion_order = vec![2];
-        order_rows(&mut result_table, order_map, insertion_order);
+        let result_table = order_rows(result_table, order_map, insertion_order, None);
 
-        assert_eq!(
-            result_table,
-            vec![
-                "2,Bob,25".to_string(),
-                "1,Agus,30".to_string(),
-                "3,Gon,35".to_string(),
-            ]
-        );
+        assert_eq!(result_table, rows(&["2,Bob,25", "1,Agus,30", "3,Gon,35"]));
     }
 
     #[test]
     fn test_order_rows_when_tie() {
-        let mut result_table = vec![
-            "1,Agus,30".to_string(),
-            "2,Bob,25".to_string(),
-            "3,Agus,35".to_string(),
-            "4,Daniel,25".to_string(),
-        ];
+        let result_table = rows(&["1,Agus,30", "2,Bob,25", "3,Agus,35", "4,Daniel,25"]);
 
         let mut order_map = HashMap::new();
 
@@ -517,17 +1724,393 @@ mod tests_select_query {
         order_map.insert(2, "desc".to_string());
 
         let insertion_order = vec![1, 2];
-        order_rows(&mut result_table, order_map, insertion_order);
+        let result_table = order_rows(result_table, order_map, insertion_order, None);
         println!("{:?}", result_table);
 
         assert_eq!(
             result_table,
+            rows(&["3,Agus,35", "1,Agus,30", "2,Bob,25", "4,Daniel,25"])
+        );
+    }
+
+    #[test]
+    fn test_order_rows_promotes_numbers() {
+        let result_table = rows(&["1,9.5", "2,10", "3,3000000000", "4,-0.25"]);
+
+        let mut order_map = HashMap::new();
+        order_map.insert(1, "asc".to_string());
+        let result_table = order_rows(result_table, order_map, vec![1], None);
+
+        assert_eq!(
+            result_table,
+            rows(&["4,-0.25", "1,9.5", "2,10", "3,3000000000"])
+        );
+    }
+
+    #[test]
+    fn test_order_rows_by_date() {
+        let result_table = rows(&["1,2024-05-01", "2,2023-12-31", "3,2024-01-15"]);
+
+        let mut order_map = HashMap::new();
+        order_map.insert(1, "desc".to_string());
+        let result_table = order_rows(result_table, order_map, vec![1], None);
+
+        assert_eq!(
+            result_table,
+            rows(&["1,2024-05-01", "3,2024-01-15", "2,2023-12-31"])
+        );
+    }
+
+    #[test]
+    fn test_order_rows_puts_nulls_last() {
+        let result_table = rows(&["1,Agus,", "2,Bob,25", "3,Gon,10"]);
+
+        let mut order_map = HashMap::new();
+        order_map.insert(2, "asc".to_string());
+        let result_table = order_rows(result_table, order_map, vec![2], None);
+
+        assert_eq!(result_table, rows(&["3,Gon,10", "2,Bob,25", "1,Agus,"]));
+    }
+
+    #[test]
+    fn test_filter_row_with_nulls() {
+        let headers = vec!["id", "nombre", "edad"];
+        let row = rows(&["1,Agus,"]).remove(0);
+        let filter = |condition: &str| {
+            let condition = parse_expression(&tokenize(condition).unwrap()).unwrap();
+            filter_row(&row, Some(&condition), &headers).unwrap()
+        };
+
+        assert!(!filter("edad = 30"));
+        assert!(!filter("not edad = 30"));
+        assert!(!filter("edad = null"));
+        assert!(filter("edad is null"));
+        assert!(filter("edad is null and nombre is not null"));
+    }
+
+    #[test]
+    fn test_build_result_set_projects_typed_columns() {
+        let headers = vec!["id", "name", "age"];
+        let query = SelectQuery {
+            table_name: "people".to_string(),
+            alias: None,
+            joins: Vec::new(),
+            distinct: false,
+            columns: vec![
+                SelectItem::Column("name".to_string()),
+                SelectItem::Column("age".to_string()),
+            ],
+            condition: None,
+            group_by: Vec::new(),
+            having: None,
+            order_by: Vec::new(),
+            limit: None,
+            offset: 0,
+        };
+
+        let result = build_result_set(rows(&["1,Agus,30"]), &query, &headers, &[0, 1, 2]).unwrap();
+
+        assert_eq!(result.columns, vec!["name", "age"]);
+        assert_eq!(
+            result.rows,
+            vec![vec![Value::Str("Agus".to_string()), Value::Int(30)]]
+        );
+    }
+
+    #[test]
+    fn test_build_result_set_unknown_column() {
+        let headers = vec!["id", "name"];
+        let query = SelectQuery {
+            table_name: "people".to_string(),
+            alias: None,
+            joins: Vec::new(),
+            distinct: false,
+            columns: vec![SelectItem::Column("email".to_string())],
+            condition: None,
+            group_by: Vec::new(),
+            having: None,
+            order_by: Vec::new(),
+            limit: None,
+            offset: 0,
+        };
+
+        let result = build_result_set(rows(&["1,Agus"]), &query, &headers, &[0, 1]);
+
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidColumn);
+        assert_eq!(error.context(), Some("email"));
+    }
+
+    #[test]
+    fn test_select_parser_aggregates() {
+        let tokens = tokenize("select count(*), SUM(cantidad), max(fecha) from ordenes").unwrap();
+        let columns = extract_columns(&tokens, 1).unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(SelectItem::to_string)
+                .collect::<Vec<String>>(),
+            vec!["COUNT(*)", "SUM(cantidad)", "MAX(fecha)"]
+        );
+
+        let invalid = [
+            "select sum(*) from ordenes",
+            "select mediana(cantidad) from ordenes",
+            "select id, from ordenes",
+        ];
+        for query in invalid {
+            let error = extract_columns(&tokenize(query).unwrap(), 1).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_group_by_and_having() {
+        let tokens = tokenize(
+            "select id_cliente, count(*) from ordenes where cantidad > 0 \
+             group by id_cliente, producto having sum(cantidad) >= 10 order by count(*) desc",
+        )
+        .unwrap();
+
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        assert_eq!(query.group_by, vec!["id_cliente", "producto"]);
+        assert_eq!(
+            query.condition,
+            Some(compare("cantidad", ">", Value::Int(0)))
+        );
+        assert_eq!(
+            query
+                .having
+                .as_ref()
+                .map(|having| having.aggregates().len()),
+            Some(1)
+        );
+        assert_eq!(order_labels(&query), vec![("COUNT(*)".to_string(), true)]);
+
+        let invalid = [
+            "select id_cliente from ordenes group id_cliente",
+            "select id_cliente from ordenes group by id_cliente,",
+            "select id_cliente from ordenes where count(*) > 1 group by id_cliente",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_order_rows_with_limit_keeps_top_rows() {
+        let result_table = rows(&["1,Agus,30", "2,Bob,25", "3,Gon,35", "4,Daniel,25", "5,Eve,"]);
+
+        let mut order_map = HashMap::new();
+        order_map.insert(2, "asc".to_string());
+        let result_table = order_rows(result_table, order_map.clone(), vec![2], Some(3));
+        assert_eq!(
+            result_table,
+            rows(&["2,Bob,25", "4,Daniel,25", "1,Agus,30"])
+        );
+
+        assert!(order_rows(result_table, order_map, vec![2], Some(0)).is_empty());
+    }
+
+    #[test]
+    fn test_select_parser_limit_and_offset() {
+        let tokens =
+            tokenize("select * from ordenes where id > 1 order by id desc limit 10 offset 5")
+                .unwrap();
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        assert_eq!(query.condition, Some(compare("id", ">", Value::Int(1))));
+        assert_eq!(order_labels(&query), vec![("id".to_string(), true)]);
+        assert_eq!((query.limit, query.offset), (Some(10), 5));
+
+        let invalid = [
+            "select * from ordenes limit",
+            "select * from ordenes limit -1",
+            "select * from ordenes limit 2.5",
+            "select * from ordenes offset 1 2",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_distinct() {
+        let tokens = tokenize(
+            "select distinct producto, count(distinct id_cliente) from ordenes group by producto",
+        )
+        .unwrap();
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        assert!(query.distinct);
+        assert_eq!(query.columns[1].to_string(), "COUNT(DISTINCT id_cliente)");
+
+        let invalid = [
+            "select distinct from ordenes",
+            "select distinct producto from ordenes order by id",
+            "select count(distinct *) from ordenes",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_joins() {
+        let tokens = tokenize(
+            "select o.id, c.nombre from ordenes as o inner join clientes c on o.id_cliente = c.id \
+             left outer join productos on productos.nombre = o.producto where c.id > 1 order by c.nombre",
+        )
+        .unwrap();
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        assert_eq!(query.table_name, "ordenes");
+        assert_eq!(query.name(), "o");
+        assert_eq!(
+            query.columns,
+            vec![
+                SelectItem::Column("o.id".to_string()),
+                SelectItem::Column("c.nombre".to_string())
+            ]
+        );
+        assert_eq!(query.joins.len(), 2);
+        assert_eq!(query.joins[0].kind, JoinKind::Inner);
+        assert_eq!(query.joins[0].name(), "c");
+        assert_eq!(
+            query.joins[0].condition,
+            Some(JoinCondition::On(Expr::Compare {
+                left: Box::new(Expr::Column("o.id_cliente".to_string())),
+                operator: "=".to_string(),
+                right: Box::new(Expr::Column("c.id".to_string())),
+            }))
+        );
+        assert_eq!(query.joins[1].kind, JoinKind::Left);
+        assert_eq!(query.joins[1].name(), "productos");
+        assert_eq!(query.condition, Some(compare("c.id", ">", Value::Int(1))));
+        assert_eq!(order_labels(&query), vec![("c.nombre".to_string(), false)]);
+
+        let invalid = [
+            "select * from ordenes o join clientes c",
+            "select * from ordenes join on id = id",
+            "select * from ordenes as join clientes on id = id",
+            "select * from ordenes join ordenes on id = id",
+            "select * from ordenes o clientes c",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_outer_cross_and_natural_joins() {
+        let tokens = tokenize(
+            "select * from a full outer join b using (id, fecha) cross join c \
+             natural left join d right join e on e.id = a.id",
+        )
+        .unwrap();
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        let joins: Vec<(JoinKind, &str)> = query
+            .joins
+            .iter()
+            .map(|join| (join.kind, join.name()))
+            .collect();
+        assert_eq!(
+            joins,
             vec![
-                "3,Agus,35".to_string(),
-                "1,Agus,30".to_string(),
-                "2,Bob,25".to_string(),
-                "4,Daniel,25".to_string(),
+                (JoinKind::Full, "b"),
+                (JoinKind::Cross, "c"),
+                (JoinKind::Left, "d"),
+                (JoinKind::Right, "e")
             ]
         );
+        assert_eq!(
+            query.joins[0].condition,
+            Some(JoinCondition::Using(vec![
+                "id".to_string(),
+                "fecha".to_string()
+            ]))
+        );
+        assert_eq!(query.joins[1].condition, None);
+        assert_eq!(query.joins[2].condition, Some(JoinCondition::Natural));
+
+        let invalid = [
+            "select * from a cross join b on a.id = b.id",
+            "select * from a natural join b using (id)",
+            "select * from a natural cross join b",
+            "select * from a full join b",
+            "select * from a join b using id",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_eq!(error.kind(), ErrorType::InvalidSyntax, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_expressions_and_aliases() {
+        let tokens = tokenize(
+            "select producto, cantidad * 2 as doble, nombre || ' ' || apellido completo, 'fijo', \
+             date_trunc('month', fecha), count(*) total from ordenes group by producto, cantidad, nombre, apellido, fecha",
+        )
+        .unwrap();
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        let labels: Vec<String> = query.columns.iter().map(SelectItem::to_string).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "producto",
+                "doble",
+                "completo",
+                "'fijo'",
+                "DATE_TRUNC('month', fecha)",
+                "total"
+            ]
+        );
+        assert_eq!(query.columns[0], SelectItem::Column("producto".to_string()));
+        assert!(matches!(
+            &query.columns[1],
+            SelectItem::Expression { alias: Some(alias), .. } if alias == "doble"
+        ));
+        assert_eq!(query_aggregates(&query).len(), 1);
+
+        let invalid = [
+            "select * as todo from ordenes",
+            "select cantidad as from ordenes",
+            "select cantidad * from ordenes",
+        ];
+        for query in invalid {
+            let error = SelectParser.parse(tokenize(query).unwrap()).unwrap_err();
+            assert_ne!(error.kind(), ErrorType::Error, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_select_parser_from_inside_function() {
+        let tokens = tokenize(
+            "select id, extract(year from fecha) as anio from ordenes where extract(month from fecha) = 5",
+        )
+        .unwrap();
+        let Ok(Query::Select(query)) = SelectParser.parse(tokens) else {
+            panic!("se esperaba una consulta SELECT");
+        };
+        assert_eq!(query.table_name, "ordenes");
+        let labels: Vec<String> = query.columns.iter().map(SelectItem::to_string).collect();
+        assert_eq!(labels, vec!["id", "anio"]);
+
+        let tokens = tokenize("select extract(year from fecha)").unwrap();
+        assert!(SelectParser.validate_syntax(&tokens).is_err());
     }
 }
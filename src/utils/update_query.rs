@@ -1,33 +1,51 @@
 use std::{
-    fs::{self, File},
-    io::{self, BufRead, BufReader, BufWriter, Write},
+    fs::File,
+    io::{BufReader, Write},
 };
 
-use crate::utils::{
-    insert_query::{generate_row_to_insert, write_csv},
-    select_query::filter_row,
-};
+use crate::utils::select_query::filter_row;
 
 use crate::query::{CommandParser, Query};
 use crate::{
-    error::{self, print_error, ErrorType},
-    extras::{cleaned_values, get_column_index, get_condition_columns},
+    csv::{write_record, CsvReader},
+    error::{ErrorType, SqlError},
+    expression::{parse_expression, Expr},
+    extras::{
+        get_condition, get_table_path, open_table, read_error, replace_table, syntax_error_at,
+        token_field, write_error, Value,
+    },
+    lexer::{Token, TokenKind},
+    schema::{parse_row, validate_values, TableSchema},
 };
 
+#[derive(Debug, PartialEq)]
+
+/// Representa el valor asignado a una columna en el `SET` de un `UPDATE`.
+///
+/// # Variantes
+/// * `Field` - Un valor literal, que se escribe tal cual en la tabla (`None` para `NULL`).
+/// * `Expression` - Una expresión, como `UPPER(nombre)` o `cantidad + 1`, que se calcula con los valores que
+///   tiene cada fila antes de actualizarse.
+///
+pub enum SetValue {
+    Field(Option<String>),
+    Expression(Expr),
+}
+
 #[derive(Debug)]
 
 /// Representa una consulta `UPDATE`, con los parámetros:
 ///
 /// * `table_name` - El nombre de la tabla a actualizar.
 /// * `columns` - Las columnas que se actualizarán.
-/// * `values` - Los nuevos valores para las columnas.
-/// * `condition` - La condición para seleccionar las filas a actualizar.
+/// * `values` - Los nuevos valores para las columnas (ver `SetValue`).
+/// * `condition` - La condición para seleccionar las filas a actualizar, si la consulta tiene `WHERE`.
 ///
 pub struct UpdateQuery {
     pub table_name: String,
     pub columns: Vec<String>,
-    pub values: Vec<String>,
-    pub condition: Vec<String>,
+    pub values: Vec<SetValue>,
+    pub condition: Option<Expr>,
 }
 
 pub struct UpdateParser;
@@ -35,43 +53,34 @@ impl CommandParser for UpdateParser {
     /// Valida la sintaxis de la consulta `UPDATE`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una referencia a un `Vec<String>` con los componentes de la consulta ya parseados.
+    /// * `tokens` - Una referencia a los tokens de la consulta.
     ///
     /// # Retorno
-    /// Devuelve `Ok(())` si la sintaxis es válida, o `Err(ErrorType)` si es inválida.
+    /// Devuelve `Ok(())` si la sintaxis es válida, o un `SqlError` si es inválida.
     ///
-    fn validate_syntax(&self, parsed_query: &[String]) -> Result<(), ErrorType> {
-        if parsed_query.len() < 4
-            || parsed_query[0] != "update"
-            || !parsed_query.contains(&"set".to_string())
+    fn validate_syntax(&self, tokens: &[Token]) -> Result<(), SqlError> {
+        if tokens.len() < 4
+            || !tokens[0].is_keyword("update")
+            || !tokens.iter().any(|token| token.is_keyword("set"))
         {
-            error::print_error(
+            return Err(SqlError::new(
                 ErrorType::InvalidSyntax,
                 "Sintaxis inválida: falta 'UPDATE' o 'SET'",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
         }
 
-        let set_index = parsed_query
+        let set_index = tokens
             .iter()
-            .position(|x| x == "set")
-            .ok_or(ErrorType::InvalidSyntax)?;
-        let mut set_found = false;
-
-        for i in (set_index + 1)..parsed_query.len() {
-            if parsed_query[i] == "=" && i + 1 < parsed_query.len() {
-                set_found = true;
-            } else if parsed_query[i] == "where" {
-                break;
-            }
-        }
-
-        if !set_found {
-            error::print_error(
-                ErrorType::InvalidSyntax,
+            .position(|token| token.is_keyword("set"))
+            .unwrap_or(0);
+        let (columns, _) = extract_columns_and_values(tokens, set_index + 1)?;
+
+        if columns.is_empty() {
+            return Err(syntax_error_at(
+                tokens,
+                set_index + 1,
                 "Sintaxis inválida: no se encontraron asignaciones 'columna=valor'",
-            );
-            return Err(ErrorType::InvalidSyntax);
+            ));
         }
 
         Ok(())
@@ -80,18 +89,31 @@ impl CommandParser for UpdateParser {
     /// Parsea la consulta `UPDATE` en un objeto `Query`.
     ///
     /// # Argumentos
-    /// * `parsed_query` - Una `Vec<String>` que contiene los componentes de la consulta.
+    /// * `tokens` - Los tokens de la consulta.
     ///
     /// # Retorno
-    /// Devuelve un `Query::Update` que contiene los detalles de la consulta, o un `Err(ErrorType)` en caso de error.
+    /// Devuelve un `Query::Update` que contiene los detalles de la consulta, o un `SqlError` en caso de error.
     ///
-    fn parse(&self, parsed_query: Vec<String>) -> Result<Query, ErrorType> {
-        let table_name = extract_table_name(&parsed_query)?;
-        let set_index = parsed_query.iter().position(|x| x == "set").unwrap_or(0);
+    fn parse(&self, tokens: Vec<Token>) -> Result<Query, SqlError> {
+        let table_name = extract_table_name(&tokens)?;
+        let set_index = tokens
+            .iter()
+            .position(|token| token.is_keyword("update"))
+            .map_or(tokens.len(), |index| index + 2);
+        if !tokens
+            .get(set_index)
+            .is_some_and(|token| token.is_keyword("set"))
+        {
+            return Err(syntax_error_at(
+                &tokens,
+                set_index,
+                "Sintaxis inválida: se esperaba 'SET' luego del nombre de la tabla",
+            ));
+        }
 
-        let (columns, values) = extract_columns_and_values(&parsed_query, set_index + 1);
+        let (columns, values) = extract_columns_and_values(&tokens, set_index + 1)?;
 
-        let condition = cleaned_values(get_condition_columns(&parsed_query));
+        let condition = get_condition(&tokens, &[])?;
 
         Ok(Query::Update(UpdateQuery {
             table_name,
@@ -105,224 +127,246 @@ impl CommandParser for UpdateParser {
 /// Extrae el nombre de la tabla de la consulta de actualización.
 ///
 /// # Argumentos
-/// * `parsed_query` - Un vector de `String` que representa la consulta descompuesta en tokens.
+/// * `tokens` - Los tokens de la consulta.
 ///
 /// # Retorno
-/// Devuelve el nombre de la tabla como un `String` si se encuentra, o un `ErrorType::InvalidSyntax` si no se encuentra.
+/// Devuelve el nombre de la tabla como un `String` si se encuentra, o un `SqlError` de tipo `ErrorType::InvalidSyntax` si no se encuentra.
 ///
-fn extract_table_name(parsed_query: &[String]) -> Result<String, ErrorType> {
-    parsed_query
+fn extract_table_name(tokens: &[Token]) -> Result<String, SqlError> {
+    let index = tokens
         .iter()
-        .position(|x| x == "update")
-        .map(|index| parsed_query[index + 1].to_string())
+        .position(|token| token.is_keyword("update"))
+        .map_or(tokens.len(), |index| index + 1);
+    tokens
+        .get(index)
+        .filter(|token| token.kind == TokenKind::Identifier)
+        .map(|token| token.value.to_string())
         .ok_or_else(|| {
-            error::print_error(
-                ErrorType::InvalidSyntax,
-                "Sintaxis inválida, falta 'update'",
-            );
-            ErrorType::InvalidSyntax
+            syntax_error_at(
+                tokens,
+                index,
+                "Sintaxis inválida, falta el nombre de la tabla luego de 'update'",
+            )
         })
 }
 
-/// Extrae las columnas y los valores de la consulta de actualización.
+/// Extrae las columnas y los valores de las asignaciones `columna = valor` de la consulta de actualización.
 ///
 /// # Argumentos
-/// * `parsed_query` - Un vector de `String` que representa la consulta descompuesta en tokens.
-/// * `start_index` - El índice de inicio para la extracción de columnas y valores.
+/// * `tokens` - Los tokens de la consulta.
+/// * `start_index` - El índice de la primera asignación (el token siguiente a `SET`).
 ///
 /// # Retorno
-/// Devuelve una tupla con dos vectores de `String`, el primero contiene las columnas y el segundo contiene los valores.
+/// Devuelve una tupla con dos vectores, el primero contiene las columnas y el segundo contiene los valores (ver `SetValue`),
+/// o un `SqlError` de tipo `ErrorType::InvalidSyntax` si alguna asignación está mal formada.
+///
+/// # Notas
+/// Un literal de un solo token (`'Juan'`, `3`, `TRUE`, `NULL`) se escribe tal cual; cualquier otro valor (`nombre`,
+/// `UPPER(nombre)`, `cantidad + 1`) se parsea como una expresión, que termina en la siguiente coma o en `WHERE` fuera de paréntesis.
 ///
 fn extract_columns_and_values(
-    parsed_query: &[String],
+    tokens: &[Token],
     start_index: usize,
-) -> (Vec<String>, Vec<String>) {
+) -> Result<(Vec<String>, Vec<SetValue>), SqlError> {
     let mut columns = Vec::new();
     let mut values = Vec::new();
 
     let mut i = start_index;
-    while i < parsed_query.len() {
-        if parsed_query[i] == "=" && i + 1 < parsed_query.len() {
-            columns.push(parsed_query[i - 1].to_string());
-            values.push(parsed_query[i + 1].to_string());
-            i += 2;
-        } else if parsed_query[i] == "where" {
-            break;
-        } else {
+    while i < tokens.len() && !tokens[i].is_keyword("where") {
+        let end = assignment_end(tokens, i + 2);
+        match (tokens.get(i), tokens.get(i + 1)) {
+            (Some(column), Some(equal))
+                if column.kind == TokenKind::Identifier
+                    && equal.is_operator("=")
+                    && end > i + 2 =>
+            {
+                columns.push(column.value.to_string());
+                values.push(set_value(&tokens[i + 2..end])?);
+            }
+            _ => {
+                return Err(syntax_error_at(
+                    tokens,
+                    i,
+                    "Sintaxis inválida: se esperaba una asignación 'columna = valor'",
+                ));
+            }
+        }
+        i = end;
+        if tokens.get(i).map(|token| &token.kind) == Some(&TokenKind::Comma) {
             i += 1;
         }
     }
 
-    (cleaned_values(columns), cleaned_values(values))
+    Ok((columns, values))
+}
+
+/// Devuelve el índice donde termina el valor de una asignación que empieza en `start`: la primera coma o
+/// `WHERE` fuera de paréntesis, o el final de la consulta.
+fn assignment_end(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token.kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen => depth = depth.saturating_sub(1),
+            TokenKind::Comma if depth == 0 => return index,
+            _ if depth == 0 && token.is_keyword("where") => return index,
+            _ => {}
+        }
+    }
+    tokens.len().max(start)
+}
+
+/// Convierte los tokens del valor de una asignación en un `SetValue`. Un identificador solo es una
+/// referencia a otra columna (`SET apellido = nombre`), por lo que también se parsea como expresión.
+fn set_value(tokens: &[Token]) -> Result<SetValue, SqlError> {
+    if let [token] = tokens {
+        if token.kind != TokenKind::Identifier {
+            if let Some(field) = token_field(token) {
+                return Ok(SetValue::Field(field));
+            }
+        }
+    }
+    parse_expression(tokens).map(SetValue::Expression)
 }
 /// Actualiza las filas del archivo según la consulta.
 ///
 /// # Argumentos
-/// * `path` - La ruta del archivo CSV.
+/// * `path` - La ruta del directorio de la base de datos, donde cada tabla es un archivo `<tabla>.csv`.
 /// * `query` - La consulta de actualización.
 ///
 /// # Retorno
-/// Devuelve `Ok(())` si la actualización es exitosa, o un `ErrorType` si ocurre un error durante la actualización.
+/// Devuelve la cantidad de filas actualizadas si la actualización es exitosa, o un `SqlError` si ocurre un error durante la actualización.
+/// Si alguna de las columnas de `SET` no existe en la tabla, devuelve un error de tipo `ErrorType::InvalidColumn`, y si la tabla
+/// tiene esquema y algún valor no corresponde al tipo de su columna, un error de tipo `ErrorType::Error`. En ambos casos el archivo no se modifica.
 ///
-pub fn update(path: &str, query: UpdateQuery) -> Result<(), ErrorType> {
-    let file = File::open(path).map_err(|_| {
-        print_error(ErrorType::InvalidTable, "No se pudo abrir el archivo");
-        ErrorType::InvalidTable
-    })?;
-
-    let mut reader = io::BufReader::new(file);
-    let mut header = String::new();
-    reader.read_line(&mut header).map_err(|_| {
-        print_error(ErrorType::InvalidTable, "No se pudo leer el archivo");
-        ErrorType::InvalidTable
-    })?;
-
-    let headers: Vec<&str> = header.trim().split(',').collect();
-
-    if query.condition.is_empty() {
-        let row_to_insert = generate_row_to_insert(
-            &headers
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>(),
-            &query.columns,
-            &query.values,
-        );
-        return {
-            write_csv(path, Some(row_to_insert));
-            Ok(())
-        };
-    }
+/// # Notas
+/// Sin `WHERE`, se actualizan todas las filas de la tabla. Los valores calculados con expresiones se verifican fila por
+/// fila; si alguno falla, la tabla tampoco se modifica.
+///
+pub fn update(path: &str, query: UpdateQuery) -> Result<usize, SqlError> {
+    let path = &get_table_path(path, &query.table_name)?;
+    let (header, reader) = open_table(path)?;
+    let schema = TableSchema::load(path, &header)?;
+    let headers: Vec<&str> = header.iter().map(|s| s.as_str()).collect();
+
+    let fields: Vec<Option<String>> = query
+        .values
+        .iter()
+        .map(|value| match value {
+            SetValue::Field(field) => field.clone(),
+            SetValue::Expression(_) => None,
+        })
+        .collect();
+    validate_values(schema.as_ref(), &header, &query.columns, &fields)?;
 
-    update_rows(path, reader, &headers, &query)?;
-    Ok(())
+    replace_table(path, |writer| {
+        write_record(writer, &header).map_err(|e| write_error(path, e))?;
+        update_rows(path, reader, writer, schema.as_ref(), &headers, &query)
+    })
 }
 
-/// Actualiza las filas en el archivo basándose en la condición de la consulta.
+/// Actualiza las filas de la tabla basándose en la condición de la consulta.
 ///
 /// # Argumentos
 /// * `path` - La ruta del archivo CSV.
-/// * `reader` - Un `BufReader` para leer el archivo.
+/// * `reader` - Un `CsvReader` posicionado en la primera fila de datos.
+/// * `writer` - El archivo temporal donde se escribe la tabla actualizada.
+/// * `schema` - El esquema de la tabla, o `None` si la tabla no tiene esquema.
 /// * `headers` - Los encabezados de las columnas.
 /// * `query` - La consulta de actualización.
 ///
 /// # Retorno
-/// Devuelve `Ok(())` si la actualización es exitosa, o un `ErrorType` si ocurre un error durante la actualización.
+/// Devuelve la cantidad de filas actualizadas, o un `SqlError` si ocurre un error durante la actualización.
 ///
 /// # Notas
-/// Se toma linea a linea y se filtra según la condición indicada en la consulta. Si la fila cumple con la condición, se actualiza.
+/// Se toma registro a registro y se filtra según la condición indicada en la consulta. Si la fila cumple con la condición,
+/// se escribe actualizada; si no, se copia sin cambios. El archivo se recorre una sola vez.
 ///
-fn update_rows(
+fn update_rows<W: Write>(
     path: &str,
-    reader: io::BufReader<File>,
+    reader: CsvReader<BufReader<File>>,
+    writer: &mut W,
+    schema: Option<&TableSchema>,
     headers: &[&str],
     query: &UpdateQuery,
-) -> Result<(), ErrorType> {
-    for (i, line) in reader.lines().enumerate() {
-        let line = line.map_err(|_| {
-            print_error(ErrorType::InvalidTable, "No se pudo leer el archivo");
-            ErrorType::InvalidTable
-        })?;
-
-        let values: Vec<String> = line.split(',').map(|s| s.to_string()).collect();
-
-        if filter_row(&values, &query.condition, headers) {
-            let updated_line = create_updated_line(headers, &query.columns, &query.values, &values);
-            let _ = update_line(path, i + 1, Some(&updated_line));
+) -> Result<usize, SqlError> {
+    let mut updated_rows = 0;
+    for record in reader {
+        let record = record.map_err(|e| read_error(path, e))?;
+        let values = parse_row(schema, &record)?;
+
+        if filter_row(&values, query.condition.as_ref(), headers)? {
+            let new_values = assigned_values(schema, headers, query, &values)?;
+            let updated_line = create_updated_line(headers, &query.columns, &new_values, &record);
+            write_record(writer, &updated_line).map_err(|e| write_error(path, e))?;
+            updated_rows += 1;
+        } else {
+            write_record(writer, &record).map_err(|e| write_error(path, e))?;
         }
     }
-    Ok(())
+    Ok(updated_rows)
 }
 
-/// Crea una línea actualizada con los nuevos valores.
+/// Calcula los valores a escribir en una fila: los literales tal cual y las expresiones evaluadas con los valores
+/// actuales de la fila.
 ///
 /// # Argumentos
+/// * `schema` - El esquema de la tabla, o `None` si la tabla no tiene esquema.
 /// * `headers` - Los encabezados de las columnas.
-/// * `columns` - Las columnas que se actualizarán.
-/// * `values_to_update` - Los nuevos valores para las columnas.
-/// * `values` - Los valores actuales en la fila.
+/// * `query` - La consulta de actualización.
+/// * `row` - Los valores actuales de la fila.
 ///
 /// # Retorno
-/// Devuelve un vector de `String` que representa la línea actualizada.
+/// Devuelve un valor por cada columna de `SET` (`None` para `NULL`), o un `SqlError` si alguna expresión no puede
+/// evaluarse o su resultado no corresponde al tipo de la columna.
 ///
-pub fn create_updated_line(
+fn assigned_values(
+    schema: Option<&TableSchema>,
     headers: &[&str],
-    columns: &Vec<String>,
-    values_to_update: &[String],
-    values: &[String],
-) -> Vec<String> {
-    let mut row_to_insert: Vec<String> = vec![String::new(); headers.len()];
-
-    for i in headers {
-        let n_column = get_column_index(
-            &headers
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>(),
-            i,
-        ) as usize;
-        row_to_insert[n_column as usize].push_str(&values[n_column as usize]);
-
-        for j in columns {
-            if j == i {
-                let n_column = get_column_index(
-                    &headers
-                        .iter()
-                        .map(|s| s.to_string())
-                        .collect::<Vec<String>>(),
-                    j,
-                ) as usize;
-                let n_value = get_column_index(columns, i) as usize;
-                row_to_insert[n_column] = values_to_update[n_value].to_string();
+    query: &UpdateQuery,
+    row: &[Value],
+) -> Result<Vec<Option<String>>, SqlError> {
+    query
+        .columns
+        .iter()
+        .zip(&query.values)
+        .map(|(column, value)| match value {
+            SetValue::Field(field) => Ok(field.clone()),
+            SetValue::Expression(expr) => {
+                let field = expr.evaluate(row, headers)?.to_field();
+                if let (Some(schema), Some(raw)) = (schema, &field) {
+                    schema.parse_value(column, raw)?;
+                }
+                Ok(field)
             }
-        }
-    }
-    row_to_insert
+        })
+        .collect()
 }
 
-/// Actualiza una línea específica en el archivo CSV.
+/// Crea una línea actualizada con los nuevos valores.
 ///
 /// # Argumentos
-/// * `file_path` - La ruta del archivo CSV.
-/// * `line_index` - El índice de la línea a actualizar.
-/// * `row` - Una opción que contiene la nueva línea, o `None` para mantener la línea original.
+/// * `headers` - Los encabezados de las columnas.
+/// * `columns` - Las columnas que se actualizarán.
+/// * `values_to_update` - Los nuevos valores para las columnas (`None` para `NULL`).
+/// * `values` - Los campos actuales de la fila.
 ///
 /// # Retorno
-/// Devuelve un `io::Result<()>` que indica el éxito o el fallo de la operación.
+/// Devuelve un vector con los campos de la línea actualizada.
 ///
-pub fn update_line(
-    file_path: &str,
-    line_index: usize,
-    row: Option<&Vec<String>>,
-) -> io::Result<()> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
-    let lines = reader.lines();
-
-    let temp_file_path = format!("{}.csv", file_path);
-    let temp_file = File::create(&temp_file_path)?;
-    let mut writer = BufWriter::new(temp_file);
-    let mut updated_line = String::new();
-
-    if let Some(row) = row {
-        for (i, value) in row.iter().enumerate() {
-            if i > 0 {
-                updated_line.push(',');
-            }
-            updated_line.push_str(value);
-        }
-    }
-    for (index, line) in lines.enumerate() {
-        if index != line_index {
-            writeln!(writer, "{}", line?)?;
-        } else {
-            writeln!(writer, "{}", updated_line)?;
+pub fn create_updated_line(
+    headers: &[&str],
+    columns: &[String],
+    values_to_update: &[Option<String>],
+    values: &[Option<String>],
+) -> Vec<Option<String>> {
+    let mut row_to_insert = values.to_vec();
+    for (column, value) in columns.iter().zip(values_to_update) {
+        if let Some(index) = headers.iter().position(|header| header == column) {
+            row_to_insert[index] = value.clone();
         }
     }
-    writer.flush()?;
-    drop(writer);
-    fs::rename(temp_file_path, file_path)?;
-    Ok(())
+    row_to_insert
 }
 
 // Testing -----
@@ -330,31 +374,32 @@ pub fn update_line(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::extras::Value;
+    use crate::lexer::tokenize;
+    use std::fs;
 
     #[test]
     fn test_update_parser() {
         let parser = UpdateParser;
-        let input = vec![
-            "update".to_string(),
-            "my_table".to_string(),
-            "column1".to_string(),
-            "=".to_string(),
-            "value1".to_string(),
-            "where".to_string(),
-            "column2".to_string(),
-            "=".to_string(),
-            "value2".to_string(),
-        ];
+        let input =
+            tokenize("update my_table set column1 = 'value1' where column2 = 'value2'").unwrap();
 
         let result = parser.parse(input);
         println!("{:?}", result);
         if let Ok(Query::Update(update_query)) = result {
             assert_eq!(update_query.table_name, "my_table");
             assert_eq!(update_query.columns, vec!["column1".to_string()]);
-            assert_eq!(update_query.values, vec!["value1".to_string()]);
+            assert_eq!(
+                update_query.values,
+                vec![SetValue::Field(Some("value1".to_string()))]
+            );
             assert_eq!(
                 update_query.condition,
-                vec!["column2".to_string(), "=".to_string(), "value2".to_string()]
+                Some(Expr::Compare {
+                    left: Box::new(Expr::Column("column2".to_string())),
+                    operator: "=".to_string(),
+                    right: Box::new(Expr::Literal(Value::Str("value2".to_string()))),
+                })
             );
         }
     }
@@ -363,49 +408,220 @@ mod test {
     fn test_create_updated_line() {
         let headers = vec!["column1", "column2", "column3"];
         let columns = vec!["column2".to_string()];
-        let values_to_update = vec!["new_value2".to_string()];
-        let values = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-        ];
+        let values_to_update = vec![Some("new_value2".to_string())];
+        let values = vec![Some("value1".to_string()), Some("value2".to_string()), None];
 
         let updated_line = create_updated_line(&headers, &columns, &values_to_update, &values);
         assert_eq!(
             updated_line,
             vec![
-                "value1".to_string(),
-                "new_value2".to_string(),
-                "value3".to_string()
+                Some("value1".to_string()),
+                Some("new_value2".to_string()),
+                None
             ]
         );
     }
 
+    fn update_query(table_name: &str, condition: Option<Expr>) -> UpdateQuery {
+        UpdateQuery {
+            table_name: table_name.to_string(),
+            columns: vec!["cantidad".to_string()],
+            values: vec![SetValue::Field(Some("0".to_string()))],
+            condition,
+        }
+    }
+
     #[test]
-    fn test_update_line() -> Result<(), Box<dyn std::error::Error>> {
-        let test_file = "test_update_line.csv";
+    fn test_update_rows_in_one_pass() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_update_one_pass.csv";
+        fs::write(
+            test_file,
+            "id,producto,cantidad\n1,manzana,5\n2,pera,3\n3,uva,8\n",
+        )?;
 
-        let mut file = File::create(test_file)?;
-        writeln!(file, "id,id_cliente,producto,cantidad")?;
-        writeln!(file, "1,1,manzana,5")?;
-        writeln!(file, "2,8,pera,3")?;
+        let condition = Expr::Compare {
+            left: Box::new(Expr::Column("cantidad".to_string())),
+            operator: ">".to_string(),
+            right: Box::new(Expr::Literal(Value::Int(4))),
+        };
+        let result = update(".", update_query("test_update_one_pass", Some(condition)));
 
-        update_line(
+        assert_eq!(result.ok(), Some(2));
+        let contents = fs::read_to_string(test_file)?;
+        assert_eq!(
+            contents,
+            "id,producto,cantidad\n1,manzana,0\n2,pera,3\n3,uva,0\n"
+        );
+        assert!(!std::path::Path::new("test_update_one_pass.csv.tmp").exists());
+
+        fs::remove_file(test_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_parser_requires_set_after_table_name() {
+        let input = tokenize("UPDATE ordenes junk SET cantidad = 5").unwrap();
+        assert_eq!(
+            UpdateParser
+                .parse(input)
+                .map(|_| ())
+                .map_err(|error| error.kind()),
+            Err(ErrorType::InvalidSyntax)
+        );
+    }
+
+    #[test]
+    fn test_update_parser_rejects_trailing_clauses() {
+        for query in [
+            "UPDATE ordenes SET cantidad = 5 WHERE id = 101 ORDER BY id",
+            "UPDATE ordenes SET cantidad = 5 WHERE id = 101 LIMIT 1",
+            "UPDATE ordenes SET cantidad = 5 LIMIT 1",
+        ] {
+            assert_eq!(
+                UpdateParser
+                    .parse(tokenize(query).unwrap())
+                    .map(|_| ())
+                    .map_err(|error| error.kind()),
+                Err(ErrorType::InvalidSyntax),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_without_where_updates_every_row() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_update_every_row.csv";
+        fs::write(test_file, "id,producto,cantidad\n1,manzana,5\n2,pera,3\n")?;
+
+        let result = update(".", update_query("test_update_every_row", None));
+
+        assert_eq!(result.ok(), Some(2));
+        let contents = fs::read_to_string(test_file)?;
+        assert_eq!(contents, "id,producto,cantidad\n1,manzana,0\n2,pera,0\n");
+
+        fs::remove_file(test_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_set_null_and_where_is_null() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_update_null.csv";
+        fs::write(test_file, "id,producto,cantidad\n1,manzana,\n2,pera,3\n")?;
+
+        let input = tokenize("update test_update_null set cantidad = 0 where cantidad is null")?;
+        let Query::Update(query) = UpdateParser.parse(input)? else {
+            panic!("Se esperaba una consulta UPDATE");
+        };
+        assert_eq!(update(".", query).ok(), Some(1));
+
+        let input = tokenize("update test_update_null set producto = NULL where cantidad = 3")?;
+        let Query::Update(query) = UpdateParser.parse(input)? else {
+            panic!("Se esperaba una consulta UPDATE");
+        };
+        assert_eq!(update(".", query).ok(), Some(1));
+        assert_eq!(
+            fs::read_to_string(test_file)?,
+            "id,producto,cantidad\n1,manzana,0\n2,,3\n"
+        );
+
+        fs::remove_file(test_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_unknown_column() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_update_unknown_column.csv";
+        fs::write(test_file, "id,producto\n1,manzana\n")?;
+
+        let result = update(".", update_query("test_update_unknown_column", None));
+
+        assert_eq!(
+            result.map_err(|error| error.kind()),
+            Err(ErrorType::InvalidColumn)
+        );
+        assert_eq!(fs::read_to_string(test_file)?, "id,producto\n1,manzana\n");
+
+        fs::remove_file(test_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_set_expressions() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_update_expressions.csv";
+        fs::write(
             test_file,
-            2,
-            Some(&vec![
-                "2".to_string(),
-                "8".to_string(),
-                "pera".to_string(),
-                "10".to_string(),
-            ]),
+            "id,producto,cantidad\n1, manzana ,5\n2,pera,\n3,uva,8\n",
         )?;
 
-        let contents = fs::read_to_string(test_file)?;
-        assert!(contents.contains("2,8,pera,10"));
+        let input = tokenize(
+            "update test_update_expressions set producto = upper(trim(producto)), \
+             cantidad = coalesce(cantidad, 0) + 1 where id < 3",
+        )?;
+        let Query::Update(query) = UpdateParser.parse(input)? else {
+            panic!("Se esperaba una consulta UPDATE");
+        };
+        assert!(matches!(query.values[0], SetValue::Expression(_)));
+        assert_eq!(update(".", query).ok(), Some(2));
+        assert_eq!(
+            fs::read_to_string(test_file)?,
+            "id,producto,cantidad\n1,MANZANA,6\n2,PERA,1\n3,uva,8\n"
+        );
+
+        let input = tokenize("update test_update_expressions set cantidad = cantidad / 0")?;
+        let Query::Update(query) = UpdateParser.parse(input)? else {
+            panic!("Se esperaba una consulta UPDATE");
+        };
+        assert_eq!(
+            update(".", query).map_err(|error| error.kind()),
+            Err(ErrorType::Error)
+        );
+        assert_eq!(
+            fs::read_to_string(test_file)?,
+            "id,producto,cantidad\n1,MANZANA,6\n2,PERA,1\n3,uva,8\n"
+        );
+
+        let invalid = tokenize("update test_update_expressions set producto = 'a' cantidad = 2")?;
+        assert!(UpdateParser.parse(invalid).is_err());
 
         fs::remove_file(test_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_set_column_to_column() -> Result<(), Box<dyn std::error::Error>> {
+        let test_file = "test_update_column_to_column.csv";
+        fs::write(test_file, "id,nombre,apellido\n1,Juan,Paz\n2,Ana,Gil\n")?;
+
+        let input = tokenize(
+            "update test_update_column_to_column set apellido = nombre, nombre = TRUE where id = 1",
+        )?;
+        let Query::Update(query) = UpdateParser.parse(input)? else {
+            panic!("Se esperaba una consulta UPDATE");
+        };
+        assert_eq!(
+            query.values,
+            vec![
+                SetValue::Expression(Expr::Column("nombre".to_string())),
+                SetValue::Field(Some("true".to_string())),
+            ]
+        );
+        assert_eq!(update(".", query).ok(), Some(1));
+        assert_eq!(
+            fs::read_to_string(test_file)?,
+            "id,nombre,apellido\n1,true,Juan\n2,Ana,Gil\n"
+        );
 
+        let input = tokenize("update test_update_column_to_column set apellido = segundo")?;
+        let Query::Update(query) = UpdateParser.parse(input)? else {
+            panic!("Se esperaba una consulta UPDATE");
+        };
+        assert_eq!(
+            update(".", query).map_err(|error| error.kind()),
+            Err(ErrorType::InvalidColumn)
+        );
+
+        fs::remove_file(test_file)?;
         Ok(())
     }
 }
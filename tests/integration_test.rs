@@ -1,6 +1,10 @@
+use sql::error::ErrorType;
+use sql::expression::Expr;
+use sql::extras::Value;
+use sql::query::parse_query;
 use sql::utils::delete_query::{delete, DeleteQuery};
 use sql::utils::insert_query::{insert, InsertQuery};
-use sql::utils::update_query::update_line;
+use sql::utils::select_query::{select, SelectItem, SelectQuery};
 use std::fs::{self, File};
 use std::io::Write;
 use std::process::Command;
@@ -15,7 +19,7 @@ fn test_select_every_row() {
     let output = Command::new("cargo")
         .arg("run")
         .arg("--")
-        .arg("tests/ordenes.csv")
+        .arg("tests")
         .arg("SELECT * FROM ordenes;")
         .output();
 
@@ -57,7 +61,7 @@ fn test_select_with_where_clause() {
     let output = std::process::Command::new("cargo")
         .arg("run")
         .arg("--")
-        .arg("tests/ordenes.csv")
+        .arg("tests")
         .arg("SELECT id, producto, id_cliente FROM ordenes WHERE cantidad > 1;")
         .output();
 
@@ -103,7 +107,7 @@ fn test_select_with_where_and_order_by_clause() {
     let output = std::process::Command::new("cargo")
         .arg("run")
         .arg("--")
-        .arg("tests/clientes.csv")
+        .arg("tests")
         .arg("SELECT id, nombre, email FROM clientes WHERE apellido = 'López' ORDER BY email DESC;")
         .output();
 
@@ -150,8 +154,8 @@ fn test_select_with_two_conditions() {
     let output = std::process::Command::new("cargo")
         .arg("run")
         .arg("--")
-        .arg("tests/ordenes.csv")
-        .arg("SELECT id, id_cliente, producto, cantidad FROM ordenes WHERE cantidad > 1 AND producto = 'mouse';")
+        .arg("tests")
+        .arg("SELECT id, id_cliente, producto, cantidad FROM ordenes WHERE cantidad > 1 AND producto ILIKE 'mouse';")
         .output();
 
     let output = match output {
@@ -196,8 +200,8 @@ fn test_select_with_three_conditions() {
     let output = std::process::Command::new("cargo")
         .arg("run")
         .arg("--")
-        .arg("tests/ordenes.csv")
-        .arg("SELECT id, id_cliente, producto, cantidad FROM ordenes WHERE producto = 'mouse' or id_cliente = 6 and cantidad > 1;")
+        .arg("tests")
+        .arg("SELECT id, id_cliente, producto, cantidad FROM ordenes WHERE producto = 'Mouse' or id_cliente = 6 and cantidad > 1;")
         .output();
 
     let output = match output {
@@ -244,10 +248,14 @@ fn test_delete_function() -> Result<(), Box<dyn std::error::Error>> {
 
     let delete_query = DeleteQuery {
         table_name: "test_delete_function".to_string(),
-        condition: vec!["id".to_string(), "=".to_string(), "1".to_string()],
+        condition: Some(Expr::Compare {
+            left: Box::new(Expr::Column("id".to_string())),
+            operator: "=".to_string(),
+            right: Box::new(Expr::Literal(Value::Int(1))),
+        }),
     };
 
-    let _ = delete(test_file, delete_query);
+    assert_eq!(delete(".", delete_query).ok(), Some(1));
 
     let contents = fs::read_to_string(test_file)?;
     let expected_result = "id,name\n2,Tina\n";
@@ -268,10 +276,10 @@ fn test_insert() -> Result<(), Box<dyn std::error::Error>> {
     let insert_query = InsertQuery {
         table_name: "test_insert".to_string(),
         columns: vec!["name".to_string(), "age".to_string()],
-        values: vec!["Alice".to_string(), "30".to_string()],
+        values: vec![Some("Alice".to_string()), Some("30".to_string())],
     };
 
-    let _ = insert(test_file, insert_query);
+    assert_eq!(insert(".", insert_query).ok(), Some(1));
 
     let contents = fs::read_to_string(test_file)?;
     assert!(contents.contains(",Alice,30"));
@@ -281,29 +289,642 @@ fn test_insert() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 #[test]
-fn test_update_line() -> Result<(), Box<dyn std::error::Error>> {
-    let test_file = "test_update_line.csv";
+fn test_select_missing_table() {
+    let select_query = SelectQuery {
+        table_name: "inexistente".to_string(),
+        alias: None,
+        joins: Vec::new(),
+        distinct: false,
+        columns: vec![SelectItem::All],
+        condition: None,
+        group_by: Vec::new(),
+        having: None,
+        order_by: Vec::new(),
+        limit: None,
+        offset: 0,
+    };
+
+    let result = select("tests", select_query);
+    assert_eq!(
+        result.map_err(|error| error.kind()),
+        Err(ErrorType::InvalidTable)
+    );
+}
+
+#[test]
+fn test_quoted_fields_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_quoted_fields.csv";
 
     let mut file = File::create(test_file)?;
-    writeln!(file, "id,id_cliente,producto,cantidad")?;
-    writeln!(file, "1,1,manzana,5")?;
-    writeln!(file, "2,8,pera,3")?;
+    writeln!(file, "id,producto")?;
+    writeln!(file, "1,\"Mouse, inalámbrico\"")?;
+    writeln!(file, "2,\"Monitor\n\"\"4K\"\"\"")?;
+
+    let insert_query = InsertQuery {
+        table_name: "test_quoted_fields".to_string(),
+        columns: vec!["id".to_string(), "producto".to_string()],
+        values: vec![
+            Some("3".to_string()),
+            Some("Teclado, \"gamer\"".to_string()),
+        ],
+    };
+    assert_eq!(insert(".", insert_query).ok(), Some(1));
+
+    let delete_query = DeleteQuery {
+        table_name: "test_quoted_fields".to_string(),
+        condition: Some(Expr::Compare {
+            left: Box::new(Expr::Column("id".to_string())),
+            operator: "=".to_string(),
+            right: Box::new(Expr::Literal(Value::Int(1))),
+        }),
+    };
+    assert_eq!(delete(".", delete_query).ok(), Some(1));
+
+    let select_query = SelectQuery {
+        table_name: "test_quoted_fields".to_string(),
+        alias: None,
+        joins: Vec::new(),
+        distinct: false,
+        columns: vec![SelectItem::Column("producto".to_string())],
+        condition: None,
+        group_by: Vec::new(),
+        having: None,
+        order_by: Vec::new(),
+        limit: None,
+        offset: 0,
+    };
+    let result = select(".", select_query)?;
+    assert_eq!(
+        result.rows,
+        vec![
+            vec![Value::Str("Monitor\n\"4K\"".to_string())],
+            vec![Value::Str("Teclado, \"gamer\"".to_string())],
+        ]
+    );
+
+    let contents = fs::read_to_string(test_file)?;
+    assert_eq!(
+        contents,
+        "id,producto\n2,\"Monitor\n\"\"4K\"\"\"\n3,\"Teclado, \"\"gamer\"\"\"\n"
+    );
+
+    fs::remove_file(test_file)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_select_with_schema_keeps_text_codes() {
+    let output = Command::new("cargo")
+        .arg("run")
+        .arg("--")
+        .arg("tests")
+        .arg("SELECT codigo, nombre FROM productos WHERE codigo = '007' OR stock < 10 ORDER BY codigo")
+        .output()
+        .expect("No se pudo ejecutar el comando");
+
+    let actual_output = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(actual_output, "codigo,nombre\n007,Lapicera\n7,Regla\n");
+}
+
+#[test]
+fn test_null_values_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_null_values.csv";
+    fs::write(test_file, "id,nombre,email\n")?;
 
-    update_line(
+    parse_query(
+        ".",
+        "INSERT INTO test_null_values (id, nombre, email) VALUES (1, 'Ana', '')",
+    )?;
+    parse_query(
+        ".",
+        "INSERT INTO test_null_values (id, nombre) VALUES (2, 'Luis')",
+    )?;
+    assert_eq!(
+        fs::read_to_string(test_file)?,
+        "id,nombre,email\n1,Ana,\"\"\n2,Luis,\n"
+    );
+
+    let result = parse_query(".", "SELECT id FROM test_null_values WHERE email IS NULL")?;
+    assert_eq!(result.rows, vec![vec![Value::Int(2)]]);
+    let result = parse_query(".", "SELECT id FROM test_null_values WHERE email = NULL")?;
+    assert!(result.rows.is_empty());
+    let result = parse_query(
+        ".",
+        "SELECT id, email FROM test_null_values WHERE NOT email = 'x'",
+    )?;
+    assert_eq!(result.to_string(), "id,email\n1,\"\"\n");
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_aggregates() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_select_aggregates.csv";
+    fs::write(
         test_file,
-        2,
-        Some(&vec![
-            "2".to_string(),
-            "8".to_string(),
-            "pera".to_string(),
-            "10".to_string(),
-        ]),
+        "id,id_cliente,producto,cantidad\n1,1,Laptop,2\n2,1,Mouse,\n3,1,Monitor,5\n4,2,Teclado,7\n",
     )?;
 
-    let contents = fs::read_to_string(test_file)?;
-    assert!(contents.contains("2,8,pera,10"));
+    let result = parse_query(
+        ".",
+        "SELECT COUNT(*), COUNT(cantidad), SUM(cantidad), AVG(cantidad), MIN(producto), MAX(cantidad) \
+         FROM test_select_aggregates WHERE id_cliente = 1",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "COUNT(*),COUNT(cantidad),SUM(cantidad),AVG(cantidad),MIN(producto),MAX(cantidad)\n\
+         3,2,7,3.5,Laptop,5\n"
+    );
+
+    let result = parse_query(
+        ".",
+        "SELECT COUNT(*), SUM(cantidad) FROM test_select_aggregates WHERE id_cliente = 9",
+    )?;
+    assert_eq!(result.rows, vec![vec![Value::Int(0), Value::Null]]);
+
+    let error = parse_query(".", "SELECT SUM(producto) FROM test_select_aggregates").unwrap_err();
+    assert_eq!(error.kind(), ErrorType::Error);
 
     fs::remove_file(test_file)?;
+    Ok(())
+}
 
+#[test]
+fn test_select_group_by_and_having() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_select_group_by.csv";
+    fs::write(
+        test_file,
+        "id,id_cliente,producto,cantidad\n1,1,Laptop,2\n2,2,Mouse,1\n3,1,Monitor,5\n\
+         4,3,Teclado,7\n5,2,Mouse,4\n6,,Cable,3\n",
+    )?;
+
+    let result = parse_query(
+        ".",
+        "SELECT id_cliente, COUNT(*), SUM(cantidad) FROM test_select_group_by \
+         GROUP BY id_cliente HAVING COUNT(*) > 1 OR id_cliente IS NULL ORDER BY SUM(cantidad) DESC",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "id_cliente,COUNT(*),SUM(cantidad)\n1,2,7\n2,2,5\n,1,3\n"
+    );
+
+    let result = parse_query(
+        ".",
+        "SELECT producto FROM test_select_group_by WHERE id_cliente = 2 GROUP BY producto",
+    )?;
+    assert_eq!(result.to_string(), "producto\nMouse\n");
+
+    let error = parse_query(
+        ".",
+        "SELECT id_cliente FROM test_select_group_by GROUP BY id_cliente HAVING cantidad > 1",
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorType::InvalidColumn);
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_limit_and_offset() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_select_limit.csv";
+    let schema_file = "test_select_limit.schema";
+    fs::write(
+        test_file,
+        "id,producto,cantidad\n1,Laptop,2\n2,Mouse,1\n3,Monitor,5\n4,Teclado,7\nx,Roto,0\n",
+    )?;
+    fs::write(schema_file, "id INT\nproducto TEXT\ncantidad INT\n")?;
+
+    let result = parse_query(".", "SELECT id FROM test_select_limit LIMIT 2 OFFSET 1")?;
+    assert_eq!(result.to_string(), "id\n2\n3\n");
+
+    // Sin ORDER BY, la lectura termina antes de llegar a la fila inválida; con ORDER BY hay que leerlas todas.
+    let error = parse_query(".", "SELECT id FROM test_select_limit LIMIT 10").unwrap_err();
+    assert_eq!(error.kind(), ErrorType::Error);
+    let error = parse_query(
+        ".",
+        "SELECT id FROM test_select_limit ORDER BY cantidad LIMIT 1",
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorType::Error);
+
+    fs::write(
+        test_file,
+        "id,producto,cantidad\n1,Laptop,2\n2,Mouse,1\n3,Monitor,5\n4,Teclado,7\n",
+    )?;
+    let result = parse_query(
+        ".",
+        "SELECT producto FROM test_select_limit ORDER BY cantidad DESC LIMIT 2 OFFSET 1",
+    )?;
+    assert_eq!(result.to_string(), "producto\nMonitor\nLaptop\n");
+
+    fs::remove_file(test_file)?;
+    fs::remove_file(schema_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_distinct() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_select_distinct.csv";
+    fs::write(
+        test_file,
+        "id,id_cliente,producto\n1,1,Mouse\n2,2,Laptop\n3,1,Mouse\n4,3,Teclado\n5,2,Mouse\n6,3,\n7,4,\n",
+    )?;
+
+    let result = parse_query(
+        ".",
+        "SELECT DISTINCT producto FROM test_select_distinct ORDER BY producto LIMIT 3",
+    )?;
+    assert_eq!(result.to_string(), "producto\nLaptop\nMouse\nTeclado\n");
+
+    let result = parse_query(".", "SELECT DISTINCT producto FROM test_select_distinct")?;
+    assert_eq!(result.to_string(), "producto\nMouse\nLaptop\nTeclado\n\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT producto, COUNT(DISTINCT id_cliente), COUNT(*) FROM test_select_distinct \
+         WHERE producto IS NOT NULL GROUP BY producto ORDER BY COUNT(DISTINCT id_cliente) DESC LIMIT 1",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "producto,COUNT(DISTINCT id_cliente),COUNT(*)\nMouse,2,3\n"
+    );
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_join() -> Result<(), Box<dyn std::error::Error>> {
+    let clientes = "test_join_clientes.csv";
+    let ordenes = "test_join_ordenes.csv";
+    fs::write(clientes, "id,nombre\n1,Juan\n2,Ana\n3,Carlos\n")?;
+    fs::write(
+        ordenes,
+        "id,id_cliente,producto\n101,1,Laptop\n102,2,Mouse\n103,1,Monitor\n104,9,Teclado\n105,,Cable\n",
+    )?;
+
+    let result = parse_query(
+        ".",
+        "SELECT o.id, c.nombre, producto FROM test_join_ordenes o \
+         JOIN test_join_clientes AS c ON o.id_cliente = c.id ORDER BY o.id",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "id,nombre,producto\n101,Juan,Laptop\n102,Ana,Mouse\n103,Juan,Monitor\n"
+    );
+
+    let result = parse_query(
+        ".",
+        "SELECT o.id, c.nombre FROM test_join_ordenes o \
+         LEFT JOIN test_join_clientes c ON o.id_cliente = c.id WHERE o.id > 102",
+    )?;
+    assert_eq!(result.to_string(), "id,nombre\n103,Juan\n104,\n105,\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT c.nombre, COUNT(o.id) FROM test_join_clientes c \
+         LEFT OUTER JOIN test_join_ordenes o ON o.id_cliente = c.id GROUP BY c.nombre ORDER BY COUNT(o.id) DESC",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "nombre,COUNT(o.id)\nJuan,2\nAna,1\nCarlos,0\n"
+    );
+
+    let result = parse_query(
+        ".",
+        "SELECT nombre, COUNT(*) FROM test_join_clientes c \
+         JOIN test_join_ordenes o ON c.id = o.id_cliente GROUP BY c.nombre ORDER BY nombre",
+    )?;
+    assert_eq!(result.to_string(), "nombre,COUNT(*)\nAna,1\nJuan,2\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT c.nombre, COUNT(*) FROM test_join_clientes c \
+         JOIN test_join_ordenes o ON c.id = o.id_cliente GROUP BY nombre HAVING c.nombre = 'Juan'",
+    )?;
+    assert_eq!(result.to_string(), "nombre,COUNT(*)\nJuan,2\n");
+
+    let error = parse_query(
+        ".",
+        "SELECT id FROM test_join_ordenes o JOIN test_join_clientes c ON o.id_cliente = c.id",
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorType::InvalidColumn);
+
+    fs::remove_file(clientes)?;
+    fs::remove_file(ordenes)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_outer_cross_and_natural_joins() -> Result<(), Box<dyn std::error::Error>> {
+    let clientes = "test_outer_join_clientes.csv";
+    let pagos = "test_outer_join_pagos.csv";
+    fs::write(clientes, "id,nombre\n1,Juan\n2,Ana\n3,Carlos\n")?;
+    fs::write(pagos, "id,monto\n1,10\n3,30\n4,40\n")?;
+
+    let result = parse_query(
+        ".",
+        "SELECT * FROM test_outer_join_clientes FULL OUTER JOIN test_outer_join_pagos USING (id) ORDER BY id",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "id,nombre,monto\n1,Juan,10\n2,Ana,\n3,Carlos,30\n4,,40\n"
+    );
+
+    let result = parse_query(
+        ".",
+        "SELECT c.id, p.id, monto FROM test_outer_join_clientes c \
+         RIGHT JOIN test_outer_join_pagos p ON c.id = p.id WHERE c.id IS NULL",
+    )?;
+    assert_eq!(result.to_string(), "id,id,monto\n,4,40\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT id, nombre FROM test_outer_join_clientes NATURAL JOIN test_outer_join_pagos",
+    )?;
+    assert_eq!(result.to_string(), "id,nombre\n1,Juan\n3,Carlos\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT COUNT(*) FROM test_outer_join_clientes CROSS JOIN test_outer_join_pagos",
+    )?;
+    assert_eq!(result.to_string(), "COUNT(*)\n9\n");
+
+    fs::remove_file(clientes)?;
+    fs::remove_file(pagos)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_computed_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_select_computed.csv";
+    fs::write(
+        test_file,
+        "id,producto,cantidad,nombre,apellido\n1,Mouse,3,Ana,Paz\n2,Laptop,1,Luis,Gil\n3,Mouse,2,Eva,\n",
+    )?;
+
+    let result = parse_query(
+        ".",
+        "SELECT producto, cantidad * 2 AS doble, nombre || ' ' || apellido AS cliente, 'x' \
+         FROM test_select_computed ORDER BY doble DESC",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "producto,doble,cliente,'x'\nMouse,6,Ana Paz,x\nMouse,4,,x\nLaptop,2,Luis Gil,x\n"
+    );
+
+    let result = parse_query(
+        ".",
+        "SELECT producto, SUM(cantidad) * 2 AS total FROM test_select_computed \
+         GROUP BY producto ORDER BY total",
+    )?;
+    assert_eq!(result.to_string(), "producto,total\nLaptop,2\nMouse,10\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT producto, cantidad * 2 FROM test_select_computed ORDER BY cantidad * 2",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "producto,cantidad * 2\nLaptop,2\nMouse,4\nMouse,6\n"
+    );
+
+    let error = parse_query(
+        ".",
+        "SELECT producto FROM test_select_computed ORDER BY cantidad * 2",
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+
+    let error = parse_query(".", "SELECT cantidad / 0 FROM test_select_computed").unwrap_err();
+    assert_eq!(error.kind(), ErrorType::Error);
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_scalar_functions() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_scalar_functions.csv";
+    fs::write(
+        test_file,
+        "id,nombre,precio\n1,  ana ,10.456\n2,Luis,-3\n3,,7\n",
+    )?;
+
+    let result = parse_query(
+        ".",
+        "SELECT id, UPPER(TRIM(nombre)) AS nombre, COALESCE(nombre, 'anon') IS NULL AS vacio, \
+         ROUND(ABS(precio), 1) AS precio FROM test_scalar_functions \
+         WHERE LENGTH(TRIM(nombre)) = 3 OR NULLIF(id, 3) IS NULL ORDER BY id",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "id,nombre,vacio,precio\n1,ANA,false,10.5\n3,,false,7\n"
+    );
+
+    parse_query(
+        ".",
+        "UPDATE test_scalar_functions SET nombre = CONCAT(SUBSTR(nombre, 1, 1), '.'), \
+         precio = REPLACE(precio, '-', '') WHERE id = 2",
+    )?;
+    let result = parse_query(
+        ".",
+        "SELECT nombre, precio FROM test_scalar_functions WHERE LOWER(nombre) = 'l.'",
+    )?;
+    assert_eq!(result.to_string(), "nombre,precio\nL.,3\n");
+
+    let error = parse_query(".", "SELECT SUBSTR(nombre) FROM test_scalar_functions").unwrap_err();
+    assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_like_in_and_between_predicates() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_predicates.csv";
+    fs::write(
+        test_file,
+        "id,producto,cantidad\n1,Laptop Pro,5\n2,laptop,\n3,Mouse_2,3\n4,Monitor,8\n",
+    )?;
+
+    let result = parse_query(
+        ".",
+        "SELECT id FROM test_predicates WHERE producto LIKE 'M%' AND cantidad BETWEEN 1 AND 5",
+    )?;
+    assert_eq!(result.to_string(), "id\n3\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT id FROM test_predicates WHERE producto ILIKE 'LAP%' OR producto LIKE '%!_%' ESCAPE '!'",
+    )?;
+    assert_eq!(result.to_string(), "id\n1\n2\n3\n");
+
+    let result = parse_query(
+        ".",
+        "SELECT id FROM test_predicates WHERE cantidad NOT IN (3, 8) ORDER BY id DESC",
+    )?;
+    assert_eq!(result.to_string(), "id\n1\n");
+
+    parse_query(
+        ".",
+        "UPDATE test_predicates SET cantidad = 0 WHERE id IN (2, 4)",
+    )?;
+    parse_query(
+        ".",
+        "DELETE FROM test_predicates WHERE cantidad NOT BETWEEN 1 AND 4",
+    )?;
+    assert_eq!(
+        fs::read_to_string(test_file)?,
+        "id,producto,cantidad\n3,Mouse_2,3\n"
+    );
+
+    let error = parse_query(".", "SELECT id FROM test_predicates WHERE id IN 1, 2").unwrap_err();
+    assert_eq!(error.kind(), ErrorType::InvalidSyntax);
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_select_extract_in_select_list() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_select_extract.csv";
+    fs::write(test_file, "id,fecha\n1,2024-05-17\n2,2023-01-02\n")?;
+
+    let result = parse_query(
+        ".",
+        "SELECT id, EXTRACT(YEAR FROM fecha) AS anio, EXTRACT(MONTH FROM fecha) \
+         FROM test_select_extract ORDER BY anio",
+    )?;
+    assert_eq!(
+        result.to_string(),
+        "id,anio,EXTRACT(MONTH FROM fecha)\n2,2023,1\n1,2024,5\n"
+    );
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_rows_with_wrong_field_count() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_wrong_field_count.csv";
+    let contents = "a,b,c\n1,2\n\n3,4,5\n";
+    fs::write(test_file, contents)?;
+
+    let queries = [
+        "SELECT * FROM test_wrong_field_count",
+        "SELECT a FROM test_wrong_field_count WHERE c = 5",
+        "UPDATE test_wrong_field_count SET b = 0",
+        "DELETE FROM test_wrong_field_count WHERE a = 3",
+    ];
+    for query in queries {
+        let error = parse_query(".", query).unwrap_err();
+        assert_eq!(error.kind(), ErrorType::InvalidTable, "{}", query);
+        assert!(
+            error.to_string().contains("el registro 2 tiene 2 campos"),
+            "{}",
+            error
+        );
+    }
+    assert_eq!(fs::read_to_string(test_file)?, contents);
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_interval_overflow_is_an_error() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_interval_overflow.csv";
+    fs::write(test_file, "id,fecha\n1,2024-01-01\n")?;
+
+    let cases = [
+        (
+            "SELECT id FROM test_interval_overflow \
+             WHERE fecha < fecha + INTERVAL '999999999999999999 years'",
+            ErrorType::InvalidSyntax,
+        ),
+        (
+            "SELECT DATE '2024-01-01' + INTERVAL '999999999999 days' FROM test_interval_overflow",
+            ErrorType::Error,
+        ),
+        (
+            "SELECT TIMESTAMP '2024-01-01 00:00' + INTERVAL '9999999999999 hours' \
+             FROM test_interval_overflow",
+            ErrorType::InvalidSyntax,
+        ),
+        (
+            "SELECT fecha - INTERVAL '2562047788 hours' FROM test_interval_overflow",
+            ErrorType::Error,
+        ),
+    ];
+    for (query, kind) in cases {
+        let result = parse_query(".", query).map(|_| ());
+        assert_eq!(result.map_err(|error| error.kind()), Err(kind), "{}", query);
+    }
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_text_column_compares_as_text() -> Result<(), Box<dyn std::error::Error>> {
+    for query in [
+        "SELECT codigo FROM productos WHERE codigo = 7",
+        "SELECT codigo FROM productos WHERE codigo = '7'",
+        "SELECT codigo FROM productos WHERE codigo IN (7, 8)",
+    ] {
+        assert_eq!(
+            parse_query("tests", query)?.to_string(),
+            "codigo\n7\n",
+            "{}",
+            query
+        );
+    }
+    assert_eq!(
+        parse_query("tests", "SELECT codigo FROM productos WHERE codigo = '007'")?.to_string(),
+        "codigo\n007\n"
+    );
+    assert_eq!(
+        parse_query("tests", "SELECT nombre FROM productos WHERE stock = '8'")?.to_string(),
+        "nombre\nRegla\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_trailing_blank_lines_are_ignored() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_trailing_blank_lines.csv";
+    fs::write(test_file, "id,name\n1,a\n\n")?;
+
+    let result = parse_query(".", "SELECT * FROM test_trailing_blank_lines")?;
+    assert_eq!(result.to_string(), "id,name\n1,a\n");
+    parse_query(".", "UPDATE test_trailing_blank_lines SET name = 'b'")?;
+    assert_eq!(fs::read_to_string(test_file)?, "id,name\n1,b\n");
+
+    fs::remove_file(test_file)?;
+    Ok(())
+}
+
+#[test]
+fn test_insert_into_table_without_final_newline() -> Result<(), Box<dyn std::error::Error>> {
+    let test_file = "test_insert_final_newline.csv";
+    fs::write(test_file, "id,name\n1,a")?;
+
+    parse_query(
+        ".",
+        "INSERT INTO test_insert_final_newline (id, name) VALUES (2, 'b')",
+    )?;
+    assert_eq!(fs::read_to_string(test_file)?, "id,name\n1,a\n2,b\n");
+    let result = parse_query(
+        ".",
+        "SELECT name FROM test_insert_final_newline WHERE id = 2",
+    )?;
+    assert_eq!(result.to_string(), "name\nb\n");
+
+    fs::remove_file(test_file)?;
     Ok(())
 }